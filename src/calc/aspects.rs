@@ -0,0 +1,277 @@
+use serde::{Serialize, Deserialize};
+use utoipa::ToSchema;
+use super::{
+  math_funcs::signed_angle_180,
+  models::{graha_pos::GrahaPos, houses::AscMc},
+  traits::*,
+};
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
+pub enum AspectType {
+  Conjunction,
+  Opposition,
+  Trine,
+  Square,
+  Sextile,
+  Quincunx,
+  SemiSextile,
+  SemiSquare,
+  Sesquiquadrate,
+  Quintile,
+  BiQuintile,
+}
+
+impl AspectType {
+  pub fn angle(&self) -> f64 {
+    match self {
+      AspectType::Conjunction => 0f64,
+      AspectType::Opposition => 180f64,
+      AspectType::Trine => 120f64,
+      AspectType::Square => 90f64,
+      AspectType::Sextile => 60f64,
+      AspectType::Quincunx => 150f64,
+      AspectType::SemiSextile => 30f64,
+      AspectType::SemiSquare => 45f64,
+      AspectType::Sesquiquadrate => 135f64,
+      AspectType::Quintile => 72f64,
+      AspectType::BiQuintile => 144f64,
+    }
+  }
+
+  /*
+  * Conventional default orbs, wider for major aspects
+  */
+  pub fn default_orb(&self) -> f64 {
+    match self {
+      AspectType::Conjunction | AspectType::Opposition => 8f64,
+      AspectType::Trine | AspectType::Square => 7f64,
+      AspectType::Sextile => 5f64,
+      AspectType::Quincunx => 3f64,
+      _ => 2f64,
+    }
+  }
+
+  pub fn as_string(&self) -> String {
+    match self {
+      AspectType::Conjunction => "conjunction",
+      AspectType::Opposition => "opposition",
+      AspectType::Trine => "trine",
+      AspectType::Square => "square",
+      AspectType::Sextile => "sextile",
+      AspectType::Quincunx => "quincunx",
+      AspectType::SemiSextile => "semi-sextile",
+      AspectType::SemiSquare => "semi-square",
+      AspectType::Sesquiquadrate => "sesquiquadrate",
+      AspectType::Quintile => "quintile",
+      AspectType::BiQuintile => "bi-quintile",
+    }.to_string()
+  }
+
+  pub fn major() -> Vec<AspectType> {
+    vec![
+      AspectType::Conjunction,
+      AspectType::Opposition,
+      AspectType::Trine,
+      AspectType::Square,
+      AspectType::Sextile,
+    ]
+  }
+
+  pub fn minor() -> Vec<AspectType> {
+    vec![
+      AspectType::Quincunx,
+      AspectType::SemiSextile,
+      AspectType::SemiSquare,
+      AspectType::Sesquiquadrate,
+      AspectType::Quintile,
+      AspectType::BiQuintile,
+    ]
+  }
+
+  pub fn all() -> Vec<AspectType> {
+    let mut items = AspectType::major();
+    items.append(&mut AspectType::minor());
+    items
+  }
+}
+
+impl FromKey<Option<AspectType>> for AspectType {
+  fn from_key(key: &str) -> Option<AspectType> {
+    let simple_key = key.to_lowercase().replace("-", "").replace("_", "");
+    match simple_key.as_str() {
+      "cj" | "conj" | "conjunction" => Some(AspectType::Conjunction),
+      "op" | "opp" | "opposition" => Some(AspectType::Opposition),
+      "tr" | "trine" => Some(AspectType::Trine),
+      "sq" | "square" => Some(AspectType::Square),
+      "sx" | "sextile" => Some(AspectType::Sextile),
+      "qc" | "quincunx" | "inconjunct" => Some(AspectType::Quincunx),
+      "ss" | "semisextile" => Some(AspectType::SemiSextile),
+      "sm" | "semisquare" | "octile" => Some(AspectType::SemiSquare),
+      "sqq" | "sesquiquadrate" | "sesquisquare" => Some(AspectType::Sesquiquadrate),
+      "qn" | "quintile" => Some(AspectType::Quintile),
+      "bq" | "biquintile" => Some(AspectType::BiQuintile),
+      _ => None,
+    }
+  }
+}
+
+impl ToKey<AspectType> for AspectType {
+  fn to_key(&self) -> &str {
+    match self {
+      AspectType::Conjunction => "cj",
+      AspectType::Opposition => "op",
+      AspectType::Trine => "tr",
+      AspectType::Square => "sq",
+      AspectType::Sextile => "sx",
+      AspectType::Quincunx => "qc",
+      AspectType::SemiSextile => "ss",
+      AspectType::SemiSquare => "sm",
+      AspectType::Sesquiquadrate => "sqq",
+      AspectType::Quintile => "qn",
+      AspectType::BiQuintile => "bq",
+    }
+  }
+}
+
+/*
+* Match a comma-separated list of aspect keys or the group keys major, minor and all
+*/
+pub fn match_aspect_types(ref_str: &str) -> Vec<AspectType> {
+  match ref_str.to_lowercase().as_str() {
+    "" | "1" | "major" => AspectType::major(),
+    "minor" => AspectType::minor(),
+    "all" => AspectType::all(),
+    _ => ref_str.split(",").filter_map(|k| AspectType::from_key(k.trim())).collect(),
+  }
+}
+
+/*
+* Simple longitude and longitude speed pair used as the source for aspect calculations
+*/
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AspectPoint {
+  pub key: String,
+  pub lng: f64,
+  pub speed: f64,
+}
+
+impl AspectPoint {
+  pub fn new(key: &str, lng: f64, speed: f64) -> Self {
+    AspectPoint { key: key.to_string(), lng, speed }
+  }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+pub struct AspectResult {
+  pub k1: String,
+  pub k2: String,
+  pub aspect: String,
+  pub key: String,
+  pub target: f64,
+  pub angle: f64,
+  pub orb: f64,
+  pub applying: bool,
+}
+
+/**
+ * Build aspect source points from body positions and optionally the ascendant and MC.
+ * The ascendant and MC are treated as stationary when calculating applying / separating states.
+ */
+pub fn to_aspect_points(bodies: &Vec<GrahaPos>, points: Option<AscMc>) -> Vec<AspectPoint> {
+  let mut items: Vec<AspectPoint> = bodies.iter().map(|b| AspectPoint::new(b.key.as_str(), b.lng, b.lng_speed)).collect();
+  if let Some(asc_mc) = points {
+    items.push(AspectPoint::new("as", asc_mc.ascendant, 0f64));
+    items.push(AspectPoint::new("mc", asc_mc.mc, 0f64));
+  }
+  items
+}
+
+/**
+ * Match the closest aspect within orb for a pair of points.
+ * Applying if the deviation from the exact aspect angle is decreasing based on the relative longitude speed.
+ */
+pub fn match_aspect(p1: &AspectPoint, p2: &AspectPoint, aspects: &Vec<AspectType>, orb: Option<f64>) -> Option<AspectResult> {
+  let diff = signed_angle_180(p2.lng - p1.lng);
+  let angle = diff.abs();
+  let mut matched: Option<(AspectType, f64)> = None;
+  for asp in aspects {
+    let max_orb = orb.unwrap_or(asp.default_orb());
+    let deviation = angle - asp.angle();
+    if deviation.abs() <= max_orb {
+      let is_closer = match matched {
+        Some((_, prev_dev)) => deviation.abs() < prev_dev.abs(),
+        None => true,
+      };
+      if is_closer {
+        matched = Some((*asp, deviation));
+      }
+    }
+  }
+  if let Some((asp, deviation)) = matched {
+    let rel_speed = p2.speed - p1.speed;
+    let angle_speed = if diff < 0f64 { 0f64 - rel_speed } else { rel_speed };
+    let applying = deviation * angle_speed < 0f64;
+    Some(AspectResult {
+      k1: p1.key.clone(),
+      k2: p2.key.clone(),
+      aspect: asp.as_string(),
+      key: asp.to_key().to_string(),
+      target: asp.angle(),
+      angle,
+      orb: deviation,
+      applying,
+    })
+  } else {
+    None
+  }
+}
+
+pub fn calc_aspects_from_points(items: &Vec<AspectPoint>, aspects: &Vec<AspectType>, orb: Option<f64>) -> Vec<AspectResult> {
+  let mut results: Vec<AspectResult> = Vec::new();
+  let num_items = items.len();
+  for i in 0..num_items {
+    for j in (i + 1)..num_items {
+      if let Some(result) = match_aspect(&items[i], &items[j], aspects, orb) {
+        results.push(result);
+      }
+    }
+  }
+  results
+}
+
+pub fn calc_aspects(bodies: &Vec<GrahaPos>, points: Option<AscMc>, aspects: &Vec<AspectType>, orb: Option<f64>) -> Vec<AspectResult> {
+  calc_aspects_from_points(&to_aspect_points(bodies, points), aspects, orb)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn matches_applying_trine() {
+    // the Moon at 118º is moving towards an exact trine with the Sun at 0º
+    let su = AspectPoint::new("su", 0f64, 1f64);
+    let mo = AspectPoint::new("mo", 118f64, 13f64);
+    let result = match_aspect(&su, &mo, &AspectType::major(), None).unwrap();
+    assert_eq!(result.key, "tr");
+    assert!(result.applying);
+  }
+
+  #[test]
+  fn matches_separating_conjunction_across_zero() {
+    let ma = AspectPoint::new("ma", 358f64, 0.5f64);
+    let ve = AspectPoint::new("ve", 2f64, 1.2f64);
+    let result = match_aspect(&ma, &ve, &AspectType::major(), None).unwrap();
+    assert_eq!(result.key, "cj");
+    assert_eq!(result.angle, 4f64);
+    assert!(!result.applying);
+  }
+
+  #[test]
+  fn respects_custom_orb() {
+    let su = AspectPoint::new("su", 10f64, 1f64);
+    let ju = AspectPoint::new("ju", 104f64, 0.1f64);
+    assert!(match_aspect(&su, &ju, &AspectType::major(), Some(3f64)).is_none());
+    assert!(match_aspect(&su, &ju, &AspectType::major(), Some(5f64)).is_some());
+  }
+}
@@ -1,37 +1,34 @@
-use crate::extensions::swe::{azalt, get_ayanamsha, set_topo};
+use crate::extensions::swe::{azalt, calc_ut_raw, get_ayanamsha, set_topo, SweCalcResult};
 use crate::calc::{
   rise_set_phases::CentricMode,
   math_funcs::subtract_360,
   math_funcs::{adjust_lng_by_body_key, calc_opposite,normalize_360, normalize_f64},
-  settings::ayanamshas::*,
+  settings::{ayanamshas::*, body_values::{asteroid_num, body_key_to_num}},
   traits::*,
   rise_set_phases::get_pheno_result,
   models::{general::*, geo_pos::*, graha_pos::*, houses::calc_ascendant}
 };
-use libswe_sys::sweconst::{Bodies, OptionalFlag};
-use libswe_sys::swerust::handler_swe03::*;
+use libswe_sys::sweconst::OptionalFlag;
 use math::round::floor;
 use std::collections::HashMap;
 
-pub fn calc_body_jd(jd: f64, key: &str, sidereal: bool, topo: bool, aya_offset: f64) -> GrahaPos {
-  let combo: i32;
+/*
+* Calculate by body number, so numbered asteroids (ast:NNN) are supported and distances in AU are kept
+*/
+pub fn calc_ut_by_key(jd: f64, key: &str, iflag: i32) -> SweCalcResult {
+  match body_key_to_num(key) {
+    Ok(ipl) => calc_ut_raw(jd, ipl, iflag),
+    // flagged like a Swiss Ephemeris error (ERR = -1)
+    Err(_) => SweCalcResult::new([0f64; 6], -1),
+  }
+}
+
+pub fn calc_body_jd(jd: f64, key: &str, sidereal: bool, mode: CentricMode, aya_offset: f64) -> GrahaPos {
   let speed_flag = OptionalFlag::Speed as i32;
   let swe_flag = OptionalFlag::SwissEph as i32;
-  if topo {
-    let topo_flag = OptionalFlag::TopocentricPosition as i32;
-    if sidereal {
-      combo = swe_flag | speed_flag | OptionalFlag::SideralPosition as i32 | topo_flag;
-    } else {
-      combo = swe_flag | speed_flag | topo_flag;
-    }
-  } else {
-    if sidereal {
-      combo = swe_flag | speed_flag | OptionalFlag::SideralPosition as i32;
-    } else {
-      combo = swe_flag | speed_flag;
-    }
-  }
-  let result = calc_ut(jd, Bodies::from_key(key), combo);
+  let sid_flag = if sidereal { OptionalFlag::SideralPosition as i32 } else { 0 };
+  let combo = swe_flag | speed_flag | sid_flag | mode.to_flag();
+  let result = calc_ut_by_key(jd, key, combo);
   // only apply for ecliptic lng if the sidereal mode is not applied via SE in conjunction with set_sid_mode
   let aya_offset_val = if sidereal { 0f64 } else { aya_offset };
   let lng = subtract_360(
@@ -44,25 +41,20 @@ pub fn calc_body_jd(jd: f64, key: &str, sidereal: bool, topo: bool, aya_offset:
     result.latitude,
     result.speed_longitude,
     result.speed_latitude,
-  )
+  ).with_distance(result.distance, result.speed_distance)
 }
 
 /**
  * Only implement tropical variants for equatorial positions
  * Ayanamsha value may be subtracted if required
  */
-pub fn calc_body_eq_jd_swe(jd: f64, key: &str, topo: bool) -> GrahaPos {
-  let combo: i32;
+pub fn calc_body_eq_jd_swe(jd: f64, key: &str, mode: CentricMode) -> GrahaPos {
   //let eq_flag = OptionalFlag::SEFLG_EQUATORIAL;
   let eq_flag = OptionalFlag::EquatorialPosition as i32;
   let swe_flag = OptionalFlag::SwissEph as i32;
   let speed_flag = OptionalFlag::Speed as i32;
-  if topo {
-    combo = swe_flag | speed_flag | OptionalFlag::TopocentricPosition as i32 | eq_flag;
-  } else {
-    combo = swe_flag | speed_flag | eq_flag;
-  }
-  let result = calc_ut(jd, Bodies::from_key(key), combo);
+  let combo = swe_flag | speed_flag | mode.to_flag() | eq_flag;
+  let result = calc_ut_by_key(jd, key, combo);
   let lng = adjust_lng_by_body_key(key, result.longitude);
   GrahaPos::new_eq(
     key,
@@ -70,50 +62,44 @@ pub fn calc_body_eq_jd_swe(jd: f64, key: &str, topo: bool) -> GrahaPos {
     result.latitude,
     result.speed_longitude,
     result.speed_latitude,
-  )
+  ).with_distance(result.distance, result.speed_distance)
 }
 
 /**
- * For Ketu fetch reversed Rahu ecliptic position and then calculate the right ascension and declination via
+ * For Ketu and the south node fetch reversed Rahu ecliptic position and then calculate the right ascension and declination via
  * ecliptic_to_equatorial_basic
  */
-pub fn calc_body_eq_jd(jd: f64, key: &str, topo: bool) -> GrahaPos {
+pub fn calc_body_eq_jd(jd: f64, key: &str, mode: CentricMode) -> GrahaPos {
   match key {
-    "ke" => {
-      let pos = calc_body_jd(jd, key, false, topo, 0f64);
+    "ke" | "sn" => {
+      let pos = calc_body_jd(jd, key, false, mode, 0f64);
       let eq_pos = ecliptic_to_equatorial_basic(jd, pos.lng, pos.lat);
-      GrahaPos::new_eq(key, eq_pos.lng, eq_pos.lat, pos.lng_speed, pos.lat_speed)
+      let mut eq = GrahaPos::new_eq(key, eq_pos.lng, eq_pos.lat, pos.lng_speed, pos.lat_speed);
+      eq.distance = pos.distance;
+      eq.distance_speed = pos.distance_speed;
+      eq
     }
-    _ => calc_body_eq_jd_swe(jd, key, topo),
+    _ => calc_body_eq_jd_swe(jd, key, mode),
   }
 }
 
 pub fn calc_body_dual_jd(
   jd: f64,
   key: &str,
-  topo: bool,
+  mode: CentricMode,
   show_pheno: bool,
   geo_opt: Option<GeoPos>,
   aya_offset: f64,
 ) -> GrahaPos {
-  let combo: i32;
   //let eq_flag = OptionalFlag::SEFLG_EQUATORIAL;
   let eq_flag = OptionalFlag::EquatorialPosition as i32;
   let swe_flag = OptionalFlag::SwissEph as i32;
   let speed_flag = OptionalFlag::Speed as i32;
-  if topo {
-    combo = swe_flag | speed_flag | OptionalFlag::TopocentricPosition as i32 | eq_flag;
-  } else {
-    combo = swe_flag | speed_flag | eq_flag;
-  }
-  let combo_geo = if topo {
-    swe_flag | speed_flag | OptionalFlag::TopocentricPosition as i32
-  } else {
-    swe_flag | speed_flag
-  };
-  let result = calc_ut(jd, Bodies::from_key(key), combo);
-  let result_ec = calc_ut(jd, Bodies::from_key(key), combo_geo);
-  let pheno = if show_pheno {
+  let combo = swe_flag | speed_flag | mode.to_flag() | eq_flag;
+  let combo_geo = swe_flag | speed_flag | mode.to_flag();
+  let result = calc_ut_by_key(jd, key, combo);
+  let result_ec = calc_ut_by_key(jd, key, combo_geo);
+  let pheno = if show_pheno && asteroid_num(key).is_none() {
     Some(get_pheno_result(jd, key, 0i32))
   } else {
     None
@@ -153,7 +139,26 @@ pub fn calc_body_dual_jd(
     pheno,
     altitude,
     azimuth,
-  )
+  ).with_distance(result_ec.distance, result_ec.speed_distance)
+}
+
+/*
+* Rectangular ecliptic or equatorial coordinates via SEFLG_XYZ (4096). These are always tropical.
+* Ketu and the south node are the antipode of Rahu
+*/
+pub fn calc_body_xyz_jd(jd: f64, key: &str, mode: CentricMode, equatorial: bool) -> XyzPos {
+  let xyz_flag = OptionalFlag::XYZCartesianNotPolarCoordinate as i32;
+  let eq_flag = if equatorial { OptionalFlag::EquatorialPosition as i32 } else { 0 };
+  let swe_flag = OptionalFlag::SwissEph as i32;
+  let speed_flag = OptionalFlag::Speed as i32;
+  let combo = swe_flag | speed_flag | mode.to_flag() | eq_flag | xyz_flag;
+  let result = calc_ut_by_key(jd, key, combo);
+  let xx = [result.longitude, result.latitude, result.distance, result.speed_longitude, result.speed_latitude, result.speed_distance];
+  let xx = match key {
+    "ke" | "sn" => xx.map(|v| -v),
+    _ => xx,
+  };
+  XyzPos::new(key, xx)
 }
 
 pub fn calc_body_hor_jd(
@@ -183,8 +188,8 @@ pub fn calc_body_hor_jd(
   } else {
     swe_flag | speed_flag
   };
-  let result = calc_ut(jd, Bodies::from_key(key), combo);
-  let result_ec = calc_ut(jd, Bodies::from_key(key), combo_geo);
+  let result = calc_ut_by_key(jd, key, combo);
+  let result_ec = calc_ut_by_key(jd, key, combo_geo);
   
   // let ra = adjust_lng_by_body_key(key, result.longitude);
   let (ra, dec) = adjust_ra_dec_by_body_key(
@@ -236,11 +241,11 @@ pub fn calc_body_hor_jd(
     None,
     Some(azimuth),
     Some(altitude),
-  )
+  ).with_distance(result_ec.distance, result_ec.speed_distance)
 }
 
 pub fn calc_body_dual_jd_geo(jd: f64, key: &str, show_pheno: bool, geo_opt: Option<GeoPos>, aya_offset: f64) -> GrahaPos {
-  calc_body_dual_jd(jd, key, false, show_pheno, geo_opt, aya_offset)
+  calc_body_dual_jd(jd, key, CentricMode::Geo, show_pheno, geo_opt, aya_offset)
 }
 
 pub fn calc_body_dual_jd_topo(
@@ -251,23 +256,24 @@ pub fn calc_body_dual_jd_topo(
   aya_offset: f64,
 ) -> GrahaPos {
   set_topo(geo.lat, geo.lng, geo.alt);
-  calc_body_dual_jd(jd, key, true, show_pheno, Some(geo), aya_offset)
+  calc_body_dual_jd(jd, key, CentricMode::Topo, show_pheno, Some(geo), aya_offset)
 }
 
 pub fn calc_body_eq_jd_topo(jd: f64, key: &str, geo: GeoPos) -> GrahaPos {
   set_topo(geo.lat, geo.lng, geo.alt);
-  calc_body_eq_jd(jd, key, true)
+  calc_body_eq_jd(jd, key, CentricMode::Topo)
 }
 
 /*
  Get tropical geocentric coordinates
 */
 pub fn calc_body_jd_geo(jd: f64, key: &str, aya_offset: f64) -> GrahaPos {
-  calc_body_jd(jd, key, false, false, aya_offset)
+  calc_body_jd(jd, key, false, CentricMode::Geo, aya_offset)
 }
 
 /*
- Get set of tropical geocentric coordinates for groups of celestial bodies
+ Get set of tropical geocentric, topocentric (with geo-coordinates), heliocentric or barycentric coordinates for groups of celestial bodies.
+ cs 5 and 6 yield ecliptic or equatorial XYZ coordinates
 */
 pub fn calc_bodies_positions_jd(
   jd_start: f64,
@@ -275,6 +281,7 @@ pub fn calc_bodies_positions_jd(
   days: u16,
   num_per_day: f64,
   geo: Option<GeoPos>,
+  centric: CentricMode,
   cs: u8,
   iso_mode: bool,
   aya_offset: f64,
@@ -287,20 +294,33 @@ pub fn calc_bodies_positions_jd(
     None => false,
     _ => true,
   };
+  let rect_mode = CoordinateSystem::from_u8(cs).filter(|c| c.is_rectangular());
   for i in 0..max {
     let curr_jd = jd_start + (i as f64 * increment);
+    if let Some(rect_cs) = rect_mode {
+      let bodies = get_bodies_xyz(curr_jd, keys, centric, geo, rect_cs == CoordinateSystem::RectangularEq);
+      items.push(GrahaPosSet::new_rectangular(curr_jd, bodies, iso_mode));
+      continue;
+    }
     let mut bodies: Vec<GrahaPos> = Vec::new();
     for key in keys {
-      let graha_pos = match cs {
-        1 => match topo {
-          true => calc_body_eq_jd_topo(curr_jd, key, geo.unwrap()),
-          _ => calc_body_eq_jd(curr_jd, key, false),
-        },
-        3 => calc_body_hor_jd(curr_jd, key, topo, geo),
-        _ => match topo {
-          true => calc_body_jd_topo(curr_jd, key, geo.unwrap(), aya_offset),
-          _ => calc_body_jd_geo(curr_jd, key, aya_offset),
-        },
+      let graha_pos = if centric.is_solar_system() {
+        match cs {
+          1 => calc_body_eq_jd(curr_jd, key, centric),
+          _ => calc_body_jd(curr_jd, key, false, centric, aya_offset),
+        }
+      } else {
+        match cs {
+          1 => match topo {
+            true => calc_body_eq_jd_topo(curr_jd, key, geo.unwrap()),
+            _ => calc_body_eq_jd(curr_jd, key, CentricMode::Geo),
+          },
+          3 => calc_body_hor_jd(curr_jd, key, topo, geo),
+          _ => match topo {
+            true => calc_body_jd_topo(curr_jd, key, geo.unwrap(), aya_offset),
+            _ => calc_body_jd_geo(curr_jd, key, aya_offset),
+          },
+        }
       };
       bodies.push(graha_pos);
     }
@@ -319,7 +339,7 @@ pub fn calc_bodies_positions_jd(
 */
 /* pub fn calc_body_jd_geo_sidereal(jd: f64, key: &str, aya_key: &str) -> GrahaPos {
   set_sid_mode(Ayanamsha::from_key(aya_key).as_i32());
-  calc_body_jd(jd, key, true, false, 0f64)
+  calc_body_jd(jd, key, true, CentricMode::Geo, 0f64)
 } */
 
 /*
@@ -327,7 +347,7 @@ pub fn calc_bodies_positions_jd(
 */
 pub fn calc_body_jd_topo(jd: f64, key: &str, geo: GeoPos, aya_offset: f64) -> GrahaPos {
   set_topo(geo.lat, geo.lng, geo.alt);
-  calc_body_jd(jd, key, false, true, aya_offset)
+  calc_body_jd(jd, key, false, CentricMode::Topo, aya_offset)
 }
 
 /*
@@ -336,7 +356,7 @@ pub fn calc_body_jd_topo(jd: f64, key: &str, geo: GeoPos, aya_offset: f64) -> Gr
 /* pub fn calc_body_jd_topo_sidereal(jd: f64, key: &str, geo: GeoPos, aya_key: &str) -> GrahaPos {
   set_topo(geo.lat, geo.lng, geo.alt);
   set_sid_mode(Ayanamsha::from_key(aya_key).as_i32());
-  calc_body_jd(jd, key, false, true, 0f64)
+  calc_body_jd(jd, key, false, CentricMode::Topo, 0f64)
 }
  */
 /*
@@ -372,7 +392,7 @@ pub fn get_bodies_eq_geo(jd: f64, keys: &Vec<&str>) -> Vec<GrahaPos> {
   let mut bodies: Vec<GrahaPos> = Vec::new();
   for key in keys {
     let key = key.to_owned();
-    let result = calc_body_eq_jd(jd, key, false);
+    let result = calc_body_eq_jd(jd, key, CentricMode::Geo);
     bodies.push(result);
   }
   bodies
@@ -403,6 +423,60 @@ pub fn get_bodies_ecl_topo(
   bodies
 }
 
+/*
+* Heliocentric or barycentric positions in ecliptic, equatorial or both coordinate systems.
+* Horizontal coordinates and planetary phenomena require an observer on the earth
+*/
+pub fn get_bodies_centric(
+  jd: f64,
+  keys: &Vec<&str>,
+  mode: CentricMode,
+  cs: CoordinateSystem,
+  aya_offset: f64,
+) -> Vec<GrahaPos> {
+  let mut bodies: Vec<GrahaPos> = Vec::new();
+  for key in keys {
+    let result = match cs {
+      CoordinateSystem::Ecliptic => calc_body_jd(jd, key, false, mode, aya_offset),
+      CoordinateSystem::Equatorial => calc_body_eq_jd(jd, key, mode),
+      _ => calc_body_dual_jd(jd, key, mode, false, None, aya_offset),
+    };
+    bodies.push(result);
+  }
+  bodies
+}
+
+/*
+* Rectangular coordinates of all bodies relative to any centric mode. The observer location is only used if topocentric
+*/
+pub fn get_bodies_xyz(jd: f64, keys: &Vec<&str>, mode: CentricMode, geo_opt: Option<GeoPos>, equatorial: bool) -> Vec<XyzPos> {
+  if let (CentricMode::Topo, Some(geo)) = (mode, geo_opt) {
+    set_topo(geo.lat, geo.lng, geo.alt);
+  }
+  keys.iter().map(|key| calc_body_xyz_jd(jd, key, mode, equatorial)).collect()
+}
+
+/*
+* The lunar nodes and the ascendant are only defined relative to the earth. With heliocentric positions
+* the earth replaces the sun, while barycentric positions include both
+*/
+pub fn filter_centric_keys(keys: &Vec<String>, mode: CentricMode) -> Vec<String> {
+  if !mode.is_solar_system() {
+    return keys.clone();
+  }
+  let geo_only_keys = ["ra", "ke", "mn", "sn", "as"];
+  let mut matched: Vec<String> = keys
+    .iter()
+    .filter(|k| !geo_only_keys.contains(&k.as_str()))
+    .filter(|k| !(mode == CentricMode::Helio && k.as_str() == "su"))
+    .cloned()
+    .collect();
+  if keys.contains(&"su".to_string()) && !matched.contains(&"ea".to_string()) {
+    matched.push("ea".to_string());
+  }
+  matched
+}
+
 pub fn get_body_longitudes(
   jd: f64,
   geo: GeoPos,
@@ -415,18 +489,23 @@ pub fn get_body_longitudes(
   let bodies = match equatorial {
     true => match mode {
       CentricMode::Topo => get_bodies_eq_topo(jd, keys, geo),
+      CentricMode::Helio | CentricMode::Bary => get_bodies_centric(jd, keys, mode, CoordinateSystem::Equatorial, aya_offset),
       _ => get_bodies_eq_geo(jd, keys),
     },
     _ => match mode {
       CentricMode::Topo => get_bodies_ecl_topo(jd, keys, geo, aya_offset),
+      CentricMode::Helio | CentricMode::Bary => get_bodies_centric(jd, keys, mode, CoordinateSystem::Ecliptic, aya_offset),
       _ => get_bodies_ecl_geo(jd, keys, aya_offset),
     },
   };
-  let aya_offset_val = if equatorial { 0f64 } else { aya_offset };
-  items.insert(
-    "as".to_string(),
-    subtract_360(calc_ascendant(jd, geo), aya_offset_val),
-  );
+  // the ascendant only exists for an observer on the earth
+  if !mode.is_solar_system() {
+    let aya_offset_val = if equatorial { 0f64 } else { aya_offset };
+    items.insert(
+      "as".to_string(),
+      subtract_360(calc_ascendant(jd, geo), aya_offset_val),
+    );
+  }
   for body in bodies {
     let lng = if equatorial {
       body.rect_ascension
@@ -613,7 +692,7 @@ pub fn adjust_ra_dec_by_body_key(
   lat: f64,
 ) -> (f64, f64) {
   match key {
-    "ke" => {
+    "ke" | "sn" => {
       if let (Some(ra), Some(dec)) = ecliptic_to_equatorial_tuple(jd, calc_opposite(lng), lat) {
         (ra, dec)
       } else {
@@ -650,7 +729,7 @@ pub fn extract_sun_rise_set_jd(transitions: &Vec<KeyNumValueSet>, is_set: bool)
 pub fn calc_sun_at_sun_rise_set(items: &Vec<KeyNumValueSet>, is_set: bool, aya_val: f64) -> Option<BodyPos> {
   let ref_val = extract_sun_rise_set_jd(items, is_set);
   if let Some(tr_jd )= ref_val {
-    Some(calc_body_jd(tr_jd, "su", true, false, aya_val).to_body(CoordinateSystem::Ecliptic))
+    Some(calc_body_jd(tr_jd, "su", true, CentricMode::Geo, aya_val).to_body(CoordinateSystem::Ecliptic))
   } else {
     None
   }
@@ -693,4 +772,76 @@ pub fn calc_sun_period(rise_set_items: &Vec<KeyNumValueSet>, jd: f64) -> SunPeri
     end = end_item.value;
   }
   SunPeriod::new(jd,start,end,night)
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::ephemeris::with_ephemeris;
+
+  fn to_keys(keys: &[&str]) -> Vec<String> {
+    keys.iter().map(|k| k.to_string()).collect()
+  }
+
+  #[test]
+  fn test_filter_centric_keys() {
+    let keys = to_keys(&["su", "mo", "ma", "ra", "ke", "as"]);
+    assert_eq!(filter_centric_keys(&keys, CentricMode::Geo), keys);
+    assert_eq!(filter_centric_keys(&keys, CentricMode::Helio), to_keys(&["mo", "ma", "ea"]));
+    assert_eq!(filter_centric_keys(&keys, CentricMode::Bary), to_keys(&["su", "mo", "ma", "ea"]));
+  }
+
+  #[test]
+  fn test_south_node() {
+    let (ra, ke, sn) = with_ephemeris(|| (
+      calc_body_jd(2451545f64, "ra", false, CentricMode::Geo, 0f64),
+      calc_body_jd(2451545f64, "ke", false, CentricMode::Geo, 0f64),
+      calc_body_jd(2451545f64, "sn", false, CentricMode::Geo, 0f64),
+    ));
+    // the true node at J2000 is close to 123º
+    assert!((ra.lng - 123.95).abs() < 0.5);
+    assert!((sn.lng - calc_opposite(ra.lng)).abs() < 0.000001);
+    assert!((sn.lng - ke.lng).abs() < 0.000001);
+    let (ke_eq, sn_eq) = with_ephemeris(|| (calc_body_eq_jd(2451545f64, "ke", CentricMode::Geo), calc_body_eq_jd(2451545f64, "sn", CentricMode::Geo)));
+    assert!(sn_eq.rect_ascension > 0f64);
+    assert!((sn_eq.rect_ascension - ke_eq.rect_ascension).abs() < 0.000001);
+  }
+
+  #[test]
+  fn test_centric_flags() {
+    let (sun, earth, bary_sun) = with_ephemeris(|| (
+      calc_body_jd(2451545f64, "su", false, CentricMode::Geo, 0f64),
+      calc_body_jd(2451545f64, "ea", false, CentricMode::Helio, 0f64),
+      calc_body_jd(2451545f64, "su", false, CentricMode::Bary, 0f64),
+    ));
+    // the heliocentric earth is opposite the geocentric sun at the same distance
+    assert!((sun.lng - calc_opposite(earth.lng)).abs() < 0.01);
+    assert!((earth.distance.unwrap() - sun.distance.unwrap()).abs() < 0.0001);
+    // the sun stays within about 0.01 AU of the solar system barycentre
+    assert!(bary_sun.distance.unwrap() < 0.02);
+  }
+
+  #[test]
+  fn test_bodies_xyz() {
+    let (sun, items) = with_ephemeris(|| (
+      calc_body_jd_geo(2451545f64, "su", 0f64),
+      get_bodies_xyz(2451545f64, &vec!["su", "ra", "ke"], CentricMode::Geo, None, false),
+    ));
+    let xyz_sun = &items[0];
+    let norm = (xyz_sun.x.powi(2) + xyz_sun.y.powi(2) + xyz_sun.z.powi(2)).sqrt();
+    assert!((norm - sun.distance.unwrap()).abs() < 0.000001);
+    // ecliptic longitude from the rectangular coordinates
+    assert!((normalize_360(xyz_sun.y.atan2(xyz_sun.x).to_degrees()) - sun.lng).abs() < 0.0001);
+    let (ra, ke) = (&items[1], &items[2]);
+    assert_eq!((ke.x, ke.y, ke.z), (-ra.x, -ra.y, -ra.z));
+  }
+
+  #[test]
+  fn test_distance_serialization() {
+    let pos = GrahaPos::new("as", 100f64, 0f64, 0f64, 0f64);
+    let value = serde_json::to_value(&pos).unwrap();
+    assert!(value.get("distance").is_none());
+    let value = serde_json::to_value(&pos.with_distance(0.98, 0.0001)).unwrap();
+    assert_eq!(value["distance"], 0.98);
+  }
+}
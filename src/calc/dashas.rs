@@ -0,0 +1,240 @@
+use serde::{Serialize, Deserialize};
+use super::{
+  dates::julian_day_to_iso_datetime,
+  settings::{dashas::*, nakshatras::*},
+};
+
+/*
+* Maximum span covered by the mahadasha sequence from the birth date
+*/
+pub const MAX_DASHA_YEARS: f64 = 120f64;
+
+/*
+* Mahadasha, antardasha and pratyantardasha
+*/
+pub const MAX_DASHA_DEPTH: u8 = 3;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum DashaSystem {
+  Vimshottari,
+  Yogini,
+  Ashtottari,
+}
+
+impl DashaSystem {
+  pub fn from_key(key: &str) -> Self {
+    match key.to_lowercase().as_str() {
+      "yo" | "yog" | "yogini" => DashaSystem::Yogini,
+      "as" | "ash" | "ashtottari" => DashaSystem::Ashtottari,
+      _ => DashaSystem::Vimshottari,
+    }
+  }
+
+  pub fn as_string(&self) -> String {
+    match self {
+      DashaSystem::Vimshottari => "vimshottari",
+      DashaSystem::Yogini => "yogini",
+      DashaSystem::Ashtottari => "ashtottari",
+    }.to_string()
+  }
+
+  pub fn lords(&self) -> Vec<(&'static str, &'static str, f64)> {
+    match self {
+      DashaSystem::Vimshottari => VIMSHOTTARI_LORDS.to_vec(),
+      DashaSystem::Yogini => YOGINI_LORDS.to_vec(),
+      DashaSystem::Ashtottari => ASHTOTTARI_LORDS.to_vec(),
+    }
+  }
+
+  pub fn total_years(&self) -> f64 {
+    self.lords().iter().map(|(_, _, years)| years).sum()
+  }
+
+  /**
+   * Index of the first lord and the fraction of its period already elapsed at the given sidereal Moon longitude
+   */
+  pub fn start_position(&self, moon_lng: f64) -> (usize, f64) {
+    let lng = moon_lng.rem_euclid(360f64);
+    let nak_num = nakshatra_num(lng);
+    let nak_fraction = (lng % NAKSHATRA_SPAN) / NAKSHATRA_SPAN;
+    match self {
+      DashaSystem::Vimshottari => ((nak_num as usize - 1) % 9, nak_fraction),
+      DashaSystem::Yogini => ((nak_num as usize + 2) % 8, nak_fraction),
+      DashaSystem::Ashtottari => {
+        let offset = (nak_num as i32 - ASHTOTTARI_START_NAKSHATRA as i32).rem_euclid(27) as u16;
+        let mut group_start: u16 = 0;
+        let mut index: usize = 0;
+        for (i, size) in ASHTOTTARI_GROUP_SIZES.iter().enumerate() {
+          if offset < group_start + size {
+            index = i;
+            break;
+          }
+          group_start += size;
+        }
+        let size = ASHTOTTARI_GROUP_SIZES[index] as f64;
+        (index, ((offset - group_start) as f64 + nak_fraction) / size)
+      }
+    }
+  }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DashaPeriod {
+  pub key: String,
+  pub name: String,
+  pub level: u8,
+  pub years: f64,
+  pub start: f64,
+  pub end: f64,
+  #[serde(rename = "startDt", skip_serializing_if = "String::is_empty")]
+  pub start_dt: String,
+  #[serde(rename = "endDt", skip_serializing_if = "String::is_empty")]
+  pub end_dt: String,
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub periods: Vec<DashaPeriod>,
+}
+
+impl DashaPeriod {
+  pub fn new(key: &str, name: &str, level: u8, years: f64, start: f64, end: f64, iso_mode: bool) -> Self {
+    let (start_dt, end_dt) = if iso_mode { (julian_day_to_iso_datetime(start), julian_day_to_iso_datetime(end)) } else { ("".to_string(), "".to_string()) };
+    DashaPeriod {
+      key: key.to_string(),
+      name: name.to_string(),
+      level,
+      years,
+      start,
+      end,
+      start_dt,
+      end_dt,
+      periods: vec![],
+    }
+  }
+
+  pub fn contains(&self, jd: f64) -> bool {
+    jd >= self.start && jd < self.end
+  }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DashaSet {
+  pub system: String,
+  #[serde(rename = "yearLength")]
+  pub year_length: f64,
+  #[serde(rename = "moonLng")]
+  pub moon_lng: f64,
+  pub nakshatra: u16,
+  #[serde(rename = "nakshatraName")]
+  pub nakshatra_name: String,
+  /*
+  * Years of the first mahadasha remaining at birth
+  */
+  pub balance: f64,
+  pub periods: Vec<DashaPeriod>,
+}
+
+/*
+* Subperiods follow the same sequence starting with the lord of the parent period and are proportional to its length
+*/
+fn calc_sub_periods(system: DashaSystem, lord_index: usize, start_jd: f64, parent_years: f64, year_length: f64, level: u8, depth: u8, iso_mode: bool) -> Vec<DashaPeriod> {
+  let lords = system.lords();
+  let total_years = system.total_years();
+  let mut periods: Vec<DashaPeriod> = Vec::with_capacity(lords.len());
+  let mut ref_jd = start_jd;
+  for i in 0..lords.len() {
+    let index = (lord_index + i) % lords.len();
+    let (key, name, lord_years) = lords[index];
+    let years = parent_years * lord_years / total_years;
+    let end_jd = ref_jd + years * year_length;
+    let mut period = DashaPeriod::new(key, name, level, years, ref_jd, end_jd, iso_mode);
+    if level < depth {
+      period.periods = calc_sub_periods(system, index, ref_jd, years, year_length, level + 1, depth, iso_mode);
+    }
+    periods.push(period);
+    ref_jd = end_jd;
+  }
+  periods
+}
+
+/**
+ * Dasha sequence from the birth Moon's sidereal longitude. The first mahadasha starts before birth
+ * by the elapsed part of the Moon's nakshatra (or nakshatra group) and mahadashas run until 120 years after birth
+ */
+pub fn calc_dashas(birth_jd: f64, moon_lng: f64, system: DashaSystem, year_length: f64, depth: u8, iso_mode: bool) -> DashaSet {
+  let lords = system.lords();
+  let max_depth = if depth < 1 { 1 } else if depth > MAX_DASHA_DEPTH { MAX_DASHA_DEPTH } else { depth };
+  let (start_index, elapsed) = system.start_position(moon_lng);
+  let first_years = lords[start_index].2;
+  let end_limit = birth_jd + MAX_DASHA_YEARS * year_length;
+  let mut periods: Vec<DashaPeriod> = Vec::new();
+  let mut ref_jd = birth_jd - elapsed * first_years * year_length;
+  let mut i: usize = 0;
+  while ref_jd < end_limit {
+    let index = (start_index + i) % lords.len();
+    let (key, name, years) = lords[index];
+    let end_jd = ref_jd + years * year_length;
+    let mut period = DashaPeriod::new(key, name, 1, years, ref_jd, end_jd, iso_mode);
+    if max_depth > 1 {
+      period.periods = calc_sub_periods(system, index, ref_jd, years, year_length, 2, max_depth, iso_mode);
+    }
+    periods.push(period);
+    ref_jd = end_jd;
+    i += 1;
+  }
+  let lng = moon_lng.rem_euclid(360f64);
+  let nak_num = nakshatra_num(lng);
+  DashaSet {
+    system: system.as_string(),
+    year_length,
+    moon_lng: lng,
+    nakshatra: nak_num,
+    nakshatra_name: nakshatra_name(nak_num),
+    balance: (1f64 - elapsed) * first_years,
+    periods,
+  }
+}
+
+/*
+* Chain of periods (mahadasha, antardasha etc.) running at the given date
+*/
+pub fn match_current_periods(periods: &Vec<DashaPeriod>, jd: f64) -> Vec<DashaPeriod> {
+  let mut items: Vec<DashaPeriod> = Vec::new();
+  let mut level_periods = periods;
+  while let Some(period) = level_periods.iter().find(|p| p.contains(jd)) {
+    let mut item = period.clone();
+    item.periods = vec![];
+    items.push(item);
+    level_periods = &period.periods;
+  }
+  items
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_vimshottari_start() {
+    // 10º Aries is in Ashwini, ruled by Ketu, 0.75 of the nakshatra traversed
+    let (index, elapsed) = DashaSystem::Vimshottari.start_position(10f64);
+    assert_eq!(VIMSHOTTARI_LORDS[index].0, "ke");
+    assert!((elapsed - 0.75).abs() < 0.000001);
+  }
+
+  #[test]
+  fn test_ashtottari_start() {
+    // the middle of Revati (27), the second nakshatra in the Rahu group from Uttara Bhadrapada
+    let lng = 26.5f64 * NAKSHATRA_SPAN;
+    let (index, elapsed) = DashaSystem::Ashtottari.start_position(lng);
+    assert_eq!(ASHTOTTARI_LORDS[index].0, "ra");
+    assert!((elapsed - 0.375).abs() < 0.000001);
+  }
+
+  #[test]
+  fn test_sub_periods_span_parent() {
+    let set = calc_dashas(2451545f64, 100f64, DashaSystem::Vimshottari, DEFAULT_DASHA_YEAR_LENGTH, 2, false);
+    let first = set.periods.first().unwrap();
+    let sub_end = first.periods.last().unwrap().end;
+    assert!((sub_end - first.end).abs() < 0.000001);
+    assert_eq!(first.periods.first().unwrap().key, first.key);
+  }
+}
@@ -1,6 +1,6 @@
 use chrono::NaiveDateTime;
 use julian_day_converter::*;
-use crate::calc::rise_set_phases::MIN_JD;
+use crate::calc::{errors::CalcError, rise_set_phases::MIN_JD};
 
 /**
  * Get NaiveDateTimeObject for the current date time. Will only fail in exceptional circumstmances
@@ -20,11 +20,11 @@ pub fn current_datetime_string() -> String {
   
 }
 
-pub fn iso_string_to_datetime(dt_str: &str) -> NaiveDateTime {
+pub fn iso_string_to_datetime(dt_str: &str) -> Result<NaiveDateTime, CalcError> {
   if let Some(dt) = NaiveDateTime::from_fuzzy_iso_string(dt_str) {
-    dt
+    Ok(dt)
   } else {
-    current_datetime().unwrap()
+    Err(CalcError::InvalidDate(dt_str.to_string()))
   }
 }
 
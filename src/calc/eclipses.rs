@@ -0,0 +1,229 @@
+use serde::{Serialize, Deserialize};
+use crate::extensions::swe::{eclipse_when_glob, eclipse_when_loc, EclipseResult};
+use super::{
+  dates::julian_day_to_iso_datetime,
+  models::{general::{FlexiValue, KeyNumValue}, geo_pos::GeoPos},
+};
+
+#[derive(Debug, Copy, Clone)]
+pub enum EclipseFlag {
+  Total = 4,
+  Annular = 8,
+  Partial = 16,
+  Hybrid = 32,
+  Penumbral = 64,
+}
+
+impl EclipseFlag {
+  pub fn matches(&self, flag: i32) -> bool {
+    flag & (*self as i32) != 0
+  }
+}
+
+pub fn eclipse_type_key(flag: i32) -> String {
+  if EclipseFlag::Hybrid.matches(flag) {
+    "hybrid"
+  } else if EclipseFlag::Total.matches(flag) {
+    "total"
+  } else if EclipseFlag::Annular.matches(flag) {
+    "annular"
+  } else if EclipseFlag::Partial.matches(flag) {
+    "partial"
+  } else if EclipseFlag::Penumbral.matches(flag) {
+    "penumbral"
+  } else {
+    "none"
+  }.to_string()
+}
+
+/*
+* Match event times by their index in the Swiss Ephemeris tret array, skipping events that do not apply
+*/
+fn to_contacts(times: &[f64; 10], refs: Vec<(&str, usize)>, iso_mode: bool) -> Vec<FlexiValue> {
+  refs.into_iter()
+    .filter(|(_, index)| times[*index] > 0f64)
+    .map(|(key, index)| KeyNumValue::new(key, times[index]).as_flexi_value(iso_mode))
+    .collect()
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LocalEclipse {
+  pub visible: bool,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub jd: Option<f64>,
+  #[serde(skip_serializing_if = "String::is_empty")]
+  pub dt: String,
+  pub magnitude: f64,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub obscuration: Option<f64>,
+  pub altitude: f64,
+  pub azimuth: f64,
+  pub contacts: Vec<FlexiValue>,
+}
+
+impl LocalEclipse {
+  pub fn not_visible() -> Self {
+    LocalEclipse {
+      visible: false,
+      jd: None,
+      dt: "".to_string(),
+      magnitude: 0f64,
+      obscuration: None,
+      altitude: 0f64,
+      azimuth: 0f64,
+      contacts: vec![],
+    }
+  }
+
+  pub fn new(result: &EclipseResult, lunar: bool, iso_mode: bool) -> Self {
+    let jd = result.times[0];
+    let dt = if iso_mode { julian_day_to_iso_datetime(jd) } else { "".to_string() };
+    let contacts = if lunar {
+      to_contacts(&result.times, vec![("penumbral_begin", 6), ("partial_begin", 2), ("total_begin", 4), ("total_end", 5), ("partial_end", 3), ("penumbral_end", 7), ("moonrise", 8), ("moonset", 9)], iso_mode)
+    } else {
+      to_contacts(&result.times, vec![("first", 1), ("second", 2), ("third", 3), ("fourth", 4), ("sunrise", 5), ("sunset", 6)], iso_mode)
+    };
+    LocalEclipse {
+      visible: true,
+      jd: Some(jd),
+      dt,
+      magnitude: if lunar { result.attrs[0] } else { result.attrs[8] },
+      obscuration: if lunar { None } else { Some(result.attrs[2]) },
+      altitude: result.attrs[5],
+      azimuth: result.attrs[4],
+      contacts,
+    }
+  }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EclipseInfo {
+  pub kind: String,
+  pub r#type: String,
+  pub jd: f64,
+  #[serde(skip_serializing_if = "String::is_empty")]
+  pub dt: String,
+  pub magnitude: f64,
+  #[serde(rename = "penumbralMagnitude", skip_serializing_if = "Option::is_none")]
+  pub penumbral_magnitude: Option<f64>,
+  pub contacts: Vec<FlexiValue>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub local: Option<LocalEclipse>,
+}
+
+impl EclipseInfo {
+  pub fn new(result: &EclipseResult, lunar: bool, iso_mode: bool) -> Self {
+    let jd = result.times[0];
+    let dt = if iso_mode { julian_day_to_iso_datetime(jd) } else { "".to_string() };
+    let contacts = if lunar {
+      to_contacts(&result.times, vec![("penumbral_begin", 6), ("partial_begin", 2), ("total_begin", 4), ("total_end", 5), ("partial_end", 3), ("penumbral_end", 7)], iso_mode)
+    } else {
+      to_contacts(&result.times, vec![("begin", 2), ("total_begin", 4), ("center_begin", 6), ("center_end", 7), ("total_end", 5), ("end", 3)], iso_mode)
+    };
+    EclipseInfo {
+      kind: if lunar { "lunar" } else { "solar" }.to_string(),
+      r#type: eclipse_type_key(result.flag),
+      jd,
+      dt,
+      magnitude: if lunar { result.attrs[0] } else { result.attrs[8] },
+      penumbral_magnitude: if lunar { Some(result.attrs[1]) } else { None },
+      contacts,
+      local: None,
+    }
+  }
+
+  pub fn set_local(&mut self, local: LocalEclipse) {
+    self.local = Some(local);
+  }
+}
+
+/**
+ * Match the local circumstances of a global eclipse. The next locally observable eclipse
+ * is only the same event if its maximum lies within a day of the global maximum
+ */
+pub fn calc_local_eclipse(global_jd: f64, lunar: bool, geo: GeoPos, iso_mode: bool) -> LocalEclipse {
+  let result = eclipse_when_loc(global_jd - 1f64, lunar, geo.lat, geo.lng, geo.alt);
+  if result.is_valid() && (result.times[0] - global_jd).abs() < 1f64 {
+    LocalEclipse::new(&result, lunar, iso_mode)
+  } else {
+    LocalEclipse::not_visible()
+  }
+}
+
+pub fn calc_eclipses_by_kind(start_jd: f64, end_jd: f64, lunar: bool, geo_opt: Option<GeoPos>, iso_mode: bool) -> Vec<EclipseInfo> {
+  let mut items: Vec<EclipseInfo> = Vec::new();
+  let mut ref_jd = start_jd;
+  while ref_jd < end_jd {
+    let result = eclipse_when_glob(ref_jd, lunar);
+    if !result.is_valid() || result.times[0] > end_jd {
+      break;
+    }
+    let mut info = EclipseInfo::new(&result, lunar, iso_mode);
+    if let Some(geo) = geo_opt {
+      info.set_local(calc_local_eclipse(result.times[0], lunar, geo, iso_mode));
+    }
+    items.push(info);
+    // eclipses of the same kind are always at least a lunar month apart
+    ref_jd = result.times[0] + 1f64;
+  }
+  items
+}
+
+/**
+ * List solar and / or lunar eclipses between two dates in chronological order
+ */
+pub fn calc_eclipses(start_jd: f64, end_jd: f64, solar: bool, lunar: bool, geo_opt: Option<GeoPos>, iso_mode: bool) -> Vec<EclipseInfo> {
+  let mut items: Vec<EclipseInfo> = Vec::new();
+  if solar {
+    items.append(&mut calc_eclipses_by_kind(start_jd, end_jd, false, geo_opt, iso_mode));
+  }
+  if lunar {
+    items.append(&mut calc_eclipses_by_kind(start_jd, end_jd, true, geo_opt, iso_mode));
+  }
+  items.sort_by(|a, b| a.jd.partial_cmp(&b.jd).unwrap());
+  items
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::ephemeris::with_ephemeris;
+  use julian_day_converter::JulianDay;
+  use chrono::NaiveDateTime;
+
+  fn to_jd(dt: &str) -> f64 {
+    NaiveDateTime::parse_from_str(dt, "%Y-%m-%dT%H:%M:%S").unwrap().to_jd()
+  }
+
+  /*
+  * 1 March 2024 to 1 April 2025
+  */
+  fn calc_test_eclipses(solar: bool, lunar: bool, geo_opt: Option<GeoPos>) -> Vec<EclipseInfo> {
+    with_ephemeris(|| calc_eclipses(2460370.5, 2460766.5, solar, lunar, geo_opt, false))
+  }
+
+  #[test]
+  fn test_total_solar_eclipse() {
+    // greatest eclipse on 8 April 2024 at 18:17:16 UT, total in Dallas with maximum at 18:42 UT
+    let dallas = GeoPos::new(32.7767, -96.797, 150f64);
+    let items = calc_test_eclipses(true, false, Some(dallas));
+    let eclipse = items.iter().find(|e| e.r#type == "total").unwrap();
+    assert_eq!(eclipse.kind, "solar");
+    assert!((eclipse.jd - to_jd("2024-04-08T18:17:16")).abs() * 1440f64 < 1f64);
+    let local = eclipse.local.clone().unwrap();
+    assert!(local.visible && local.magnitude > 1f64);
+    assert!((local.jd.unwrap() - to_jd("2024-04-08T18:42:00")).abs() * 1440f64 < 2f64);
+  }
+
+  #[test]
+  fn test_total_lunar_eclipse() {
+    // greatest eclipse on 14 March 2025 at 06:58:43 UT
+    let items = calc_test_eclipses(false, true, None);
+    let eclipse = items.iter().find(|e| e.r#type == "total").unwrap();
+    assert_eq!(eclipse.kind, "lunar");
+    assert!((eclipse.jd - to_jd("2025-03-14T06:58:43")).abs() * 1440f64 < 1f64);
+    assert!(eclipse.magnitude > 1f64);
+    assert_eq!(items.len(), 3);
+    assert!(items.windows(2).all(|pair| pair[0].jd < pair[1].jd));
+  }
+}
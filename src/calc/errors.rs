@@ -0,0 +1,124 @@
+use std::fmt::{Display, Formatter, Result};
+
+/*
+* Invalid input detected in the calculation layer. The offending values are kept so they can be listed in error responses
+*/
+#[derive(Debug, Clone, PartialEq)]
+pub enum CalcError {
+  InvalidBodyKeys(Vec<String>),
+  InvalidLocation(String),
+  InvalidDate(String),
+  InvalidTimeZone(String),
+  InvalidParam(String, String),
+  TooManyItems(usize),
+  CalculationFailed,
+  TimedOut(u64),
+}
+
+impl Display for CalcError {
+  fn fmt(&self, f: &mut Formatter) -> Result {
+    match self {
+      CalcError::InvalidBodyKeys(keys) => write!(f, "invalid body keys: {}", keys.join(",")),
+      CalcError::InvalidLocation(loc) => write!(f, "invalid location: {}", loc),
+      CalcError::InvalidDate(dt) => write!(f, "invalid date: {}", dt),
+      CalcError::InvalidTimeZone(tz) => write!(f, "invalid time zone: {}", tz),
+      CalcError::InvalidParam(key, reason) => write!(f, "invalid {}: {}", key, reason),
+      CalcError::TooManyItems(max) => write!(f, "too many items, the maximum is {}", max),
+      CalcError::CalculationFailed => write!(f, "the calculation could not be completed"),
+      CalcError::TimedOut(secs) => write!(f, "the calculation did not complete within {} seconds", secs),
+    }
+  }
+}
+
+impl std::error::Error for CalcError {}
+
+/*
+* All input errors of a request, so they can be reported together
+*/
+#[derive(Debug, Clone, Default)]
+pub struct CalcErrors {
+  pub items: Vec<CalcError>,
+}
+
+impl CalcErrors {
+  pub fn new() -> Self {
+    CalcErrors { items: vec![] }
+  }
+
+  pub fn add(&mut self, error: CalcError) {
+    self.items.push(error);
+  }
+
+  /*
+  * Keep the error if any and return the valid value as an option
+  */
+  pub fn check<T>(&mut self, result: std::result::Result<T, CalcError>) -> Option<T> {
+    match result {
+      Ok(value) => Some(value),
+      Err(error) => {
+        self.add(error);
+        None
+      }
+    }
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.items.is_empty()
+  }
+
+  /*
+  * Failures not caused by invalid input
+  */
+  pub fn is_internal(&self) -> bool {
+    self.items.contains(&CalcError::CalculationFailed) || self.is_timed_out()
+  }
+
+  /*
+  * Calculations cancelled after the configured calculation timeout
+  */
+  pub fn is_timed_out(&self) -> bool {
+    self.items.iter().any(|e| matches!(e, CalcError::TimedOut(_)))
+  }
+
+  pub fn invalid_keys(&self) -> Vec<String> {
+    self.items.iter().filter_map(|e| match e { CalcError::InvalidBodyKeys(keys) => Some(keys.clone()), _ => None }).flatten().collect()
+  }
+
+  pub fn invalid_locations(&self) -> Vec<String> {
+    self.items.iter().filter_map(|e| match e { CalcError::InvalidLocation(loc) => Some(loc.clone()), _ => None }).collect()
+  }
+
+  pub fn invalid_dates(&self) -> Vec<String> {
+    self.items.iter().filter_map(|e| match e { CalcError::InvalidDate(dt) => Some(dt.clone()), _ => None }).collect()
+  }
+
+  pub fn invalid_time_zones(&self) -> Vec<String> {
+    self.items.iter().filter_map(|e| match e { CalcError::InvalidTimeZone(tz) => Some(tz.clone()), _ => None }).collect()
+  }
+
+  /*
+  * Names of query parameters with unsupported or out-of-range values
+  */
+  pub fn invalid_params(&self) -> Vec<String> {
+    self.items.iter().filter_map(|e| match e { CalcError::InvalidParam(key, _) => Some(key.clone()), _ => None }).collect()
+  }
+
+  pub fn into_result(self) -> std::result::Result<(), CalcErrors> {
+    if self.is_empty() { Ok(()) } else { Err(self) }
+  }
+}
+
+impl From<CalcError> for CalcErrors {
+  fn from(error: CalcError) -> Self {
+    CalcErrors { items: vec![error] }
+  }
+}
+
+impl Display for CalcErrors {
+  fn fmt(&self, f: &mut Formatter) -> Result {
+    let messages: Vec<String> = self.items.iter().map(|e| e.to_string()).collect();
+    write!(f, "{}", messages.join("; "))
+  }
+}
+
+impl std::error::Error for CalcErrors {}
@@ -0,0 +1,214 @@
+use serde::{Serialize, Deserialize};
+use super::{
+  core::calc_body_jd_geo,
+  dates::julian_day_to_iso_datetime,
+  math_funcs::signed_angle_180,
+  search::{bisect_root, body_search_step},
+  settings::nakshatras::*,
+  transits::AyanamshaRange,
+};
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum IngressMode {
+  Sign,
+  Nakshatra,
+  Pada,
+}
+
+impl IngressMode {
+  pub fn span(&self) -> f64 {
+    match self {
+      IngressMode::Sign => SIGN_SPAN,
+      IngressMode::Nakshatra => NAKSHATRA_SPAN,
+      IngressMode::Pada => PADA_SPAN,
+    }
+  }
+
+  pub fn as_string(&self) -> String {
+    match self {
+      IngressMode::Sign => "sign",
+      IngressMode::Nakshatra => "nakshatra",
+      IngressMode::Pada => "pada",
+    }.to_string()
+  }
+
+  /*
+  * 0: signs only, 1: signs and nakshatras, 2: signs, nakshatras and padas
+  */
+  pub fn from_level(level: u8) -> Vec<IngressMode> {
+    match level {
+      0 => vec![IngressMode::Sign],
+      1 => vec![IngressMode::Sign, IngressMode::Nakshatra],
+      _ => vec![IngressMode::Sign, IngressMode::Nakshatra, IngressMode::Pada],
+    }
+  }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Ingress {
+  pub key: String,
+  pub mode: String,
+  pub num: u16,
+  #[serde(skip_serializing_if = "String::is_empty")]
+  pub name: String,
+  #[serde(rename = "prevNum")]
+  pub prev_num: u16,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub pada: Option<u16>,
+  pub jd: f64,
+  #[serde(skip_serializing_if = "String::is_empty")]
+  pub dt: String,
+  pub lng: f64,
+  pub retro: bool,
+}
+
+impl Ingress {
+  pub fn new(key: &str, mode: IngressMode, boundary: f64, jd: f64, speed: f64, iso_mode: bool) -> Self {
+    let span = mode.span();
+    let retro = speed < 0f64;
+    // the body sits exactly on the boundary, so derive the entered division from the direction of motion
+    let ref_lng = if retro { boundary - span / 2f64 } else { boundary + span / 2f64 };
+    let prev_lng = if retro { boundary + span / 2f64 } else { boundary - span / 2f64 };
+    let (num, prev_num, name, pada) = match mode {
+      IngressMode::Sign => (sign_num(ref_lng), sign_num(prev_lng), sign_name(sign_num(ref_lng)), None),
+      IngressMode::Nakshatra => (nakshatra_num(ref_lng), nakshatra_num(prev_lng), nakshatra_name(nakshatra_num(ref_lng)), None),
+      IngressMode::Pada => (nakshatra_num(ref_lng), nakshatra_num(prev_lng), nakshatra_name(nakshatra_num(ref_lng)), Some(pada_num(ref_lng))),
+    };
+    let dt = if iso_mode { julian_day_to_iso_datetime(jd) } else { "".to_string() };
+    Ingress {
+      key: key.to_string(),
+      mode: mode.as_string(),
+      num,
+      name,
+      prev_num,
+      pada,
+      jd,
+      dt,
+      lng: boundary.rem_euclid(360f64),
+      retro,
+    }
+  }
+}
+
+/*
+* Longitude boundaries crossed when moving from lng1 by the signed difference diff
+*/
+fn crossed_boundaries(lng1: f64, diff: f64, span: f64) -> Vec<f64> {
+  let mut boundaries: Vec<f64> = Vec::new();
+  if diff > 0f64 {
+    let mut b = (lng1 / span).floor() * span + span;
+    while b <= lng1 + diff {
+      boundaries.push(b);
+      b += span;
+    }
+  } else if diff < 0f64 {
+    let mut b = (lng1 / span).floor() * span;
+    while b > lng1 + diff {
+      boundaries.push(b);
+      b -= span;
+    }
+  }
+  boundaries
+}
+
+/**
+ * List the exact moments the body enters a new sign, nakshatra or pada between start_jd and end_jd.
+ * The sampling step is short enough for the Moon to cross at most one or two padas per step.
+ */
+pub fn calc_body_ingresses(key: &str, modes: &Vec<IngressMode>, start_jd: f64, end_jd: f64, aya: &AyanamshaRange, iso_mode: bool) -> Vec<Ingress> {
+  let mut items: Vec<Ingress> = Vec::new();
+  let has_minor = modes.iter().any(|m| *m != IngressMode::Sign);
+  let step = if has_minor { body_search_step(key) / 2f64 } else { body_search_step(key) };
+  let lng_at = |jd: f64| calc_body_jd_geo(jd, key, aya.value(jd)).lng;
+  let mut prev_jd = start_jd;
+  let mut prev_lng = lng_at(prev_jd);
+  while prev_jd < end_jd {
+    let next_jd = if prev_jd + step > end_jd { end_jd } else { prev_jd + step };
+    let next_lng = lng_at(next_jd);
+    let diff = signed_angle_180(next_lng - prev_lng);
+    for mode in modes {
+      for boundary in crossed_boundaries(prev_lng, diff, mode.span()) {
+        // the nakshatra and sign boundaries coinciding with pada boundaries are only listed once
+        if *mode == IngressMode::Pada && (boundary / NAKSHATRA_SPAN - (boundary / NAKSHATRA_SPAN).round()).abs() < 0.000001 {
+          continue;
+        }
+        let jd = bisect_root(&|jd: f64| signed_angle_180(lng_at(jd) - boundary), prev_jd, next_jd);
+        let speed = calc_body_jd_geo(jd, key, aya.value(jd)).lng_speed;
+        items.push(Ingress::new(key, *mode, boundary, jd, speed, iso_mode));
+      }
+    }
+    prev_jd = next_jd;
+    prev_lng = next_lng;
+  }
+  items.sort_by(|a, b| a.jd.partial_cmp(&b.jd).unwrap());
+  items
+}
+
+pub fn calc_ingresses(keys: &Vec<&str>, modes: &Vec<IngressMode>, start_jd: f64, end_jd: f64, aya: &AyanamshaRange, iso_mode: bool) -> Vec<Ingress> {
+  let mut items: Vec<Ingress> = Vec::new();
+  for key in keys {
+    let mut rows = calc_body_ingresses(key, modes, start_jd, end_jd, aya, iso_mode);
+    items.append(&mut rows);
+  }
+  items.sort_by(|a, b| a.jd.partial_cmp(&b.jd).unwrap());
+  items
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::ephemeris::with_ephemeris;
+
+  fn is_multiple(lng: f64, span: f64) -> bool {
+    (lng / span - (lng / span).round()).abs() < 0.000001
+  }
+
+  #[test]
+  fn test_crossed_boundaries() {
+    assert_eq!(crossed_boundaries(25f64, 40f64, SIGN_SPAN), vec![30f64, 60f64]);
+    assert_eq!(crossed_boundaries(359f64, 2f64, SIGN_SPAN), vec![360f64]);
+    assert_eq!(crossed_boundaries(65f64, -40f64, SIGN_SPAN), vec![60f64, 30f64]);
+    assert_eq!(crossed_boundaries(1f64, -2f64, SIGN_SPAN), vec![0f64]);
+    // a boundary reached at the end of one step is not crossed again at the start of the next
+    assert_eq!(crossed_boundaries(20f64, 10f64, SIGN_SPAN), vec![30f64]);
+    assert!(crossed_boundaries(30f64, 5f64, SIGN_SPAN).is_empty());
+    assert!(crossed_boundaries(40f64, -10f64, SIGN_SPAN).is_empty());
+    assert_eq!(crossed_boundaries(30f64, -5f64, SIGN_SPAN), vec![30f64]);
+    assert!(crossed_boundaries(12f64, 0f64, SIGN_SPAN).is_empty());
+  }
+
+  #[test]
+  fn test_nakshatra_pada_spans() {
+    assert_eq!(crossed_boundaries(12f64, 2f64, NAKSHATRA_SPAN), vec![NAKSHATRA_SPAN]);
+    assert_eq!(crossed_boundaries(12f64, 2f64, PADA_SPAN).len(), 1);
+    assert_eq!(crossed_boundaries(0.5, 13f64, PADA_SPAN).len(), 4);
+    // direct: the Moon enters Bharani at 13º20', retrograde motion re-enters the 4th pada of Ashwini
+    let direct = Ingress::new("mo", IngressMode::Nakshatra, NAKSHATRA_SPAN, 2451545f64, 13f64, false);
+    assert_eq!((direct.num, direct.prev_num, direct.retro), (2, 1, false));
+    let retro = Ingress::new("ma", IngressMode::Pada, NAKSHATRA_SPAN, 2451545f64, -0.1, false);
+    assert_eq!((retro.num, retro.prev_num, retro.pada, retro.retro), (1, 2, Some(4), true));
+    let pada = Ingress::new("mo", IngressMode::Pada, PADA_SPAN * 2f64, 2451545f64, 13f64, false);
+    assert_eq!((pada.num, pada.pada), (1, Some(3)));
+  }
+
+  #[test]
+  fn test_moon_ingresses() {
+    // over 3 days the Moon moves about 40º, nakshatra boundaries are not listed again as padas
+    let (items, lng1, lng2) = with_ephemeris(|| (
+      calc_body_ingresses("mo", &IngressMode::from_level(2), 2451545f64, 2451548f64, &AyanamshaRange::tropical(), false),
+      calc_body_jd_geo(2451545f64, "mo", 0f64).lng,
+      calc_body_jd_geo(2451548f64, "mo", 0f64).lng,
+    ));
+    let nakshatras: Vec<&Ingress> = items.iter().filter(|item| item.mode == "nakshatra").collect();
+    let padas: Vec<&Ingress> = items.iter().filter(|item| item.mode == "pada").collect();
+    assert!(nakshatras.len() >= 2 && nakshatras.len() <= 4);
+    assert!(nakshatras.iter().all(|item| is_multiple(item.lng, NAKSHATRA_SPAN)));
+    assert!(padas.iter().all(|item| is_multiple(item.lng, PADA_SPAN) && !is_multiple(item.lng, NAKSHATRA_SPAN)));
+    assert_eq!(padas.len() + nakshatras.len(), crossed_boundaries(lng1, signed_angle_180(lng2 - lng1), PADA_SPAN).len());
+    assert!(items.windows(2).all(|pair| pair[0].jd <= pair[1].jd));
+    for item in items {
+      let lng = with_ephemeris(|| calc_body_jd_geo(item.jd, "mo", 0f64).lng);
+      assert!(signed_angle_180(lng - item.lng).abs() < 0.001);
+    }
+  }
+}
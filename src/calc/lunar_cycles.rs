@@ -1,11 +1,109 @@
 use serde::Serialize;
 use ring360::*;
-use crate::calc::models::geo_pos::GeoPos;
-use crate::calc::core::calc_body_jd_topo;
+use crate::calc::core::calc_body_jd_geo;
 use crate::calc::dates::julian_day_to_iso_datetime;
+use crate::calc::search::{find_angle_roots, find_roots};
+use crate::calc::settings::panchanga::{masa_name, tithi_name};
+use crate::calc::timezones::TimeZoneRef;
 
-const SHORT_LUNAR_MONTH: f64 = 24.0;
-const MEDIAN_LUNAR_MONTH: f64 = 29.53059;
+pub const MEDIAN_LUNAR_MONTH: f64 = 29.53059;
+const PHASE_SEARCH_STEP: f64 = 0.25;
+const PERIGEE_SEARCH_STEP: f64 = 1.0;
+
+/*
+* The Moon gains at least 10º per day on the Sun, so the next boundary of any phase division is found within span / 10 days
+*/
+const MIN_ELONGATION_SPEED: f64 = 10.0;
+
+pub const KM_PER_AU: f64 = 149_597_870.7;
+
+/*
+* New or full moons within 90% of the mean range from apogee (406,700 km) to perigee (356,500 km)
+*/
+const SUPERMOON_MAX_DISTANCE_KM: f64 = 361_520.0;
+
+const OCTANT_NAMES: [&'static str; 8] = [
+  "new",
+  "waxing-crescent",
+  "first-quarter",
+  "waxing-gibbous",
+  "full",
+  "waning-gibbous",
+  "last-quarter",
+  "waning-crescent",
+];
+
+/*
+* Elongation boundaries of the phases: 4 quarters (90º), 8 octants (45º) or 30 tithis (12º)
+*/
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum PhaseDivision {
+  Quarters,
+  Octants,
+  Tithis,
+}
+
+impl PhaseDivision {
+  pub fn from_u8(num: u8) -> Option<Self> {
+    match num {
+      4 => Some(PhaseDivision::Quarters),
+      8 => Some(PhaseDivision::Octants),
+      30 => Some(PhaseDivision::Tithis),
+      _ => None,
+    }
+  }
+
+  pub fn count(&self) -> u8 {
+    match self {
+      PhaseDivision::Quarters => 4,
+      PhaseDivision::Octants => 8,
+      PhaseDivision::Tithis => 30,
+    }
+  }
+
+  pub fn span(&self) -> f64 {
+    360.0 / self.count() as f64
+  }
+
+  /*
+  * 1-based phase number starting at the new moon, e.g. 3 = full moon with quarters
+  */
+  pub fn name(&self, num: u8) -> String {
+    match self {
+      PhaseDivision::Tithis => tithi_name(num as u16),
+      _ => {
+        let index = (num.max(1) as usize - 1) * 8 / self.count() as usize;
+        OCTANT_NAMES[index % 8].to_string()
+      }
+    }
+  }
+}
+
+/*
+* Amanta months run from new moon to new moon, purnimanta months from full moon to full moon
+*/
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum MonthScheme {
+  Amanta,
+  Purnimanta,
+}
+
+impl MonthScheme {
+  pub fn from_key(key: &str) -> Option<Self> {
+    match key.to_lowercase().as_str() {
+      "amanta" | "am" => Some(MonthScheme::Amanta),
+      "purnimanta" | "pm" => Some(MonthScheme::Purnimanta),
+      _ => None,
+    }
+  }
+
+  pub fn to_key(&self) -> String {
+    match self {
+      MonthScheme::Amanta => "amanta",
+      MonthScheme::Purnimanta => "purnimanta",
+    }.to_string()
+  }
+}
 
 #[derive(Debug, Clone, Serialize)]
 pub struct MoonPhase {
@@ -13,25 +111,91 @@ pub struct MoonPhase {
   utc: String,
   angle: f64,
   num: u8,
+  name: String,
   waxing: bool,
   #[serde(skip_serializing_if = "Option::is_none")]
   days: Option<f64>,
+  /* distance in km and days from the nearest perigee of new and full moons */
+  #[serde(skip_serializing_if = "Option::is_none")]
+  distance: Option<f64>,
+  #[serde(rename = "perigeeDays", skip_serializing_if = "Option::is_none")]
+  perigee_days: Option<f64>,
+  #[serde(skip_serializing_if = "std::ops::Not::not")]
+  supermoon: bool,
+  /* second full moon (blue) or second new moon (black) in the same calendar month */
+  #[serde(skip_serializing_if = "std::ops::Not::not")]
+  blue: bool,
+  #[serde(skip_serializing_if = "std::ops::Not::not")]
+  black: bool,
 }
 
 impl MoonPhase {
-  pub fn new(ref_jd: f64, angle: f64, num: u8, days: Option<f64>) -> Self {
-    let target_angle = if num > 1 { (num - 1) as f64 * 90.0 } else { 360.0 };
-    let rem_angle = target_angle - angle;
-    let angle = if rem_angle > 0.05 { angle } else { target_angle % 360.0 };
-    let extra_jd = rem_angle / 90.0 * (MEDIAN_LUNAR_MONTH / 4.0);
-    let jd = if extra_jd < 0.0005 { ref_jd + extra_jd } else { ref_jd };
+  pub fn new(jd: f64, angle: f64, num: u8, name: String, days: Option<f64>) -> Self {
     MoonPhase {
       jd,
       utc: julian_day_to_iso_datetime(jd),
       angle,
-      waxing: num <= 2,
       num,
-      days
+      name,
+      waxing: angle < 180.0,
+      days,
+      distance: None,
+      perigee_days: None,
+      supermoon: false,
+      blue: false,
+      black: false,
+    }
+  }
+
+  pub fn is_new(&self) -> bool {
+    self.angle == 0.0
+  }
+
+  pub fn is_full(&self) -> bool {
+    self.angle == 180.0
+  }
+
+  pub fn with_perigee(mut self, distance: f64, perigee_jd: Option<f64>) -> Self {
+    let km = distance * KM_PER_AU;
+    self.distance = Some(km);
+    self.perigee_days = perigee_jd.map(|p_jd| self.jd - p_jd);
+    self.supermoon = km <= SUPERMOON_MAX_DISTANCE_KM;
+    self
+  }
+
+  /*
+  * Flag the second new or full moon in the same calendar month as the previous one
+  */
+  pub fn with_month_repeat(mut self, same_month: bool) -> Self {
+    self.blue = same_month && self.is_full();
+    self.black = same_month && self.is_new();
+    self
+  }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LunarMonth {
+  num: u8,
+  name: String,
+  adhika: bool,
+  start: f64,
+  end: f64,
+  #[serde(rename = "startUtc")]
+  start_utc: String,
+  #[serde(rename = "endUtc")]
+  end_utc: String,
+}
+
+impl LunarMonth {
+  pub fn new(num: u8, adhika: bool, start: f64, end: f64) -> Self {
+    LunarMonth {
+      num,
+      name: masa_name(num),
+      adhika,
+      start,
+      end,
+      start_utc: julian_day_to_iso_datetime(start),
+      end_utc: julian_day_to_iso_datetime(end),
     }
   }
 }
@@ -43,93 +207,175 @@ pub fn calc_sun_moon_angle(moon_lng: f64, sun_lng: f64) -> (f64, bool, u8) {
   (angle, waxing, phase)
 }
 
-pub fn calc_sun_moon_angle_and_phase(jd: f64, geo: GeoPos) -> (f64, bool, u8, f64) {
-  let su = calc_body_jd_topo(jd, "su", geo, 0.0);
-  let mo = calc_body_jd_topo(jd, "mo", geo, 0.0);
-  let spd = (su.lng_speed + mo.lng_speed) / 2.0;
-  let (a, b ,c) = calc_sun_moon_angle(mo.lng, su.lng);
-  (a, b ,c, spd)
-}
-
-fn adjusted_micro_increment(rem_val: f64, increment: &f64) -> f64 {
-  if rem_val < 0.0078125 {
-    1.0 / 15360.0
-  } else if rem_val < 0.015625 {
-    1.0 / 3840.0
-  } else if rem_val < 0.03125 {
-    1.0 / 3840.0
-  } else  if rem_val < 0.0625 {
-    1.0 / 1920.0
-  } else if rem_val < 0.125 {
-    1.0 / 960.0
-  } else if rem_val < 0.25 {
-    1.0 / 480.0
-  } else if rem_val < 0.50 {
-    1.0 / 240.0
-  } else if rem_val < 1.0 {
-    1.0 / 96.0
-  } else if rem_val < 2.0 {
-    1.0 / 48.0
-  } else if rem_val < 4.0 {
-    1.0 / 24.0
-  } else {
-    increment.to_owned().clone()
-  }
-}
-
-pub fn calc_next_phase(start_jd_target: f64, geo: GeoPos, current_angle: f64, phase: u8, prev_jd: f64) -> MoonPhase {
-  let mut rem_val = 90.0 - (current_angle % 90.0);
-  let mut increment = 1.0 / 12.0;
-  let mut counter = 0;
-  let mut nx_jd = 0.0;
-  let mut nx_angle = 0.0;
-  // let mut nx_speed = 0.0;
-  let next_phase = if phase < 4 { phase + 1 } else { 1 };
-  let min_tolerance = 0.005;
-
-  let mut next_jd_target = start_jd_target + increment;
-  while rem_val > min_tolerance {
-    let (next_angle, _waxing, _next_phase, _speed) = calc_sun_moon_angle_and_phase(next_jd_target, geo);
-    rem_val = 90.0 - (next_angle % 90.0);
-    increment = adjusted_micro_increment(rem_val, &increment);
-    if rem_val < min_tolerance {
-      nx_angle = next_angle;
-      nx_jd = next_jd_target;
-      //nx_speed = speed;
+/*
+* Geocentric elongation of the Moon from the Sun, 0º at new moon and 180º at full moon
+*/
+pub fn calc_elongation(jd: f64) -> f64 {
+  let sun = calc_body_jd_geo(jd, "su", 0.0);
+  let moon = calc_body_jd_geo(jd, "mo", 0.0);
+  (moon.lng - sun.lng).rem_euclid(360.0)
+}
+
+/*
+* Exact time the elongation next reaches the target angle, at most one span of the phase division after jd.
+* None if no boundary is found, e.g. outside the ephemeris range
+*/
+fn calc_next_boundary(jd: f64, angle: f64, span: f64) -> Option<f64> {
+  let end_jd = jd + span / MIN_ELONGATION_SPEED + PHASE_SEARCH_STEP;
+  let roots = find_angle_roots(jd, end_jd, PHASE_SEARCH_STEP, |ref_jd| calc_elongation(ref_jd) - angle);
+  roots.first().map(|r| r.to_owned())
+}
+
+/*
+* Times of minimum lunar distance, where the distance speed turns from negative to positive
+*/
+pub fn calc_perigees(start_jd: f64, end_jd: f64) -> Vec<f64> {
+  let distance_speed = |jd: f64| calc_body_jd_geo(jd, "mo", 0.0).distance_speed.unwrap_or(0.0);
+  find_roots(start_jd, end_jd, PERIGEE_SEARCH_STEP, f64::MAX, distance_speed)
+    .into_iter()
+    .filter(|jd| distance_speed(jd + PHASE_SEARCH_STEP) > 0.0)
+    .collect()
+}
+
+fn calendar_month(jd: f64, tz: &TimeZoneRef) -> String {
+  tz.to_local_iso(jd).chars().take(7).collect()
+}
+
+/**
+ * Exact geocentric phases for the number of lunar cycles from jd, with quarters, octants or tithis as boundaries.
+ * New and full moons include the lunar distance, supermoons and blue or black moons in calendar months of the time zone
+ */
+pub fn calc_moon_phases(jd: f64, cycles: u8, division: PhaseDivision, tz: &TimeZoneRef) -> Vec<MoonPhase> {
+  let count = division.count() as usize;
+  let span = division.span();
+  let num_phases = cycles.max(1) as usize * count;
+  // start one lunation earlier to match blue and black moons in the first month
+  let mut ref_jd = jd - MEDIAN_LUNAR_MONTH;
+  let perigees = calc_perigees(ref_jd - MEDIAN_LUNAR_MONTH / 2.0, jd + (cycles.max(1) as f64 + 0.5) * MEDIAN_LUNAR_MONTH);
+  let mut index = (calc_elongation(ref_jd) / span).floor() as usize + 1;
+  let mut prev_jd: Option<f64> = None;
+  let mut prev_new_jd: Option<f64> = None;
+  let mut prev_full_jd: Option<f64> = None;
+  let mut phases: Vec<MoonPhase> = Vec::new();
+  while phases.len() < num_phases {
+    let phase_index = index % count;
+    let angle = phase_index as f64 * span;
+    let phase_jd = match calc_next_boundary(ref_jd, angle, span) {
+      Some(next_jd) => next_jd,
+      None => break,
+    };
+    let days = prev_jd.map(|p_jd| phase_jd - p_jd);
+    let num = phase_index as u8 + 1;
+    let mut phase = MoonPhase::new(phase_jd, angle, num, division.name(num), days);
+    if phase.is_new() || phase.is_full() {
+      let distance = calc_body_jd_geo(phase_jd, "mo", 0.0).distance.unwrap_or(0.0);
+      let perigee = perigees.iter().map(|p| p.to_owned()).min_by(|a, b| (a - phase_jd).abs().total_cmp(&(b - phase_jd).abs()));
+      let prev_syzygy_jd = if phase.is_new() { prev_new_jd } else { prev_full_jd };
+      let month = calendar_month(phase_jd, tz);
+      let same_month = prev_syzygy_jd.map(|p_jd| month.len() > 0 && calendar_month(p_jd, tz) == month).unwrap_or(false);
+      phase = phase.with_perigee(distance, perigee).with_month_repeat(same_month);
+      if phase.is_new() {
+        prev_new_jd = Some(phase_jd);
+      } else {
+        prev_full_jd = Some(phase_jd);
+      }
     }
-    next_jd_target += increment;
-    counter += 1;
-    if counter > 500 {
-      break;
+    if phase_jd >= jd {
+      phases.push(phase);
     }
+    prev_jd = Some(phase_jd);
+    ref_jd = phase_jd;
+    index += 1;
   }
-  let days_f64 = nx_jd - prev_jd;
-  let days = if days_f64 < SHORT_LUNAR_MONTH { Some(days_f64 )} else { None };
-  MoonPhase::new(nx_jd, nx_angle, next_phase, days)
+  phases
 }
 
-pub fn calc_moon_phases(jd: f64, geo: GeoPos, cycles: u8) -> Vec<MoonPhase> {
-  let mut phases: Vec<MoonPhase> = Vec::new();
-  let (current_angle, _w, phase, _speed) = calc_sun_moon_angle_and_phase(jd, geo);
-  let next_target_angle = phase as f64 * 90.0;
-  let distance = next_target_angle - current_angle;
-  let min_lunar_month_quarter = SHORT_LUNAR_MONTH / 4.0;
-  let progress = distance / 90.0;
-  let mut start_jd_target = jd + (min_lunar_month_quarter * progress);
-  let next_phase = calc_next_phase(start_jd_target, geo, current_angle, phase, 0.0);
-  let mut phase = next_phase.num;
-  phases.push(next_phase);
-  let min_cycles = if cycles < 1 { 1 } else { cycles } as usize;
-  let num_extra_phases = 3 + ((min_cycles - 1) * 4);
-  
-  for _index in 0..num_extra_phases {
-    let prev_jd = start_jd_target;
-    start_jd_target = prev_jd + min_lunar_month_quarter;
-    let ref_angle = (phase as f64 * 90.0) % 360.0;
-    let next_phase = calc_next_phase(start_jd_target, geo, ref_angle, phase, prev_jd);
-    phase = next_phase.num;
-    start_jd_target = next_phase.jd;
-    phases.push(next_phase);
+/*
+* 1-based lunar month number from the sidereal sign of the Sun at the new moon starting the month, Mina = Chaitra
+*/
+pub fn masa_num(sun_lng: f64) -> u8 {
+  let sign = (sun_lng.rem_euclid(360.0) / 30.0).floor() as u8;
+  (sign + 1) % 12 + 1
+}
+
+/**
+ * Lunar months overlapping the period from jd to end_jd. Months are named after the sidereal sign of the Sun
+ * at the starting new moon. A month without a solar ingress is adhika (intercalary) and shares the name of the next.
+ * Purnimanta months end with the full moon of the amanta month of the same name
+ */
+pub fn calc_lunar_months(jd: f64, end_jd: f64, scheme: MonthScheme, ayanamsha: f64) -> Vec<LunarMonth> {
+  // new moons from before the first month until after the last, the purnimanta month may end half a lunation later
+  let mut new_moons: Vec<(f64, f64)> = Vec::new();
+  let mut ref_jd = jd - MEDIAN_LUNAR_MONTH * 1.5;
+  while ref_jd < end_jd + MEDIAN_LUNAR_MONTH * 1.5 {
+    let new_jd = match calc_next_boundary(ref_jd, 0.0, 360.0) {
+      Some(next_jd) => next_jd,
+      None => break,
+    };
+    new_moons.push((new_jd, calc_body_jd_geo(new_jd, "su", ayanamsha).lng));
+    ref_jd = new_jd + PHASE_SEARCH_STEP;
   }
-  phases
-}
\ No newline at end of file
+  let mut months: Vec<LunarMonth> = Vec::new();
+  for pair in new_moons.windows(2) {
+    let ((start, sun_start), (end, sun_end)) = (pair[0], pair[1]);
+    let num = masa_num(sun_start);
+    let adhika = num == masa_num(sun_end);
+    let bounds = match scheme {
+      MonthScheme::Amanta => Some((start, end)),
+      MonthScheme::Purnimanta => calc_next_boundary(start, 180.0, 180.0)
+        .and_then(|full_jd| calc_next_boundary(full_jd - MEDIAN_LUNAR_MONTH - 1.0, 180.0, 180.0).map(|prev_full_jd| (prev_full_jd, full_jd))),
+    };
+    if let Some((month_start, month_end)) = bounds {
+      if month_end > jd && month_start < end_jd {
+        months.push(LunarMonth::new(num, adhika, month_start, month_end));
+      }
+    }
+  }
+  months
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::calc::core::get_ayanamsha_value;
+  use crate::ephemeris::with_ephemeris;
+
+  #[test]
+  fn test_phase_names() {
+    assert_eq!(PhaseDivision::Quarters.name(3), "full");
+    assert_eq!(PhaseDivision::Octants.name(2), "waxing-crescent");
+    assert_eq!(PhaseDivision::Tithis.name(30), "Krishna Amavasya");
+  }
+
+  #[test]
+  fn test_masa_num() {
+    // new moon with the Sun in Mina starts Chaitra, in Mesha Vaishakha
+    assert_eq!(masa_num(345.0), 1);
+    assert_eq!(masa_num(15.0), 2);
+    assert_eq!(masa_num(-10.0), 1);
+  }
+
+  #[test]
+  fn test_blue_supermoon() {
+    // the second full moon of August 2023 at 01:35 UT on the 31st was also the closest of the year
+    let phases = with_ephemeris(|| calc_moon_phases(2460185.5, 1, PhaseDivision::Quarters, &TimeZoneRef::Fixed(0)));
+    let full = phases.iter().find(|p| p.is_full()).unwrap();
+    assert!((full.jd - 2460187.566).abs() < 2.0 / 1440.0);
+    assert!(full.blue);
+    assert!(full.supermoon);
+  }
+
+  #[test]
+  fn test_adhika_shravana() {
+    // with the Sun in Karka at both new moons, the month from the new moon of 17 July 18:32 UT to 16 August 2023 is Adhika Shravana
+    let months = with_ephemeris(|| {
+      let ayanamsha = get_ayanamsha_value(2460150.5, "lahiri");
+      calc_lunar_months(2460140.5, 2460190.5, MonthScheme::Amanta, ayanamsha)
+    });
+    let adhika: Vec<&LunarMonth> = months.iter().filter(|m| m.adhika).collect();
+    assert_eq!(adhika.len(), 1);
+    assert_eq!(adhika[0].name, "Shravana");
+    assert!((adhika[0].start - 2460143.272).abs() < 0.01);
+    assert!((adhika[0].end - 2460172.901).abs() < 0.01);
+  }
+}
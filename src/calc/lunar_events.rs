@@ -0,0 +1,221 @@
+use serde::{Serialize, Deserialize};
+use utoipa::ToSchema;
+use super::{
+  core::{calc_body_eq_jd, calc_body_jd_geo},
+  dates::julian_day_to_iso_datetime,
+  lunar_cycles::KM_PER_AU,
+  models::graha_pos::GrahaPos,
+  rise_set_phases::CentricMode,
+  search::{find_angle_roots, find_roots},
+};
+
+/*
+* Apsides, node crossings and declination extremes are at least 12 days apart
+*/
+const LUNAR_EVENT_STEP: f64 = 1f64;
+
+/*
+* The mean node moves about 0.05º per day
+*/
+const STANDSTILL_SEARCH_STEP: f64 = 10f64;
+
+pub const MAX_LUNAR_EVENT_DAYS: f64 = 7306f64;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum LunarEventType {
+  Perigee,
+  Apogee,
+  AscendingNode,
+  DescendingNode,
+  MaxNorth,
+  MaxSouth,
+  MajorStandstill,
+  MinorStandstill,
+}
+
+impl LunarEventType {
+  pub fn to_key(&self) -> String {
+    match self {
+      LunarEventType::Perigee => "perigee",
+      LunarEventType::Apogee => "apogee",
+      LunarEventType::AscendingNode => "ascending-node",
+      LunarEventType::DescendingNode => "descending-node",
+      LunarEventType::MaxNorth => "max-north",
+      LunarEventType::MaxSouth => "max-south",
+      LunarEventType::MajorStandstill => "major-standstill",
+      LunarEventType::MinorStandstill => "minor-standstill",
+    }.to_string()
+  }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+pub struct LunarEvent {
+  pub key: String,
+  pub jd: f64,
+  #[serde(skip_serializing_if = "String::is_empty")]
+  pub dt: String,
+  pub lng: f64,
+  pub lat: f64,
+  /* distance in km at apsides */
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub distance: Option<f64>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub declination: Option<f64>,
+}
+
+impl LunarEvent {
+  pub fn new(event_type: LunarEventType, jd: f64, pos: &GrahaPos, iso_mode: bool) -> Self {
+    let dt = if iso_mode { julian_day_to_iso_datetime(jd) } else { "".to_string() };
+    LunarEvent {
+      key: event_type.to_key(),
+      jd,
+      dt,
+      lng: pos.lng,
+      lat: pos.lat,
+      distance: None,
+      declination: None,
+    }
+  }
+
+  pub fn with_distance(mut self, distance: f64) -> Self {
+    self.distance = Some(distance * KM_PER_AU);
+    self
+  }
+
+  pub fn with_declination(mut self, declination: f64) -> Self {
+    self.declination = Some(declination);
+    self
+  }
+}
+
+fn calc_moon_geo(jd: f64) -> GrahaPos {
+  calc_body_jd_geo(jd, "mo", 0f64)
+}
+
+fn calc_moon_eq(jd: f64) -> GrahaPos {
+  calc_body_eq_jd(jd, "mo", CentricMode::Geo)
+}
+
+/*
+* Perigees and apogees where the distance speed changes sign
+*/
+pub fn calc_lunar_apsides(start_jd: f64, end_jd: f64, iso_mode: bool) -> Vec<LunarEvent> {
+  find_roots(start_jd, end_jd, LUNAR_EVENT_STEP, f64::MAX, |jd| calc_moon_geo(jd).distance_speed.unwrap_or(0f64))
+    .into_iter()
+    .map(|jd| {
+      let pos = calc_moon_geo(jd);
+      let receding = calc_moon_geo(jd + LUNAR_EVENT_STEP / 4f64).distance_speed.unwrap_or(0f64) > 0f64;
+      let event_type = if receding { LunarEventType::Perigee } else { LunarEventType::Apogee };
+      LunarEvent::new(event_type, jd, &pos, iso_mode).with_distance(pos.distance.unwrap_or(0f64))
+    })
+    .collect()
+}
+
+/*
+* Crossings of the ecliptic, northwards at the ascending node and southwards at the descending node
+*/
+pub fn calc_lunar_node_crossings(start_jd: f64, end_jd: f64, iso_mode: bool) -> Vec<LunarEvent> {
+  find_roots(start_jd, end_jd, LUNAR_EVENT_STEP, f64::MAX, |jd| calc_moon_geo(jd).lat)
+    .into_iter()
+    .map(|jd| {
+      let pos = calc_moon_geo(jd);
+      let event_type = if pos.lat_speed > 0f64 { LunarEventType::AscendingNode } else { LunarEventType::DescendingNode };
+      LunarEvent::new(event_type, jd, &pos, iso_mode)
+    })
+    .collect()
+}
+
+/*
+* Monthly extremes of declination where the declination speed changes sign
+*/
+pub fn calc_lunar_declination_extremes(start_jd: f64, end_jd: f64, iso_mode: bool) -> Vec<LunarEvent> {
+  find_roots(start_jd, end_jd, LUNAR_EVENT_STEP, f64::MAX, |jd| calc_moon_eq(jd).lat_speed_eq)
+    .into_iter()
+    .map(|jd| {
+      let declination = calc_moon_eq(jd).declination;
+      let event_type = if declination > 0f64 { LunarEventType::MaxNorth } else { LunarEventType::MaxSouth };
+      LunarEvent::new(event_type, jd, &calc_moon_geo(jd), iso_mode).with_declination(declination)
+    })
+    .collect()
+}
+
+/**
+ * Major lunar standstills, when the mean ascending node reaches 0º Aries and the monthly declination range is widest,
+ * and minor standstills at 0º Libra when it is narrowest, about 9.3 years apart
+ */
+pub fn calc_lunar_standstills(start_jd: f64, end_jd: f64, iso_mode: bool) -> Vec<LunarEvent> {
+  let mut items: Vec<LunarEvent> = Vec::new();
+  for (event_type, node_lng) in [(LunarEventType::MajorStandstill, 0f64), (LunarEventType::MinorStandstill, 180f64)] {
+    for jd in find_angle_roots(start_jd, end_jd, STANDSTILL_SEARCH_STEP, |jd| calc_body_jd_geo(jd, "mn", 0f64).lng - node_lng) {
+      items.push(LunarEvent::new(event_type, jd, &calc_moon_geo(jd), iso_mode));
+    }
+  }
+  items
+}
+
+/**
+ * All lunar apsides, node crossings, declination extremes and standstills between two dates in chronological order
+ */
+pub fn calc_lunar_events(start_jd: f64, end_jd: f64, iso_mode: bool) -> Vec<LunarEvent> {
+  let mut items = calc_lunar_apsides(start_jd, end_jd, iso_mode);
+  items.extend(calc_lunar_node_crossings(start_jd, end_jd, iso_mode));
+  items.extend(calc_lunar_declination_extremes(start_jd, end_jd, iso_mode));
+  items.extend(calc_lunar_standstills(start_jd, end_jd, iso_mode));
+  items.sort_by(|a, b| a.jd.partial_cmp(&b.jd).unwrap_or(std::cmp::Ordering::Equal));
+  items
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::ephemeris::with_ephemeris;
+
+  /*
+  * 1 January to 1 February 2024
+  */
+  fn calc_january_events() -> Vec<LunarEvent> {
+    with_ephemeris(|| calc_lunar_events(2460310.5, 2460341.5, false))
+  }
+
+  fn find_event(items: &Vec<LunarEvent>, key: &str) -> LunarEvent {
+    items.iter().find(|item| item.key == key).unwrap().clone()
+  }
+
+  #[test]
+  fn test_lunar_apsides() {
+    // apogee on 1 January 2024 at 15:29 UT (404,909 km) and perigee on 13 January at 10:35 UT (362,267 km)
+    let items = calc_january_events();
+    let apogee = find_event(&items, "apogee");
+    assert!((apogee.jd - 2460311.1451).abs() < 0.01);
+    assert!((apogee.distance.unwrap() - 404909f64).abs() < 100f64);
+    let perigee = find_event(&items, "perigee");
+    assert!((perigee.jd - 2460322.9410).abs() < 0.01);
+    assert!((perigee.distance.unwrap() - 362267f64).abs() < 100f64);
+    assert!(items.windows(2).all(|pair| pair[0].jd <= pair[1].jd));
+  }
+
+  #[test]
+  fn test_lunar_nodes_declinations() {
+    let items = calc_january_events();
+    let ascending = find_event(&items, "ascending-node");
+    assert!(ascending.lat.abs() < 0.0001);
+    assert_eq!(items.iter().filter(|item| item.key == "descending-node").count(), 2);
+    // close to the major standstill the Moon reaches declinations of about ±28º
+    let max_south = find_event(&items, "max-south");
+    assert!((max_south.declination.unwrap() + 28.18).abs() < 0.05);
+    let max_north = find_event(&items, "max-north");
+    assert!((max_north.declination.unwrap() - 28.22).abs() < 0.05);
+    assert!(max_north.jd > ascending.jd && max_north.jd - ascending.jd < 7f64);
+  }
+
+  #[test]
+  fn test_lunar_standstills() {
+    // the mean node reaches 0º Aries in late January 2025 and 0º Libra in 2034, 9.3 years later
+    let items = with_ephemeris(|| calc_lunar_standstills(2460310.5, 2464329.5, false));
+    assert_eq!(items.len(), 2);
+    let major = find_event(&items, "major-standstill");
+    assert!((major.jd - 2460704.78).abs() < 1f64);
+    let minor = find_event(&items, "minor-standstill");
+    assert!(((minor.jd - major.jd) / 365.25 - 9.3).abs() < 0.1);
+  }
+}
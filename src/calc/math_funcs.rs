@@ -4,7 +4,7 @@ pub fn calc_opposite(lng: f64) -> f64 {
 
 pub fn adjust_lng_by_body_key(key: &str, lng: f64) -> f64 {
   match key {
-    "ke" => calc_opposite(lng),
+    "ke" | "sn" => calc_opposite(lng),
     _ => lng,
   }
 }
@@ -17,6 +17,14 @@ pub fn normalize_360(lng: f64) -> f64 {
   (lng + 360f64) % 360f64
 }
 
+/*
+* Normalise any angular difference to the range -180º to +180º
+*/
+pub fn signed_angle_180(diff: f64) -> f64 {
+  let deg = diff.rem_euclid(360f64);
+  if deg > 180f64 { deg - 360f64 } else { deg }
+}
+
 pub fn normalize_f64(lng: f64, base: i16) -> f64 {
   let base_f64 = base as f64;
   (lng + base_f64) % base_f64
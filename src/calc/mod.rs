@@ -9,4 +9,18 @@ pub mod utils;
 pub mod traits;
 pub mod math_funcs;
 pub mod planet_stations;
-pub mod lunar_cycles;
\ No newline at end of file
+pub mod lunar_cycles;
+pub mod aspects;
+pub mod search;
+pub mod transits;
+pub mod ingresses;
+pub mod eclipses;
+pub mod stars;
+pub mod errors;
+pub mod dashas;
+pub mod vargas;
+pub mod panchanga;
+pub mod timezones;
+pub mod progressions;
+pub mod lunar_events;
+pub mod seasons;
\ No newline at end of file
@@ -1,8 +1,9 @@
 use julian_day_converter::*;
 use serde::{Serialize, Deserialize};
-use crate::calc::dates::*;
+use utoipa::ToSchema;
+use crate::calc::{dates::*, errors::CalcError};
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
 pub struct DateInfo {
   pub utc: String,
   pub jd: f64,
@@ -10,13 +11,13 @@ pub struct DateInfo {
 }
 
 impl DateInfo {
-    pub fn new(dateref: &str) -> DateInfo {
-        let dt = iso_string_to_datetime(dateref);
-        DateInfo {
+    pub fn new(dateref: &str) -> Result<DateInfo, CalcError> {
+        let dt = iso_string_to_datetime(dateref)?;
+        Ok(DateInfo {
             utc: dt.format("%Y-%m-%dT%H:%M:%S").to_string(),
              jd: dt.to_jd(),
              unix: dt.timestamp()
-         }
+         })
     }
 
     pub fn new_from_jd(jd: f64) -> DateInfo {
@@ -28,6 +29,17 @@ impl DateInfo {
       }
     }
 
+    /*
+    * Only julian days that can be converted to a calendar date are valid
+    */
+    pub fn try_from_jd(jd: f64) -> Result<DateInfo, CalcError> {
+      if julian_day_to_datetime(jd).is_ok() {
+        Ok(DateInfo::new_from_jd(jd))
+      } else {
+        Err(CalcError::InvalidDate(jd.to_string()))
+      }
+    }
+
     pub fn now() -> DateInfo {
         let dt = current_datetime().unwrap();
         DateInfo {
@@ -1,8 +1,9 @@
 use serde::{Serialize, Deserialize};
+use utoipa::ToSchema;
 use crate::calc::dates::julian_day_to_iso_datetime;
 use crate::calc::traits::{MatchVecKey, AddKeyedItem};
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, PartialOrd)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, PartialOrd, ToSchema)]
 pub struct KeyNumValue {
   pub key: String,
   pub value: f64,
@@ -33,7 +34,7 @@ impl KeyNumValue {
 }
 
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, PartialOrd)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, PartialOrd, ToSchema)]
 pub struct KeyNumIdValue {
   pub key: String,
   pub num: u8,
@@ -60,7 +61,7 @@ impl MatchVecKey<KeyNumValue> for Vec<KeyNumValue> {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
 pub struct KeyStringValue {
   pub key: String,
   pub value: String,
@@ -124,7 +125,7 @@ pub struct NumValueKeySet {
  * Used for celestial objects
  */
 
-#[derive(Serialize, Deserialize, Debug, Copy, Clone)]
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, ToSchema)]
 pub struct LngLat {
   pub lng: f64,
   pub lat: f64,
@@ -140,7 +141,7 @@ pub trait ToLngLat {
   fn to_lng_lat(&self) -> LngLat;
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
 pub struct LngLatKey {
   pub lng: f64,
   pub lat: f64,
@@ -166,7 +167,7 @@ pub enum FlexiValueSet {
   FlexiValues(Vec<KeyFlexiValueSet>),
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
 #[serde(untagged)]
 pub enum FlexiValue {
   NumValue(KeyNumValue),
@@ -190,7 +191,7 @@ pub fn extract_flexi_value_string(items: &Vec<FlexiValue>, key: &str) -> Option<
   }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
 pub struct KeyFlexiValueSet {
   pub key: String,
   pub items: Vec<FlexiValue>,
@@ -209,7 +210,7 @@ pub enum TimeMode {
   Unix = 2,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
 pub struct SunPeriod {
   pub jd: f64,
   pub start: f64,
@@ -223,21 +224,69 @@ impl SunPeriod {
   }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
 pub enum CoordinateSystem {
   Ecliptic = 0,
   Equatorial = 1,
   Dual = 2, // Show Ecliptic and Equatorial (and in the full chart mode horizontal too)
-  Horizontal = 3
+  Horizontal = 3,
+  Rectangular = 5, // Ecliptic XYZ coordinates in AU
+  RectangularEq = 6, // Equatorial XYZ coordinates in AU
 }
 
 impl CoordinateSystem {
+  pub fn from_u8(num: u8) -> Option<Self> {
+    match num {
+      0 => Some(CoordinateSystem::Ecliptic),
+      1 => Some(CoordinateSystem::Equatorial),
+      2 => Some(CoordinateSystem::Dual),
+      3 => Some(CoordinateSystem::Horizontal),
+      5 => Some(CoordinateSystem::Rectangular),
+      6 => Some(CoordinateSystem::RectangularEq),
+      _ => None,
+    }
+  }
+
+  pub fn is_rectangular(&self) -> bool {
+    match self {
+      CoordinateSystem::Rectangular | CoordinateSystem::RectangularEq => true,
+      _ => false,
+    }
+  }
+
   pub fn to_key(&self) -> String {
     match self {
+      CoordinateSystem::RectangularEq => "xyzeq",
+      CoordinateSystem::Rectangular => "xyz",
       CoordinateSystem::Horizontal => "hr",
       CoordinateSystem::Dual => "dl",
       CoordinateSystem::Equatorial => "eq",
       _ => "ec"
     }.to_string()
   }
-}
\ No newline at end of file
+}
+
+/*
+* Observer position: centre of the earth, the surface location or both with topocentric variants
+*/
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum TopoMode {
+  Geocentric = 0,
+  Topocentric = 1,
+  Both = 2,
+}
+
+impl TopoMode {
+  pub fn from_u8(num: u8) -> Option<Self> {
+    match num {
+      0 => Some(TopoMode::Geocentric),
+      1 => Some(TopoMode::Topocentric),
+      2 => Some(TopoMode::Both),
+      _ => None,
+    }
+  }
+
+  pub fn is_topo(&self) -> bool {
+    *self == TopoMode::Topocentric
+  }
+}
@@ -1,8 +1,9 @@
 use serde::{Serialize, Deserialize};
+use utoipa::ToSchema;
 
 const DEFAULT_ALTITUDE: f64 = 10f64;
 
-#[derive(Serialize, Deserialize, Debug, Copy, Clone)]
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, ToSchema)]
 pub struct GeoPos {
   pub lat: f64,
   pub lng: f64,
@@ -2,8 +2,9 @@ use crate::calc::{dates::*, traits::*};
 use crate::calc::models::general::{LngLat, LngLatKey, ToLngLat, ToLngLatKey, CoordinateSystem};
 use libswe_sys::swerust::handler_swe07::PhenoUtResult;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
 pub struct BodyPos {
   pub key: String,
   pub lng: f64,
@@ -12,6 +13,10 @@ pub struct BodyPos {
   pub lng_speed: f64,
   #[serde(rename = "latSpeed")]
   pub lat_speed: f64,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub distance: Option<f64>,
+  #[serde(rename = "distanceSpeed", skip_serializing_if = "Option::is_none")]
+  pub distance_speed: Option<f64>,
   #[serde(skip_serializing)]
   pub mode: String,
 }
@@ -25,6 +30,8 @@ impl BodyPos {
       lat: lat,
       lng_speed: lng_speed,
       lat_speed: lat_speed,
+      distance: None,
+      distance_speed: None,
     }
   }
 
@@ -36,9 +43,17 @@ impl BodyPos {
       lat: 0f64,
       lng_speed: 0f64,
       lat_speed: 0f64,
+      distance: None,
+      distance_speed: None,
     }
   }
 
+  pub fn with_distance(mut self, distance: f64, distance_speed: f64) -> Self {
+    self.distance = Some(distance);
+    self.distance_speed = Some(distance_speed);
+    self
+  }
+
 }
 
 impl ToLngLat for BodyPos {
@@ -60,7 +75,7 @@ impl ToLngLatKey for BodyPos {
   }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
 pub struct PhenoResult {
   #[serde(rename = "phaseAngle")]
   pub phase_angle: f64,
@@ -87,7 +102,7 @@ impl PhenoResult {
   }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
 pub struct PhenoItem {
   pub key: String,
   #[serde(rename = "phaseAngle")]
@@ -116,7 +131,38 @@ impl PhenoItem {
   }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+/*
+* Rectangular ecliptic or equatorial coordinates in AU with daily speeds, e.g. for 3D visualisation
+*/
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+pub struct XyzPos {
+  pub key: String,
+  pub x: f64,
+  pub y: f64,
+  pub z: f64,
+  #[serde(rename = "xSpeed")]
+  pub x_speed: f64,
+  #[serde(rename = "ySpeed")]
+  pub y_speed: f64,
+  #[serde(rename = "zSpeed")]
+  pub z_speed: f64,
+}
+
+impl XyzPos {
+  pub fn new(key: &str, xx: [f64; 6]) -> Self {
+    XyzPos {
+      key: key.to_string(),
+      x: xx[0],
+      y: xx[1],
+      z: xx[2],
+      x_speed: xx[3],
+      y_speed: xx[4],
+      z_speed: xx[5],
+    }
+  }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
 pub struct GrahaPos {
   pub key: String,
   pub lng: f64,
@@ -133,6 +179,10 @@ pub struct GrahaPos {
   #[serde(rename = "latSpeedEq")]
   pub lat_speed_eq: f64,
   #[serde(skip_serializing_if = "Option::is_none")]
+  pub distance: Option<f64>,
+  #[serde(rename = "distanceSpeed", skip_serializing_if = "Option::is_none")]
+  pub distance_speed: Option<f64>,
+  #[serde(skip_serializing_if = "Option::is_none")]
   pheno: Option<PhenoResult>,
   #[serde(skip_serializing_if = "Option::is_none")]
   altitude: Option<f64>,
@@ -156,6 +206,8 @@ impl GrahaPos {
       declination: 0f64,
       lng_speed_eq: 0f64,
       lat_speed_eq: 0f64,
+      distance: None,
+      distance_speed: None,
       pheno: None,
       altitude: None,
       azimuth: None,
@@ -185,6 +237,8 @@ impl GrahaPos {
       declination,
       lng_speed_eq: lng_speed,
       lat_speed_eq: lat_speed,
+      distance: None,
+      distance_speed: None,
       pheno: None,
       altitude: None,
       azimuth: None,
@@ -215,12 +269,24 @@ impl GrahaPos {
       declination,
       lng_speed_eq,
       lat_speed_eq,
+      distance: None,
+      distance_speed: None,
       pheno,
       altitude,
       azimuth,
     }
   }
 
+  /**
+   * Distance from the centre of the earth, the observer, the sun or the solar system barycentre in AU
+   * with its daily change
+   */
+  pub fn with_distance(mut self, distance: f64, distance_speed: f64) -> Self {
+    self.distance = Some(distance);
+    self.distance_speed = Some(distance_speed);
+    self
+  }
+
   pub fn to_body(&self, mode: CoordinateSystem) -> BodyPos {
     let lng = match mode {
       CoordinateSystem::Equatorial => self.rect_ascension,
@@ -240,7 +306,10 @@ impl GrahaPos {
       CoordinateSystem::Equatorial => self.lat_speed_eq,
       _ => self.lat_speed,
     };
-    BodyPos::new(self.key.as_str(), mode, lng, lat, lng_speed, lat_speed)
+    let mut body = BodyPos::new(self.key.as_str(), mode, lng, lat, lng_speed, lat_speed);
+    body.distance = self.distance;
+    body.distance_speed = self.distance_speed;
+    body
   }
 }
 
@@ -292,35 +361,47 @@ pub struct GrahaPosSet {
   pub jd: f64,
   #[serde(skip_serializing_if = "String::is_empty")]
   pub dt: String,
-  pub bodies: Vec<BodyPos>,
+  pub bodies: FlexiBodyPos,
 }
 
 impl GrahaPosSet {
   pub fn new(jd: f64, bodies: Vec<GrahaPos>, mode: CoordinateSystem, iso: bool) -> GrahaPosSet {
-    let dt = if iso {
-      julian_day_to_iso_datetime(jd)
-    } else {
-      "".to_string()
-    };
     GrahaPosSet {
       jd,
-      dt,
-      bodies: bodies.into_iter().map(|g| g.to_body(mode)).collect(),
+      dt: build_iso_dt(jd, iso),
+      bodies: FlexiBodyPos::Simple(bodies.into_iter().map(|g| g.to_body(mode)).collect()),
+    }
+  }
+
+  pub fn new_rectangular(jd: f64, bodies: Vec<XyzPos>, iso: bool) -> GrahaPosSet {
+    GrahaPosSet {
+      jd,
+      dt: build_iso_dt(jd, iso),
+      bodies: FlexiBodyPos::Rectangular(bodies),
     }
   }
 }
 
+fn build_iso_dt(jd: f64, iso: bool) -> String {
+  if iso {
+    julian_day_to_iso_datetime(jd)
+  } else {
+    "".to_string()
+  }
+}
+
 impl ToISODateString for GrahaPosSet {
   fn iso_date_string(&self) -> String {
     julian_day_to_iso_datetime(self.jd)
   }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
 #[serde(untagged)]
 pub enum FlexiBodyPos {
   LngLat(Vec<LngLat>),
   LngLatKey(Vec<LngLatKey>),
   Simple(Vec<BodyPos>),
   Extended(Vec<GrahaPos>),
+  Rectangular(Vec<XyzPos>),
 }
@@ -5,8 +5,9 @@ use crate::calc::{
 use super::geo_pos::*;
 use libswe_sys::swerust::handler_swe14::*;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, ToSchema)]
 pub struct AscMc {
   pub ascendant: f64,
   pub mc: f64,
@@ -137,8 +138,9 @@ impl HouseData {
 
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
 pub struct HouseSet {
+  #[schema(value_type = String)]
   pub system: char,
   pub houses: Vec<f64>,
 }
@@ -153,7 +155,7 @@ impl HouseSet {
   } */
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
 pub struct HouseSetData {
   pub points: AscMc,
   pub sets: Vec<HouseSet>,
@@ -0,0 +1,236 @@
+use serde::{Serialize, Deserialize};
+use libswe_sys::sweconst::Bodies;
+use super::{
+  core::calc_body_jd_geo,
+  dates::julian_day_to_iso_datetime,
+  models::{general::{FlexiValue, KeyNumValue}, geo_pos::GeoPos},
+  rise_set_phases::{next_rise, next_set, start_jd_geo, TransitionMode},
+  search::find_angle_roots,
+  settings::{nakshatras::{nakshatra_name, NAKSHATRA_SPAN}, panchanga::*},
+};
+
+/*
+* Longest tithi or nakshatra is less than 27 hours, so the end of any element is found within 2 days
+*/
+const PANCHANGA_SEARCH_DAYS: f64 = 2f64;
+const PANCHANGA_SEARCH_STEP: f64 = 0.25f64;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum PanchangaElement {
+  Tithi,
+  Nakshatra,
+  Yoga,
+  Karana,
+}
+
+impl PanchangaElement {
+  pub fn span(&self) -> f64 {
+    match self {
+      PanchangaElement::Tithi => 12f64,
+      PanchangaElement::Nakshatra | PanchangaElement::Yoga => NAKSHATRA_SPAN,
+      PanchangaElement::Karana => 6f64,
+    }
+  }
+
+  /*
+  * Tithis and karanas depend on the Moon's elongation, nakshatras on the sidereal Moon
+  * and yogas on the sum of the sidereal Sun and Moon
+  */
+  pub fn angle(&self, sun_lng: f64, moon_lng: f64) -> f64 {
+    match self {
+      PanchangaElement::Tithi | PanchangaElement::Karana => moon_lng - sun_lng,
+      PanchangaElement::Nakshatra => moon_lng,
+      PanchangaElement::Yoga => sun_lng + moon_lng,
+    }.rem_euclid(360f64)
+  }
+
+  pub fn num(&self, angle: f64) -> u16 {
+    (angle.rem_euclid(360f64) / self.span()).floor() as u16 + 1
+  }
+
+  pub fn name(&self, num: u16) -> String {
+    match self {
+      PanchangaElement::Tithi => tithi_name(num),
+      PanchangaElement::Nakshatra => nakshatra_name(num),
+      PanchangaElement::Yoga => yoga_name(num),
+      PanchangaElement::Karana => karana_name(num),
+    }
+  }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PanchangaItem {
+  pub num: u16,
+  pub name: String,
+  pub start: f64,
+  pub end: f64,
+  #[serde(rename = "startDt", skip_serializing_if = "String::is_empty")]
+  pub start_dt: String,
+  #[serde(rename = "endDt", skip_serializing_if = "String::is_empty")]
+  pub end_dt: String,
+}
+
+impl PanchangaItem {
+  pub fn new(element: PanchangaElement, num: u16, start: f64, end: f64, iso_mode: bool) -> Self {
+    let (start_dt, end_dt) = if iso_mode { (julian_day_to_iso_datetime(start), julian_day_to_iso_datetime(end)) } else { ("".to_string(), "".to_string()) };
+    PanchangaItem {
+      num,
+      name: element.name(num),
+      start,
+      end,
+      start_dt,
+      end_dt,
+    }
+  }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Vara {
+  pub num: u8,
+  pub name: String,
+  pub lord: String,
+}
+
+impl Vara {
+  /*
+  * Weekday of the local solar date, 1 = Sunday
+  */
+  pub fn new(jd: f64, lng: f64) -> Self {
+    let local_jd = jd + lng / 360f64;
+    let index = ((local_jd + 1.5f64).floor() as i64).rem_euclid(7) as usize;
+    let (name, lord) = VARA_NAMES[index];
+    Vara { num: index as u8 + 1, name: name.to_string(), lord: lord.to_string() }
+  }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PanchangaDay {
+  pub sun: Vec<FlexiValue>,
+  pub vara: Vara,
+  pub tithi: Vec<PanchangaItem>,
+  pub nakshatra: Vec<PanchangaItem>,
+  pub yoga: Vec<PanchangaItem>,
+  pub karana: Vec<PanchangaItem>,
+}
+
+fn calc_sun_moon_sidereal(jd: f64, ayanamsha: f64) -> (f64, f64) {
+  let sun = calc_body_jd_geo(jd, "su", ayanamsha);
+  let moon = calc_body_jd_geo(jd, "mo", ayanamsha);
+  (sun.lng, moon.lng)
+}
+
+fn calc_element_angle(element: PanchangaElement, jd: f64, ayanamsha: f64) -> f64 {
+  let (sun_lng, moon_lng) = calc_sun_moon_sidereal(jd, ayanamsha);
+  element.angle(sun_lng, moon_lng)
+}
+
+/*
+* Exact time the element angle reaches the boundary, searching forwards or backwards from jd
+*/
+fn calc_boundary_jd(element: PanchangaElement, boundary: f64, jd: f64, ayanamsha: f64, forwards: bool) -> f64 {
+  let (start, end) = if forwards { (jd, jd + PANCHANGA_SEARCH_DAYS) } else { (jd - PANCHANGA_SEARCH_DAYS, jd) };
+  let roots = find_angle_roots(start, end, PANCHANGA_SEARCH_STEP, |ref_jd| calc_element_angle(element, ref_jd, ayanamsha) - boundary);
+  let root = if forwards { roots.first() } else { roots.last() };
+  root.map(|r| r.to_owned()).unwrap_or(0f64)
+}
+
+/**
+ * Elements running between the start and end of the Vedic day, beginning with the one current at sunrise.
+ * The first may have started before sunrise and the last may end after the next sunrise
+ */
+pub fn calc_element_sequence(element: PanchangaElement, start_jd: f64, end_jd: f64, ayanamsha: f64, iso_mode: bool) -> Vec<PanchangaItem> {
+  let mut items: Vec<PanchangaItem> = Vec::new();
+  let span = element.span();
+  let mut num = element.num(calc_element_angle(element, start_jd, ayanamsha));
+  let mut item_start = calc_boundary_jd(element, (num - 1) as f64 * span, start_jd, ayanamsha, false);
+  let max_items = (360f64 / span) as u16;
+  while item_start < end_jd {
+    let item_end = calc_boundary_jd(element, num as f64 * span, start_jd.max(item_start), ayanamsha, true);
+    items.push(PanchangaItem::new(element, num, item_start, item_end, iso_mode));
+    if item_end <= item_start {
+      break;
+    }
+    item_start = item_end;
+    num = (num % max_items) + 1;
+  }
+  items
+}
+
+/*
+* Sunrise that starts the Vedic day containing jd. Near the poles, where the sun may not rise, local midnight is used
+*/
+pub fn calc_vedic_day_start(jd: f64, geo: GeoPos, mode: TransitionMode) -> f64 {
+  let ref_jd = start_jd_geo(jd, geo.lng);
+  let rise = next_rise(ref_jd, Bodies::Sun, geo.lat, geo.lng, mode);
+  if rise > ref_jd && rise <= jd {
+    rise
+  } else {
+    let prev_rise = next_rise(ref_jd - 1f64, Bodies::Sun, geo.lat, geo.lng, mode);
+    if prev_rise > ref_jd - 1f64 && prev_rise <= jd { prev_rise } else { ref_jd }
+  }
+}
+
+pub fn calc_next_sunrise(sunrise: f64, geo: GeoPos, mode: TransitionMode) -> f64 {
+  let next_rise_jd = next_rise(sunrise + 0.5f64, Bodies::Sun, geo.lat, geo.lng, mode);
+  if next_rise_jd > sunrise { next_rise_jd } else { sunrise + 1f64 }
+}
+
+/**
+ * Tithi, nakshatra, yoga and karana with exact start and end times, and the weekday of the Vedic day from sunrise to sunrise
+ */
+pub fn calc_panchanga_day(sunrise: f64, next_sunrise: f64, geo: GeoPos, ayanamsha: f64, mode: TransitionMode, iso_mode: bool) -> PanchangaDay {
+  let sunset = next_set(sunrise, Bodies::Sun, geo.lat, geo.lng, mode);
+  let mut sun = vec![KeyNumValue::new("rise", sunrise).as_flexi_value(iso_mode)];
+  if sunset > sunrise && sunset < next_sunrise {
+    sun.push(KeyNumValue::new("set", sunset).as_flexi_value(iso_mode));
+  }
+  sun.push(KeyNumValue::new("nextRise", next_sunrise).as_flexi_value(iso_mode));
+  PanchangaDay {
+    sun,
+    vara: Vara::new(sunrise, geo.lng),
+    tithi: calc_element_sequence(PanchangaElement::Tithi, sunrise, next_sunrise, ayanamsha, iso_mode),
+    nakshatra: calc_element_sequence(PanchangaElement::Nakshatra, sunrise, next_sunrise, ayanamsha, iso_mode),
+    yoga: calc_element_sequence(PanchangaElement::Yoga, sunrise, next_sunrise, ayanamsha, iso_mode),
+    karana: calc_element_sequence(PanchangaElement::Karana, sunrise, next_sunrise, ayanamsha, iso_mode),
+  }
+}
+
+/*
+* Consecutive Vedic days from the one containing jd
+*/
+pub fn calc_panchanga_days(jd: f64, geo: GeoPos, days: u16, ayanamsha: f64, mode: TransitionMode, iso_mode: bool) -> Vec<PanchangaDay> {
+  let mut items: Vec<PanchangaDay> = Vec::new();
+  let mut sunrise = calc_vedic_day_start(jd, geo, mode);
+  for _ in 0..days {
+    let next_sunrise = calc_next_sunrise(sunrise, geo, mode);
+    items.push(calc_panchanga_day(sunrise, next_sunrise, geo, ayanamsha, mode, iso_mode));
+    sunrise = next_sunrise;
+  }
+  items
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_element_numbers() {
+    // the Moon 100º ahead of the Sun is in the 9th tithi and 17th karana
+    assert_eq!(PanchangaElement::Tithi.num(PanchangaElement::Tithi.angle(20f64, 120f64)), 9);
+    assert_eq!(PanchangaElement::Karana.num(PanchangaElement::Karana.angle(20f64, 120f64)), 17);
+    assert_eq!(PanchangaElement::Yoga.num(PanchangaElement::Yoga.angle(350f64, 20f64)), 1);
+  }
+
+  #[test]
+  fn test_names() {
+    assert_eq!(tithi_name(15), "Shukla Purnima");
+    assert_eq!(tithi_name(16), "Krishna Pratipada");
+    assert_eq!(karana_name(57), "Vishti");
+  }
+
+  #[test]
+  fn test_vara() {
+    // 2000-01-01 was a Saturday
+    assert_eq!(Vara::new(2451545f64, 0f64).lord, "sa");
+  }
+}
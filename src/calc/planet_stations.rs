@@ -1,12 +1,43 @@
 use serde::{Serialize, Deserialize};
-use super::{data::body_speeds::*, dates::julian_day_to_iso_datetime};
+use utoipa::ToSchema;
+use libswe_sys::sweconst::OptionalFlag;
+use super::{
+	core::{calc_body_jd, calc_ut_by_key},
+	data::body_speeds::*,
+	dates::julian_day_to_iso_datetime,
+	rise_set_phases::CentricMode,
+	search::{body_search_step, find_roots},
+	transits::{calc_longitude_transits, AyanamshaRange},
+};
+
+/*
+* Half interval in days of the central difference used to derive the change in speed
+*/
+const SPEED_DELTA_DAYS: f64 = 0.05;
+
+/*
+* Number of stations or peaks shown before and after a reference date
+*/
+const NUM_NEXTPREV_STATIONS: usize = 4;
+
+/*
+* Maximum range in days of calculated stations, about 50 years
+*/
+pub const MAX_STATION_DAYS: f64 = 18263f64;
+
+/*
+* Longest retrograde period of any planet with a margin, so stations just outside a range can complete a cycle
+*/
+const MAX_RETRO_DAYS: f64 = 200f64;
 
 pub enum PlanetStation {
 	RetroStart = 0,
 	RetroPeak = 1,
 	RetroEnd = 2,
 	Peak = 3,
-	Sample = 4
+	Sample = 4,
+	ShadowStart = 5,
+	ShadowEnd = 6,
 }
 
 impl PlanetStation {
@@ -16,12 +47,16 @@ impl PlanetStation {
 			1 => PlanetStation::RetroPeak,
 			2 => PlanetStation::RetroEnd,
 			3 => PlanetStation::Peak,
+			5 => PlanetStation::ShadowStart,
+			6 => PlanetStation::ShadowEnd,
 			_ => PlanetStation::Sample,
 		}
 	}
 
 	fn as_string(&self) -> String {
 		match self {
+			PlanetStation::ShadowStart => "shadow-start",
+			PlanetStation::ShadowEnd => "shadow-end",
 			PlanetStation::RetroStart => "retro-start",
 			PlanetStation::RetroPeak => "retro-peak",
 			PlanetStation::RetroEnd => "retro-end",
@@ -31,7 +66,7 @@ impl PlanetStation {
 	}
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
 pub struct PlanetSample {
 	pub jd: f64,
 	#[serde(skip_serializing_if = "String::is_empty")]
@@ -74,7 +109,7 @@ impl BodySpeed {
 }
 
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
 pub struct BodySpeedSet {
 	pub key: String,
 	pub stations: Vec<PlanetSample>
@@ -89,52 +124,185 @@ impl BodySpeedSet {
 	}
 }
 
-pub fn match_planet_stations_range(key: &str, ref_jd: f64, end_jd: f64) -> Vec<BodySpeed> {
-    let mut items:Vec<BodySpeed> = vec![];
-	let ref_rows:Vec<(f64, f64, f64, u8)> = match key {
-        "me" => PLANETARY_STATIONS_ME,
-        "ve" => PLANETARY_STATIONS_VE,
-        "ma" => PLANETARY_STATIONS_MA,
-        "ju" => PLANETARY_STATIONS_JU,
-        "sa" => PLANETARY_STATIONS_SA,
-        "ur" => PLANETARY_STATIONS_UR,
-        "ne" => PLANETARY_STATIONS_NE,
-				"pl" => PLANETARY_STATIONS_PL,
-        _ => PLANETARY_STATIONS_EA,
-    }.iter().cloned().collect();
-		// NB data sources are in descending chronological order
-    let num_rows = ref_rows.len();
-			if ref_rows.len() > 0 {
-			let target_jd = if end_jd < ref_jd { ref_jd } else { end_jd };
-			let index_match = ref_rows.clone().into_iter().position(|r| r.0 <= target_jd);
-			if let Some(index) = index_match {
-					let start_pos = index as i32 - 4i32;
-					
-					let start_index = if start_pos < 0 { 0usize } else { start_pos as usize };
-					let mut end_index = index + 4;
-					if end_jd > ref_jd {
-						let end_index_match = ref_rows.clone().into_iter().position(|r| r.0 < ref_jd);
-						if let Some(end_im) = end_index_match {
-							end_index = end_im;
-						} else {
-							end_index = num_rows - 1;
-						}
-					}
-					if end_index >= num_rows {
-							end_index = num_rows - 1;
-					}
-					for i in start_index..end_index {
-							if let Some(row) = ref_rows.get(i) {
-									items.push(BodySpeed::new(key, row.0, row.1, row.2, row.3));
-							}
-					}
+/*
+* Span in days before and after a reference date containing at least four stations or peaks
+*/
+fn station_search_span(key: &str) -> f64 {
+	match key {
+		"me" | "mo" => 130f64,
+		"ve" => 600f64,
+		"ma" => 800f64,
+		"su" => 1470f64,
+		"ra" | "ke" | "mn" | "sn" => 60f64,
+		_ => 400f64,
+	}
+}
+
+/*
+* Period in days between consecutive peaks of the same kind: the anomalistic month and year for the moon and sun,
+* otherwise the synodic period. The mean node moves at a near constant rate and has no peaks
+*/
+fn peak_cycle_days(key: &str) -> Option<f64> {
+	match key {
+		"mo" => Some(27.55),
+		"su" => Some(365.26),
+		"me" => Some(116f64),
+		"ve" => Some(584f64),
+		"ma" => Some(780f64),
+		"ju" => Some(399f64),
+		"ra" | "ke" | "sn" => Some(30f64),
+		"mn" => None,
+		_ => Some(400f64),
+	}
+}
+
+/*
+* Bodies outside the range of the available ephemeris files, e.g. Chiron without its asteroid file, cannot be calculated
+*/
+fn can_calc_body(key: &str, jd: f64) -> bool {
+	calc_ut_by_key(jd, key, OptionalFlag::SwissEph as i32 | OptionalFlag::Speed as i32).is_valid()
+}
+
+fn calc_lng_speed(key: &str, jd: f64) -> (f64, f64) {
+	let pos = calc_body_jd(jd, key, false, CentricMode::Geo, 0f64);
+	(pos.lng, pos.lng_speed)
+}
+
+/*
+* Daily change in longitude speed. Peak speeds occur where it crosses zero
+*/
+fn calc_lng_acceleration(key: &str, jd: f64) -> f64 {
+	(calc_lng_speed(key, jd + SPEED_DELTA_DAYS).1 - calc_lng_speed(key, jd - SPEED_DELTA_DAYS).1) / (2f64 * SPEED_DELTA_DAYS)
+}
+
+fn calc_stations(key: &str, start_jd: f64, end_jd: f64) -> Vec<BodySpeed> {
+	find_roots(start_jd, end_jd, body_search_step(key), f64::MAX, |jd| calc_lng_speed(key, jd).1)
+		.into_iter()
+		.map(|jd| {
+			let station = if calc_lng_acceleration(key, jd) < 0f64 { PlanetStation::RetroStart } else { PlanetStation::RetroEnd };
+			let (lng, speed) = calc_lng_speed(key, jd);
+			BodySpeed::new(key, jd, lng, speed, station as u8)
+		})
+		.collect()
+}
+
+/*
+* Maximum forward speeds and maximum retrograde speeds, i.e. minimum speeds, where the acceleration changes sign.
+* Perturbations add smaller wiggles to the speed, so only the greatest extreme between two stations is a peak.
+* Bodies without stations in between have one peak per cycle
+*/
+fn calc_peaks(key: &str, start_jd: f64, end_jd: f64, cycle: f64, stations: &Vec<BodySpeed>) -> Vec<BodySpeed> {
+	let candidates: Vec<BodySpeed> = find_roots(start_jd, end_jd, body_search_step(key), f64::MAX, |jd| calc_lng_acceleration(key, jd))
+		.into_iter()
+		.filter_map(|jd| {
+			let (lng, speed) = calc_lng_speed(key, jd);
+			let is_max = calc_lng_acceleration(key, jd - SPEED_DELTA_DAYS) > 0f64 && calc_lng_acceleration(key, jd + SPEED_DELTA_DAYS) < 0f64;
+			let is_min = calc_lng_acceleration(key, jd - SPEED_DELTA_DAYS) < 0f64 && calc_lng_acceleration(key, jd + SPEED_DELTA_DAYS) > 0f64;
+			if is_max && speed > 0f64 {
+				Some(BodySpeed::new(key, jd, lng, speed, PlanetStation::Peak as u8))
+			} else if is_min && speed < 0f64 {
+				Some(BodySpeed::new(key, jd, lng, speed, PlanetStation::RetroPeak as u8))
+			} else {
+				None
 			}
-		}
-    items.into_iter().rev().collect()
+		})
+		.collect();
+	let segment = |jd: f64| stations.iter().filter(|s| s.jd < jd).count();
+	candidates.iter()
+		.filter(|item| !candidates.iter().any(|other| {
+			other.station == item.station
+				&& segment(other.jd) == segment(item.jd)
+				&& (other.jd - item.jd).abs() < cycle / 2f64
+				&& other.speed.abs() > item.speed.abs()
+		}))
+		.cloned()
+		.collect()
+}
+
+/**
+ * Calculate retrograde and direct stations (speed zero), the peak retrograde speed and the peak forward speed
+ * of any body from its longitude speed. Bodies that never turn retrograde, e.g. the sun and moon, only have forward peaks.
+ * The search is extended by one cycle, so peaks near the range limits are compared with the whole phase
+ */
+pub fn calc_planet_stations_range(key: &str, start_jd: f64, end_jd: f64) -> Vec<BodySpeed> {
+	if !can_calc_body(key, start_jd) || !can_calc_body(key, end_jd) {
+		return vec![];
+	}
+	let cycle_opt = peak_cycle_days(key);
+	let (ext_start_jd, ext_end_jd) = match cycle_opt {
+		Some(cycle) => (start_jd - cycle, end_jd + cycle),
+		None => (start_jd, end_jd),
+	};
+	let stations = calc_stations(key, ext_start_jd, ext_end_jd);
+	let peaks = match cycle_opt {
+		Some(cycle) => calc_peaks(key, ext_start_jd, ext_end_jd, cycle, &stations),
+		None => vec![],
+	};
+	let mut items: Vec<BodySpeed> = stations.into_iter().chain(peaks).filter(|row| row.jd >= start_jd && row.jd <= end_jd).collect();
+	items.sort_by(|a, b| a.jd.total_cmp(&b.jd));
+	items
+}
+
+fn planet_stations_table(key: &str) -> &'static [(f64, f64, f64, u8)] {
+	match key {
+		"me" => PLANETARY_STATIONS_ME,
+		"ve" => PLANETARY_STATIONS_VE,
+		"ma" => PLANETARY_STATIONS_MA,
+		"ju" => PLANETARY_STATIONS_JU,
+		"sa" => PLANETARY_STATIONS_SA,
+		"ur" => PLANETARY_STATIONS_UR,
+		"ne" => PLANETARY_STATIONS_NE,
+		"pl" => PLANETARY_STATIONS_PL,
+		_ => PLANETARY_STATIONS_EA,
+	}
+}
+
+/*
+* The table rows have sidereal longitudes and speeds rounded to the minute, so stations are narrowed down
+* to the exact tropical station nearby and longitudes and speeds are recalculated
+*/
+fn refine_cached_station(key: &str, row: &(f64, f64, f64, u8)) -> BodySpeed {
+	let is_station = row.3 == PlanetStation::RetroStart as u8 || row.3 == PlanetStation::RetroEnd as u8;
+	let roots = if is_station { find_roots(row.0 - 0.5, row.0 + 0.5, 1f64, f64::MAX, |jd| calc_lng_speed(key, jd).1) } else { vec![] };
+	let jd = roots.first().map(|r| r.to_owned()).unwrap_or(row.0);
+	let (lng, speed) = calc_lng_speed(key, jd);
+	BodySpeed::new(key, jd, lng, speed, row.3)
+}
+
+/*
+* The precalculated tables only serve as a cache if they cover the whole range.
+* NB data sources are in descending chronological order
+*/
+fn cached_planet_stations_range(key: &str, start_jd: f64, end_jd: f64) -> Option<Vec<BodySpeed>> {
+	let rows = planet_stations_table(key);
+	match (rows.last(), rows.first()) {
+		(Some(first), Some(last)) if first.0 <= start_jd && last.0 >= end_jd => Some(
+			rows.iter()
+				.rev()
+				.filter(|row| row.0 >= start_jd && row.0 <= end_jd)
+				.map(|row| refine_cached_station(key, row))
+				.collect()
+		),
+		_ => None,
+	}
+}
+
+pub fn match_planet_stations_range(key: &str, start_jd: f64, end_jd: f64) -> Vec<BodySpeed> {
+	match cached_planet_stations_range(key, start_jd, end_jd) {
+		Some(rows) => rows,
+		None => calc_planet_stations_range(key, start_jd, end_jd),
+	}
 }
 
+/*
+* The last four stations or peaks before and the first four after the reference date
+*/
 pub fn match_nextprev_planet_stations(key: &str, ref_jd: f64) -> Vec<BodySpeed> {
-	match_planet_stations_range(key, ref_jd, 0f64)
+	let span = station_search_span(key);
+	let rows = match_planet_stations_range(key, ref_jd - span, ref_jd + span);
+	let num_prev = rows.iter().filter(|row| row.jd < ref_jd).count();
+	let skip = num_prev.saturating_sub(NUM_NEXTPREV_STATIONS);
+	rows.into_iter().skip(skip).take(num_prev - skip + NUM_NEXTPREV_STATIONS).collect()
 }
 
 pub fn match_all_nextprev_planet_stations(ref_jd: f64, bodies: Vec<&str>, iso_mode: bool) -> Vec<BodySpeedSet> {
@@ -146,11 +314,181 @@ pub fn match_all_nextprev_planet_stations(ref_jd: f64, bodies: Vec<&str>, iso_mo
 	items
 }
 
+/*
+* Stations between two dates or the stations around the start date if the end date is not later
+*/
 pub fn match_all_planet_stations_range(ref_jd: f64, end_jd: f64, bodies: Vec<&str>, iso_mode: bool) -> Vec<BodySpeedSet> {
 	let mut items: Vec<BodySpeedSet> = vec![];
 	for key in bodies {
-		let rows = match_planet_stations_range(key, ref_jd, end_jd);
+		let rows = if end_jd > ref_jd {
+			match_planet_stations_range(key, ref_jd, end_jd)
+		} else {
+			match_nextprev_planet_stations(key, ref_jd)
+		};
 		items.push(BodySpeedSet::new(key, rows, iso_mode));
 	}
 	items
-}
\ No newline at end of file
+}
+
+/*
+* Complete retrograde loop from the pre-retrograde shadow entry to the post-retrograde shadow exit.
+* The loop spans the longitudes between the direct station (lngStart) and the retrograde station (lngEnd)
+*/
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+pub struct RetrogradeCycle {
+	#[serde(rename = "lngStart")]
+	pub lng_start: f64,
+	#[serde(rename = "lngEnd")]
+	pub lng_end: f64,
+	pub arc: f64,
+	#[serde(rename = "retroDays")]
+	pub retro_days: f64,
+	pub days: f64,
+	pub events: Vec<PlanetSample>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+pub struct RetrogradeCycleSet {
+	pub key: String,
+	pub cycles: Vec<RetrogradeCycle>,
+}
+
+/*
+* Last time before the retrograde station or first time after the direct station when the body passes the given longitude
+*/
+fn calc_shadow_crossing(key: &str, lng: f64, station_jd: f64, span: f64, before: bool) -> Option<BodySpeed> {
+	let (start_jd, end_jd) = if before { (station_jd - span, station_jd) } else { (station_jd, station_jd + span) };
+	let events = calc_longitude_transits(key, lng, start_jd, end_jd, &AyanamshaRange::tropical(), false);
+	let event = if before { events.last() } else { events.first() };
+	let station = if before { PlanetStation::ShadowStart } else { PlanetStation::ShadowEnd };
+	event.map(|e| BodySpeed::new(key, e.jd, e.lng, e.lng_speed, station as u8))
+}
+
+/**
+ * Group the stations of a body into retrograde cycles starting between start_jd and end_jd
+ * and add the shadow entry and exit times
+ */
+pub fn calc_retrograde_cycles(key: &str, start_jd: f64, end_jd: f64, iso_mode: bool) -> Vec<RetrogradeCycle> {
+	let stations = match_planet_stations_range(key, start_jd, end_jd + MAX_RETRO_DAYS);
+	let mut cycles: Vec<RetrogradeCycle> = vec![];
+	for (index, retro_start) in stations.iter().enumerate() {
+		if retro_start.station != PlanetStation::RetroStart.as_string() || retro_start.jd > end_jd {
+			continue;
+		}
+		let retro_end_index = stations.iter().skip(index + 1).position(|row| row.station == PlanetStation::RetroEnd.as_string());
+		if let Some(offset) = retro_end_index {
+			let retro_end = &stations[index + 1 + offset];
+			let retro_days = retro_end.jd - retro_start.jd;
+			// the forward motion through the shadow is slower near the stations than the retrograde motion
+			let span = retro_days * 3f64 + 30f64;
+			let shadow_start = calc_shadow_crossing(key, retro_end.lng, retro_start.jd, span, true);
+			let shadow_end = calc_shadow_crossing(key, retro_start.lng, retro_end.jd, span, false);
+			if let (Some(entry), Some(exit)) = (shadow_start, shadow_end) {
+				let mut events: Vec<BodySpeed> = vec![entry.clone()];
+				events.extend(stations[index..=index + 1 + offset].iter().filter(|row| row.station != PlanetStation::Peak.as_string()).cloned());
+				events.push(exit.clone());
+				cycles.push(RetrogradeCycle {
+					lng_start: retro_end.lng,
+					lng_end: retro_start.lng,
+					arc: (retro_start.lng - retro_end.lng).rem_euclid(360f64),
+					retro_days,
+					days: exit.jd - entry.jd,
+					events: events.into_iter().map(|e| e.as_sample(iso_mode)).collect(),
+				});
+			}
+		}
+	}
+	cycles
+}
+
+pub fn calc_all_retrograde_cycles(start_jd: f64, end_jd: f64, bodies: Vec<&str>, iso_mode: bool) -> Vec<RetrogradeCycleSet> {
+	bodies.into_iter().map(|key| RetrogradeCycleSet {
+		key: key.to_string(),
+		cycles: calc_retrograde_cycles(key, start_jd, end_jd, iso_mode),
+	}).collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::calc::search::JD_TOLERANCE;
+	use crate::ephemeris::with_ephemeris;
+
+	fn count_stations(items: &Vec<BodySpeed>, station: PlanetStation) -> usize {
+		items.iter().filter(|item| item.station == station.as_string()).count()
+	}
+
+	/*
+	* The precalculated tables list sidereal longitudes with times rounded to the minute
+	*/
+	fn assert_matches_table(key: &str, start_jd: f64, end_jd: f64, max_minutes: f64) {
+		let rows: Vec<BodySpeed> = planet_stations_table(key).iter()
+			.filter(|row| row.0 >= start_jd && row.0 <= end_jd && (row.3 == PlanetStation::RetroStart as u8 || row.3 == PlanetStation::RetroEnd as u8))
+			.map(|row| BodySpeed::new(key, row.0, row.1, row.2, row.3))
+			.collect();
+		let items = with_ephemeris(|| calc_planet_stations_range(key, start_jd, end_jd));
+		assert!(rows.len() > 1);
+		assert_eq!(items.iter().filter(|item| item.station == PlanetStation::RetroStart.as_string() || item.station == PlanetStation::RetroEnd.as_string()).count(), rows.len());
+		for row in rows {
+			let item = items.iter()
+				.filter(|item| item.station == row.station)
+				.min_by(|a, b| (a.jd - row.jd).abs().total_cmp(&(b.jd - row.jd).abs()))
+				.unwrap();
+			let minutes = (item.jd - row.jd).abs() * 1440f64;
+			assert!(minutes < max_minutes, "{} {} at {} is {} minutes off", key, row.station, row.jd, minutes);
+			assert!(item.speed.abs() < 0.00001);
+		}
+		let cached = with_ephemeris(|| cached_planet_stations_range(key, start_jd, end_jd)).unwrap();
+		for row in cached.iter().filter(|row| row.station == PlanetStation::RetroStart.as_string() || row.station == PlanetStation::RetroEnd.as_string()) {
+			assert!(items.iter().any(|item| (item.jd - row.jd).abs() < JD_TOLERANCE * 10f64 && (item.lng - row.lng).abs() < 0.0001));
+		}
+	}
+
+	#[test]
+	fn test_mercury_stations() {
+		// 2024-01-01 to 2025-01-01
+		assert_matches_table("me", 2460310.5, 2460676.5, 5f64);
+	}
+
+	#[test]
+	fn test_mars_stations() {
+		// 2024-01-01 to 2026-01-01, Mars turned retrograde on 2024-12-06 and direct on 2025-02-24.
+		// Mars is slow near its stations, so small differences in speed shift the station by several minutes
+		assert_matches_table("ma", 2460310.5, 2461041.5, 20f64);
+	}
+
+	#[test]
+	fn test_single_peaks_between_stations() {
+		// 2024-01-01 to 2026-01-01: Mercury has one forward peak between each direct and retrograde station
+		let items = with_ephemeris(|| calc_planet_stations_range("me", 2460310.5, 2461041.5));
+		let stations: Vec<&BodySpeed> = items.iter().filter(|item| item.speed.abs() < 0.00001).collect();
+		for pair in stations.windows(2) {
+			let peaks = items.iter().filter(|item| item.jd > pair[0].jd && item.jd < pair[1].jd).count();
+			assert_eq!(peaks, 1, "{} peaks between {} and {}", peaks, pair[0].jd, pair[1].jd);
+		}
+		assert_eq!(count_stations(&items, PlanetStation::RetroPeak), count_stations(&items, PlanetStation::RetroStart));
+	}
+
+	#[test]
+	fn test_sun_moon_peaks() {
+		// the Sun is fastest near perihelion in early January, once a year, and never has peaks around aphelion
+		let sun = with_ephemeris(|| calc_planet_stations_range("su", 2460310.5, 2461041.5));
+		assert_eq!(sun.len(), 2);
+		assert!(sun.iter().all(|item| item.station == PlanetStation::Peak.as_string() && item.speed > 1.01));
+		assert!((sun[1].jd - sun[0].jd - 365.25).abs() < 30f64);
+		// the Moon is fastest near perigee once per anomalistic month
+		let moon = with_ephemeris(|| calc_planet_stations_range("mo", 2460310.5, 2460676.5));
+		assert!(moon.len() >= 13 && moon.len() <= 14);
+		assert!(moon.windows(2).all(|pair| pair[1].jd - pair[0].jd > 20f64));
+		// the mean node moves at a near constant rate
+		assert!(with_ephemeris(|| calc_planet_stations_range("mn", 2460310.5, 2460676.5)).is_empty());
+	}
+
+	#[test]
+	fn test_unavailable_body() {
+		// Chiron requires its asteroid file
+		let (available, items) = with_ephemeris(|| (can_calc_body("ch", 2460310.5), calc_planet_stations_range("ch", 2460310.5, 2460676.5)));
+		assert_eq!(items.is_empty(), !available);
+		assert!(items.iter().all(|item| item.lng != 0f64));
+	}
+}
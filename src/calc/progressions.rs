@@ -0,0 +1,349 @@
+use serde::{Serialize, Deserialize};
+use utoipa::ToSchema;
+use super::{
+  core::{calc_bodies_positions_jd, calc_body_eq_jd, calc_body_jd_geo, ecliptic_to_equatorial_basic, get_bodies_dual_geo, get_bodies_ecl_geo},
+  dates::julian_day_to_iso_datetime,
+  models::{general::CoordinateSystem, geo_pos::GeoPos, graha_pos::{BodyPos, FlexiBodyPos}, houses::{get_house_systems, HouseSetData}},
+  rise_set_phases::CentricMode,
+};
+
+pub const TROPICAL_YEAR_DAYS: f64 = 365.24219;
+
+pub const TROPICAL_MONTH_DAYS: f64 = 27.321582;
+
+/*
+* Mean daily rotation of the earth relative to the equinox, i.e. the daily increase of the ARMC
+*/
+pub const SIDEREAL_DEGREES_PER_DAY: f64 = 360.98564736629;
+
+/*
+* Progressions and directions are only calculated for a human lifespan
+*/
+pub const MAX_PROGRESSION_YEARS: f64 = 150f64;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ProgressionType {
+  Secondary, // one day after birth for each year of life
+  Tertiary, // one day after birth for each tropical month of life
+}
+
+impl ProgressionType {
+  pub fn to_key(&self) -> String {
+    match self {
+      ProgressionType::Secondary => "secondary",
+      ProgressionType::Tertiary => "tertiary",
+    }.to_string()
+  }
+
+  pub fn period_days(&self) -> f64 {
+    match self {
+      ProgressionType::Secondary => TROPICAL_YEAR_DAYS,
+      ProgressionType::Tertiary => TROPICAL_MONTH_DAYS,
+    }
+  }
+
+  /*
+  * Ephemeris date whose positions are progressed to the target date
+  */
+  pub fn progressed_jd(&self, natal_jd: f64, target_jd: f64) -> f64 {
+    natal_jd + (target_jd - natal_jd) / self.period_days()
+  }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum DirectionMethod {
+  Placidus,
+  Regiomontanus,
+}
+
+impl DirectionMethod {
+  pub fn from_key(key: &str) -> Option<Self> {
+    match key.to_lowercase().as_str() {
+      "p" | "placidus" => Some(DirectionMethod::Placidus),
+      "r" | "regio" | "regiomontanus" => Some(DirectionMethod::Regiomontanus),
+      _ => None,
+    }
+  }
+
+  pub fn to_key(&self) -> String {
+    match self {
+      DirectionMethod::Placidus => "placidus",
+      DirectionMethod::Regiomontanus => "regiomontanus",
+    }.to_string()
+  }
+}
+
+/*
+* Conversion of arcs of direction to years of life
+*/
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum DirectionRate {
+  Naibod, // mean daily motion of the sun, 0º59'08"
+  Ptolemy, // one degree per year
+}
+
+impl DirectionRate {
+  pub fn from_key(key: &str) -> Option<Self> {
+    match key.to_lowercase().as_str() {
+      "n" | "naibod" => Some(DirectionRate::Naibod),
+      "p" | "ptolemy" => Some(DirectionRate::Ptolemy),
+      _ => None,
+    }
+  }
+
+  pub fn to_key(&self) -> String {
+    match self {
+      DirectionRate::Naibod => "naibod",
+      DirectionRate::Ptolemy => "ptolemy",
+    }.to_string()
+  }
+
+  pub fn degrees_per_year(&self) -> f64 {
+    match self {
+      DirectionRate::Naibod => 360f64 / TROPICAL_YEAR_DAYS,
+      DirectionRate::Ptolemy => 1f64,
+    }
+  }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+pub struct ProgressedChart {
+  pub method: String,
+  #[serde(rename = "progressedJd")]
+  pub progressed_jd: f64, // ephemeris date of the progressed positions
+  #[serde(rename = "solarArc")]
+  pub solar_arc: f64,
+  #[serde(rename = "solarArcRa")]
+  pub solar_arc_ra: f64,
+  pub bodies: FlexiBodyPos,
+  pub houses: HouseSetData,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+pub struct SolarArcChart {
+  pub arc: f64,
+  pub bodies: Vec<BodyPos>,
+  pub ascendant: f64,
+  pub mc: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+pub struct PrimaryDirection {
+  pub promissor: String,
+  pub significator: String,
+  pub arc: f64,
+  pub jd: f64,
+  #[serde(skip_serializing_if = "String::is_empty")]
+  pub dt: String,
+}
+
+impl PrimaryDirection {
+  pub fn new(promissor: &str, significator: &str, arc: f64, jd: f64, iso_mode: bool) -> Self {
+    let dt = if iso_mode { julian_day_to_iso_datetime(jd) } else { "".to_string() };
+    PrimaryDirection {
+      promissor: promissor.to_string(),
+      significator: significator.to_string(),
+      arc,
+      jd,
+      dt,
+    }
+  }
+}
+
+/*
+* Hour angle west of the upper meridian (-180º to 180º) and declination of a point in the natal chart
+*/
+#[derive(Debug, Copy, Clone)]
+struct MundanePos {
+  hour_angle: f64,
+  dec: f64,
+}
+
+impl MundanePos {
+  fn new(armc: f64, ra: f64, dec: f64) -> Self {
+    let ha = (armc - ra).rem_euclid(360f64);
+    MundanePos { hour_angle: if ha > 180f64 { ha - 360f64 } else { ha }, dec }
+  }
+}
+
+fn asin_deg(value: f64) -> f64 {
+  value.clamp(-1f64, 1f64).asin().to_degrees()
+}
+
+/*
+* Ascensional difference in degrees. Circumpolar declinations are capped at 90º
+*/
+fn ascensional_difference(geo_lat: f64, dec: f64) -> f64 {
+  asin_deg(geo_lat.to_radians().tan() * dec.to_radians().tan())
+}
+
+/*
+* Placidian (semi-arc) position: the promissor must cover the same proportion of its own diurnal
+* or nocturnal semi-arc as the significator
+*/
+fn placidus_target(sig: MundanePos, prom_dec: f64, geo_lat: f64) -> f64 {
+  let ad_sig = ascensional_difference(geo_lat, sig.dec);
+  let ad_prom = ascensional_difference(geo_lat, prom_dec);
+  let dsa_sig = 90f64 + ad_sig;
+  if sig.hour_angle.abs() <= dsa_sig && dsa_sig > 0f64 {
+    sig.hour_angle / dsa_sig * (90f64 + ad_prom)
+  } else {
+    let nsa_sig = 90f64 - ad_sig;
+    if nsa_sig <= 0f64 {
+      return sig.hour_angle;
+    }
+    let lower_ha = (sig.hour_angle - 180f64).rem_euclid(360f64);
+    let lower_ha = if lower_ha > 180f64 { lower_ha - 360f64 } else { lower_ha };
+    180f64 + lower_ha / nsa_sig * (90f64 - ad_prom)
+  }
+}
+
+/*
+* Regiomontanian position: the promissor must reach the great circle through the north and south points
+* of the horizon and the significator. The pole of this circle is found by iteration
+*/
+fn regiomontanus_target(sig: MundanePos, prom_dec: f64, geo_lat: f64) -> f64 {
+  let tan_lat = geo_lat.to_radians().tan();
+  let mut equator_ha = sig.hour_angle;
+  let mut pole = 0f64;
+  for _ in 0..12 {
+    pole = (tan_lat * equator_ha.to_radians().sin()).atan();
+    equator_ha = sig.hour_angle - asin_deg(pole.tan() * sig.dec.to_radians().tan());
+  }
+  equator_ha + asin_deg(pole.tan() * prom_dec.to_radians().tan())
+}
+
+/*
+* Direct arc in mundo for the conjunction of the promissor with the significator.
+* Primary motion increases all hour angles, so the arc is the hour angle still to be covered by the promissor
+*/
+fn calc_direction_arc(method: DirectionMethod, sig: MundanePos, prom: MundanePos, geo_lat: f64) -> f64 {
+  let target = match method {
+    DirectionMethod::Placidus => placidus_target(sig, prom.dec, geo_lat),
+    DirectionMethod::Regiomontanus => regiomontanus_target(sig, prom.dec, geo_lat),
+  };
+  (target - prom.hour_angle).rem_euclid(360f64)
+}
+
+/**
+ * Secondary or tertiary progressed positions and houses. The progressed angles are derived
+ * from the natal ARMC advanced by the solar arc in right ascension
+ */
+pub fn calc_progressed_chart(
+  natal_jd: f64,
+  target_jd: f64,
+  geo: GeoPos,
+  keys: &Vec<&str>,
+  progression: ProgressionType,
+  h_systems: Vec<char>,
+  aya_offset: f64,
+) -> ProgressedChart {
+  let progressed_jd = progression.progressed_jd(natal_jd, target_jd);
+  let bodies = calc_bodies_positions_jd(progressed_jd, keys, 1, 1f64, None, CentricMode::Geo, 0, false, aya_offset)
+    .into_iter()
+    .next()
+    .map(|set| set.bodies)
+    .unwrap_or(FlexiBodyPos::Simple(vec![]));
+  let solar_arc = (calc_body_jd_geo(progressed_jd, "su", 0f64).lng - calc_body_jd_geo(natal_jd, "su", 0f64).lng).rem_euclid(360f64);
+  let solar_arc_ra = (calc_body_eq_jd(progressed_jd, "su", CentricMode::Geo).rect_ascension
+    - calc_body_eq_jd(natal_jd, "su", CentricMode::Geo).rect_ascension).rem_euclid(360f64);
+  let houses = get_house_systems(natal_jd + solar_arc_ra / SIDEREAL_DEGREES_PER_DAY, geo, h_systems, aya_offset);
+  ProgressedChart {
+    method: progression.to_key(),
+    progressed_jd,
+    solar_arc,
+    solar_arc_ra,
+    bodies,
+    houses,
+  }
+}
+
+/**
+ * Natal positions, ascendant and MC directed by the secondary progressed solar arc
+ */
+pub fn calc_solar_arc_chart(natal_jd: f64, target_jd: f64, geo: GeoPos, keys: &Vec<&str>, aya_offset: f64) -> SolarArcChart {
+  let progressed_jd = ProgressionType::Secondary.progressed_jd(natal_jd, target_jd);
+  let arc = (calc_body_jd_geo(progressed_jd, "su", 0f64).lng - calc_body_jd_geo(natal_jd, "su", 0f64).lng).rem_euclid(360f64);
+  let bodies: Vec<BodyPos> = get_bodies_ecl_geo(natal_jd, keys, aya_offset)
+    .into_iter()
+    .map(|b| BodyPos::new(b.key.as_str(), CoordinateSystem::Ecliptic, (b.lng + arc) % 360f64, b.lat, 0f64, 0f64))
+    .collect();
+  let points = get_house_systems(natal_jd, geo, vec!['W'], aya_offset).points;
+  SolarArcChart {
+    arc,
+    bodies,
+    ascendant: (points.ascendant + arc) % 360f64,
+    mc: (points.mc + arc) % 360f64,
+  }
+}
+
+/**
+ * Direct primary directions in mundo of the bodies (promissors) to the ascendant, MC and natal bodies (significators),
+ * perfected between birth and the target date, in chronological order
+ */
+pub fn calc_primary_directions(
+  natal_jd: f64,
+  target_jd: f64,
+  geo: GeoPos,
+  keys: &Vec<&str>,
+  method: DirectionMethod,
+  rate: DirectionRate,
+  iso_mode: bool,
+) -> Vec<PrimaryDirection> {
+  let points = get_house_systems(natal_jd, geo, vec!['W'], 0f64).points;
+  let armc = points.armc;
+  let bodies: Vec<(String, MundanePos)> = get_bodies_dual_geo(natal_jd, keys, false, None, 0f64)
+    .into_iter()
+    .map(|b| (b.key.clone(), MundanePos::new(armc, b.rect_ascension, b.declination)))
+    .collect();
+  let mut significators: Vec<(String, MundanePos)> = Vec::new();
+  for (key, lng) in [("as", points.ascendant), ("mc", points.mc)] {
+    let eq_pos = ecliptic_to_equatorial_basic(natal_jd, lng, 0f64);
+    significators.push((key.to_string(), MundanePos::new(armc, eq_pos.lng, eq_pos.lat)));
+  }
+  significators.extend(bodies.clone());
+  let max_arc = (target_jd - natal_jd) / TROPICAL_YEAR_DAYS * rate.degrees_per_year();
+  let mut directions: Vec<PrimaryDirection> = Vec::new();
+  for (prom_key, prom) in bodies.iter() {
+    for (sig_key, sig) in significators.iter() {
+      if prom_key == sig_key {
+        continue;
+      }
+      let arc = calc_direction_arc(method, *sig, *prom, geo.lat);
+      if arc <= max_arc {
+        let jd = natal_jd + arc / rate.degrees_per_year() * TROPICAL_YEAR_DAYS;
+        directions.push(PrimaryDirection::new(prom_key, sig_key, arc, jd, iso_mode));
+      }
+    }
+  }
+  directions.sort_by(|a, b| a.jd.partial_cmp(&b.jd).unwrap_or(std::cmp::Ordering::Equal));
+  directions
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_progressed_jd() {
+    // 30 years after birth are progressed to 30 days after birth
+    let natal_jd = 2451545f64;
+    let target_jd = natal_jd + 30f64 * TROPICAL_YEAR_DAYS;
+    assert!((ProgressionType::Secondary.progressed_jd(natal_jd, target_jd) - (natal_jd + 30f64)).abs() < 1e-9);
+  }
+
+  #[test]
+  fn test_directions_to_angles() {
+    // all methods agree for the MC and the ascendant: the promissor must culminate or rise
+    let geo_lat = 51.5;
+    let mc = MundanePos { hour_angle: 0f64, dec: 15f64 };
+    let asc_dec = 10f64;
+    let asc = MundanePos { hour_angle: -(90f64 + ascensional_difference(geo_lat, asc_dec)), dec: asc_dec };
+    let prom = MundanePos { hour_angle: -40f64, dec: -5f64 };
+    let rising_ha = -(90f64 + ascensional_difference(geo_lat, prom.dec));
+    for method in [DirectionMethod::Placidus, DirectionMethod::Regiomontanus] {
+      assert!((calc_direction_arc(method, mc, prom, geo_lat) - 40f64).abs() < 1e-6);
+      assert!((calc_direction_arc(method, asc, prom, geo_lat) - (rising_ha - prom.hour_angle).rem_euclid(360f64)).abs() < 1e-6);
+    }
+  }
+}
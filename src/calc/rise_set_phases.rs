@@ -1,4 +1,4 @@
-use crate::extensions::swe::rise_trans;
+use crate::extensions::swe::{rise_trans, rise_trans_num};
 use super::utils::converters::get_next_prev_rise_set_polar_calc_offset;
 use super::{
   core::calc_altitude_object,
@@ -8,12 +8,13 @@ use super::{
     geo_pos::*,
     graha_pos::{PhenoItem, PhenoResult},
   },
+  settings::body_values::{asteroid_num, body_key_to_num},
   traits::*,
   transposed_transitions::{
     calc_transitions_from_source_refs_altitude, calc_transitions_from_source_refs_minmax
   },
 };
-use libswe_sys::sweconst::Bodies;
+use libswe_sys::sweconst::{Bodies, OptionalFlag};
 use libswe_sys::swerust::handler_swe07::pheno_ut;
 use serde::{Deserialize, Serialize};
 use strum_macros::ToString;
@@ -121,21 +122,57 @@ impl TransitionMode {
   }
 }
 
-#[derive(Debug, Clone, Copy)]
+/*
+* Observer or reference point of body positions. Heliocentric and barycentric positions are centred on the Sun
+* and the solar system barycentre and do not depend on the location
+*/
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum CentricMode {
   Topo,
-  Geo
-}
+  Geo,
+  Helio,
+  Bary,
+}
+
+impl CentricMode {
+  pub fn from_key(key: &str) -> Option<Self> {
+    match key.trim().to_lowercase().as_str() {
+      "geo" | "geocentric" => Some(CentricMode::Geo),
+      "topo" | "topocentric" => Some(CentricMode::Topo),
+      "helio" | "heliocentric" => Some(CentricMode::Helio),
+      "bary" | "barycentric" => Some(CentricMode::Bary),
+      _ => None,
+    }
+  }
 
-/* impl CentricMode {
-  pub fn from_key(key: &str) -> Self {
-    if key.to_lowercase().starts_with("topo") {
-      CentricMode::Topo
-    } else {
-      CentricMode::Geo
+  pub fn to_key(&self) -> &'static str {
+    match self {
+      CentricMode::Topo => "topocentric",
+      CentricMode::Geo => "geocentric",
+      CentricMode::Helio => "heliocentric",
+      CentricMode::Bary => "barycentric",
     }
   }
-} */
+
+  /*
+  * Swiss Ephemeris flags: SEFLG_TOPOCTR, SEFLG_HELCTR and SEFLG_BARYCTR
+  */
+  pub fn to_flag(&self) -> i32 {
+    match self {
+      CentricMode::Topo => OptionalFlag::TopocentricPosition as i32,
+      CentricMode::Helio => OptionalFlag::Heliocentric as i32,
+      CentricMode::Bary => OptionalFlag::BarycentricPosition as i32,
+      CentricMode::Geo => 0,
+    }
+  }
+
+  pub fn is_solar_system(&self) -> bool {
+    match self {
+      CentricMode::Helio | CentricMode::Bary => true,
+      _ => false,
+    }
+  }
+}
 
 pub trait TransitionGroup {
   fn period(&self) -> f64;
@@ -541,6 +578,27 @@ pub fn calc_transition_set_alt(jd: f64, ipl: Bodies, lat: f64, lng: f64, mode: T
   }
 }
 
+/**
+ * Rise, set, MC and IC times with min/max altitudes for numbered asteroids referenced by key (ast:NNN),
+ * which cannot be mapped to the Bodies enum
+ */
+pub fn calc_transition_set_alt_by_key(jd: f64, key: &str, lat: f64, lng: f64, mode: TransitionMode) -> AltTransitionSet {
+  let ref_jd = start_jd_geo(jd, lng);
+  match (is_near_poles(lat), body_key_to_num(key)) {
+    (false, Ok(ipl)) => {
+      let (alignment, no_refrac) = mode.to_options();
+      let rise = rise_trans_num(ref_jd, ipl, lat, lng, TransitionParams::rise(alignment, no_refrac));
+      let set = rise_trans_num(ref_jd, ipl, lat, lng, TransitionParams::set(alignment, no_refrac));
+      let mc = rise_trans_num(ref_jd, ipl, lat, lng, TransitionParams::mc());
+      let ic = rise_trans_num(ref_jd, ipl, lat, lng, TransitionParams::ic());
+      let min = calc_altitude_object(ic, false, lat, lng, key);
+      let max = calc_altitude_object(mc, false, lat, lng, key);
+      AltTransitionSet { min, rise, mc, set, ic, max }
+    },
+    _ => calc_transitions_from_source_refs_minmax(ref_jd, key, GeoPos::simple(lat, lng)),
+  }
+}
+
 pub fn calc_transition_set_fast(jd: f64, ipl: Bodies, lat: f64, lng: f64, mode: TransitionMode) -> TransitionSet {
   let ref_jd = start_jd_geo(jd, lng);
   let rise = next_rise(ref_jd, ipl, lat, lng, mode);
@@ -725,7 +783,11 @@ pub fn get_transition_sets(jd: f64, keys: Vec<&str>, geo: GeoPos, mode: Transiti
       "su" | "mo" => {
         calc_transition_set_extended(jd, Bodies::from_key(key), geo.lat, geo.lng, true, mode).to_key_nums()
       }
-      _ => calc_transition_set(jd, Bodies::from_key(key), geo.lat, geo.lng, mode).to_key_nums(),
+      _ => if asteroid_num(key).is_some() {
+        calc_transition_set_alt_by_key(jd, key, geo.lat, geo.lng, mode).to_key_nums()
+      } else {
+        calc_transition_set(jd, Bodies::from_key(key), geo.lat, geo.lng, mode).to_key_nums()
+      },
     };
     transit_sets.push(KeyNumValueSet::new(key, tr_set));
   }
@@ -749,6 +811,9 @@ pub fn get_transition_sets_extended(
         calc_transition_set_alt(ref_jd, Bodies::from_key(key.as_str()), geo.lat, geo.lng, mode)
           .to_key_nums();
         tr_set.append(&mut tr_set_day);
+      } else if asteroid_num(key.as_str()).is_some() {
+        let mut tr_set_day = calc_transition_set_alt_by_key(ref_jd, key.as_str(), geo.lat, geo.lng, mode).to_key_nums();
+        tr_set.append(&mut tr_set_day);
       }
     }
     transit_sets.push(KeyNumValueSet::new(key.as_str(), tr_set));
@@ -764,7 +829,8 @@ pub fn get_pheno_result(jd: f64, key: &str, iflag: i32) -> PhenoResult {
 
 pub fn get_pheno_results(jd: f64, keys: Vec<&str>) -> Vec<PhenoItem> {
   let mut items: Vec<PhenoItem> = Vec::new();
-  for key in keys {
+  // planetary phenomena are only available for bodies in the Bodies enum
+  for key in keys.into_iter().filter(|k| asteroid_num(k).is_none()) {
     let ipl = Bodies::from_key(key);
     let result = pheno_ut(jd, ipl, 0i32);
     let item = PhenoItem::new_from_result(key, result);
@@ -804,9 +870,8 @@ pub fn to_sun_rise_sets(
 
 
 /**
- * Used by test function to estimate time zone offset based on longitude
+ * Start of the local day with a time zone offset in seconds or, if none is given, the solar time offset of the longitude
  */
-#[cfg(test)] 
 pub fn start_jd_geo_tz(jd: f64, lng: f64, tz_offset: Option<i32>) -> f64 {
   let lng_offset = match tz_offset {
     Some(tzs) => tzs as f64 / 240f64,
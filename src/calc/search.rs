@@ -0,0 +1,119 @@
+use super::math_funcs::signed_angle_180;
+
+/*
+* Precision of exact event times, about 1/10th of a second
+*/
+pub const JD_TOLERANCE: f64 = 1f64 / 864000f64;
+pub const MAX_BISECT_ITERATIONS: u16 = 64;
+
+/*
+* Largest jump between two samples of an angular deviation that may still be treated as a zero crossing.
+* Larger jumps occur when the deviation wraps around from +180º to -180º
+*/
+pub const MAX_ANGLE_JUMP: f64 = 90f64;
+
+/**
+ * Default sampling interval in days per celestial body.
+ * This must be short enough to catch separate passes during retrograde loops.
+ */
+pub fn body_search_step(key: &str) -> f64 {
+  match key {
+    "mo" => 0.25f64,
+    "su" | "me" | "ve" => 0.5f64,
+    "ma" => 1f64,
+    _ => 2f64,
+  }
+}
+
+pub fn min_search_step(keys: &Vec<&str>) -> f64 {
+  keys.iter().map(|k| body_search_step(k)).fold(2f64, f64::min)
+}
+
+/**
+ * Narrow down a zero crossing between a and b, where f(a) and f(b) have opposite signs
+ */
+pub fn bisect_root<F>(f: &F, start: f64, end: f64) -> f64 where F: Fn(f64) -> f64 {
+  let mut a = start;
+  let mut b = end;
+  let mut fa = f(a);
+  let mut counter: u16 = 0;
+  while (b - a) > JD_TOLERANCE && counter < MAX_BISECT_ITERATIONS {
+    let mid = (a + b) / 2f64;
+    let fm = f(mid);
+    if fm == 0f64 {
+      return mid;
+    }
+    if (fa < 0f64) == (fm < 0f64) {
+      a = mid;
+      fa = fm;
+    } else {
+      b = mid;
+    }
+    counter += 1;
+  }
+  (a + b) / 2f64
+}
+
+/**
+ * Sample f at regular intervals and return the exact times of all sign changes.
+ * Sign changes with a jump greater than max_jump are treated as discontinuities and skipped.
+ */
+pub fn find_roots<F>(start_jd: f64, end_jd: f64, step: f64, max_jump: f64, f: F) -> Vec<f64> where F: Fn(f64) -> f64 {
+  let mut roots: Vec<f64> = Vec::new();
+  if step <= 0f64 || end_jd <= start_jd {
+    return roots;
+  }
+  let mut prev_jd = start_jd;
+  let mut prev_val = f(prev_jd);
+  while prev_jd < end_jd {
+    let next_jd = if prev_jd + step > end_jd { end_jd } else { prev_jd + step };
+    let next_val = f(next_jd);
+    if prev_val == 0f64 {
+      roots.push(prev_jd);
+    } else if (prev_val < 0f64) != (next_val < 0f64) && next_val != 0f64 && (next_val - prev_val).abs() <= max_jump {
+      roots.push(bisect_root(&f, prev_jd, next_jd));
+    }
+    prev_jd = next_jd;
+    prev_val = next_val;
+  }
+  roots
+}
+
+/**
+ * Find all times when an angular deviation in degrees crosses zero.
+ * The deviation is normalised to -180º to +180º
+ */
+pub fn find_angle_roots<F>(start_jd: f64, end_jd: f64, step: f64, f: F) -> Vec<f64> where F: Fn(f64) -> f64 {
+  find_roots(start_jd, end_jd, step, MAX_ANGLE_JUMP, |jd| signed_angle_180(f(jd)))
+}
+
+/**
+ * Interpolate a slowly changing value such as an ayanamsha between two reference times
+ */
+pub fn interpolate_linear(jd: f64, start_jd: f64, start_val: f64, end_jd: f64, end_val: f64) -> f64 {
+  if end_jd > start_jd {
+    start_val + (end_val - start_val) * (jd - start_jd) / (end_jd - start_jd)
+  } else {
+    start_val
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn finds_all_roots_of_sine_wave() {
+    let roots = find_roots(0.5f64, 9.9f64, 0.25f64, f64::MAX, |x| (x * std::f64::consts::PI).sin());
+    assert_eq!(roots.len(), 9);
+    assert!((roots[0] - 1f64).abs() < JD_TOLERANCE * 2f64);
+  }
+
+  #[test]
+  fn skips_angle_wraparound() {
+    // a longitude increasing by 10º per day crosses 0º once and wraps from 180º to -180º once
+    let roots = find_angle_roots(0f64, 30f64, 1f64, |x| x * 10f64 - 5f64);
+    assert_eq!(roots.len(), 1);
+    assert!((roots[0] - 0.5f64).abs() < JD_TOLERANCE * 2f64);
+  }
+}
@@ -0,0 +1,89 @@
+use serde::{Serialize, Deserialize};
+use utoipa::ToSchema;
+use super::{
+  ingresses::{calc_body_ingresses, Ingress, IngressMode},
+  settings::nakshatras::{rashi_name, sign_name},
+  transits::AyanamshaRange,
+};
+
+pub const MAX_SEASON_YEARS: u16 = 100;
+
+/*
+* Tropical signs entered by the Sun at the equinoxes and solstices
+*/
+pub fn cardinal_key(sign_num: u16) -> Option<&'static str> {
+  match sign_num {
+    1 => Some("march-equinox"),
+    4 => Some("june-solstice"),
+    7 => Some("september-equinox"),
+    10 => Some("december-solstice"),
+    _ => None,
+  }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+pub struct SeasonEvent {
+  pub key: String,
+  pub num: u16,
+  pub name: String,
+  pub jd: f64,
+  #[serde(skip_serializing_if = "String::is_empty")]
+  pub dt: String,
+  pub lng: f64,
+}
+
+impl SeasonEvent {
+  pub fn new(key: &str, name: String, ingress: &Ingress) -> Self {
+    SeasonEvent {
+      key: key.to_string(),
+      num: ingress.num,
+      name,
+      jd: ingress.jd,
+      dt: ingress.dt.clone(),
+      lng: ingress.lng,
+    }
+  }
+}
+
+fn calc_sun_sign_ingresses(start_jd: f64, end_jd: f64, aya: &AyanamshaRange, iso_mode: bool) -> Vec<Ingress> {
+  calc_body_ingresses("su", &vec![IngressMode::Sign], start_jd, end_jd, aya, iso_mode)
+}
+
+/**
+ * Exact times of the equinoxes and solstices, when the apparent tropical Sun enters Aries, Cancer, Libra or Capricorn
+ */
+pub fn calc_equinoxes_solstices(start_jd: f64, end_jd: f64, iso_mode: bool) -> Vec<SeasonEvent> {
+  calc_sun_sign_ingresses(start_jd, end_jd, &AyanamshaRange::tropical(), iso_mode)
+    .iter()
+    .filter_map(|ingress| cardinal_key(ingress.num).map(|key| SeasonEvent::new(key, sign_name(ingress.num), ingress)))
+    .collect()
+}
+
+/**
+ * Sidereal solar ingresses (sankrantis) into each of the 12 rashis, e.g. Makara Sankranti
+ */
+pub fn calc_sankrantis(start_jd: f64, end_jd: f64, aya: &AyanamshaRange, iso_mode: bool) -> Vec<SeasonEvent> {
+  calc_sun_sign_ingresses(start_jd, end_jd, aya, iso_mode)
+    .iter()
+    .map(|ingress| SeasonEvent::new("sankranti", format!("{} Sankranti", rashi_name(ingress.num)), ingress))
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use crate::ephemeris::with_ephemeris;
+  use crate::calc::utils::converters::calc_days_to_next_prev_equinox;
+
+  #[test]
+  fn test_march_equinox() {
+    // 20 March 2024 at 03:06 UTC
+    let equinox_jd = 2460389.6292;
+    let items = with_ephemeris(|| calc_equinoxes_solstices(2460310.5, 2460676.5, false));
+    let keys: Vec<&str> = items.iter().map(|item| item.key.as_str()).collect();
+    assert_eq!(keys, vec!["march-equinox", "june-solstice", "september-equinox", "december-solstice"]);
+    assert!((items[0].jd - equinox_jd).abs() * 1440f64 < 1f64);
+    assert_eq!(with_ephemeris(|| calc_days_to_next_prev_equinox(2460310.5)), 79);
+  }
+}
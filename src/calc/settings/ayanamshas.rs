@@ -184,6 +184,14 @@ pub fn match_ayanamsha_key(key: &str) -> String {
   Ayanamsha::from_key(ref_key).as_string()
 }
 
+/*
+* Divisional charts and panchangas require sidereal longitudes, so True Citra is used if no ayanamsha is given
+*/
+pub fn match_sidereal_ayanamsha_key(key: &str) -> String {
+  let aya_key = match_ayanamsha_key(key);
+  if aya_key.contains("tropical") { "true_citra".to_string() } else { aya_key }
+}
+
 pub fn match_ayanamsha_num(key: &str) -> u8 {
   Ayanamsha::from_key(key).as_u8()
 }
@@ -1,5 +1,18 @@
 use libswe_sys::sweconst::Bodies;
-use crate::calc::traits::*;
+use crate::calc::{errors::CalcError, traits::*};
+
+/*
+* Body keys mapped to the Bodies enum. Bodies::from_key falls back to Earth for any other key,
+* so input keys should be validated first
+*/
+pub const BODY_KEYS: [&'static str; 20] = [
+  "su", "mo", "me", "ve", "ea", "ma", "ju", "sa", "ne", "ur", "pl", "ke", "ra", "mn", "kr", "is", "jn", "ce", "ch", "sn",
+];
+
+/*
+* Group keys resolved to default body lists by the routes
+*/
+pub const BODY_GROUP_KEYS: [&'static str; 2] = ["all", "core"];
 
 impl FromKey<Bodies> for Bodies {
   fn from_key(key: &str) -> Bodies {
@@ -59,7 +72,7 @@ impl ToKey<Bodies> for Bodies {
 
 pub trait PlanetNum {
   fn to_num(&self) -> u16;
-  fn from_num(num: u16) -> Self;
+  fn from_num(num: u16) -> Option<Self> where Self: Sized;
 }
 
 impl PlanetNum for Bodies {
@@ -67,8 +80,8 @@ impl PlanetNum for Bodies {
     *self as u16
   }
 
-  fn from_num(num: u16) -> Self {
-    Bodies::from_key(planet_num_to_graha_key(num))
+  fn from_num(num: u16) -> Option<Self> {
+    planet_num_to_graha_key(num).map(|key| Bodies::from_key(key))
   }
 
 }
@@ -76,23 +89,71 @@ impl PlanetNum for Bodies {
 /*
 * convert body number to key
 */
-pub fn planet_num_to_graha_key(num: u16) -> &'static str {
+pub fn planet_num_to_graha_key(num: u16) -> Option<&'static str> {
   match num {
-    0 => "su",
-    1 => "mo",
-    2 => "me",
-    3 => "ve",
-    4 => "ma",
-    5 => "ju",
-    6 => "sa",
-    7 => "ur",
-    8 => "ne",
-    9 => "pl",
+    0 => Some("su"),
+    1 => Some("mo"),
+    2 => Some("me"),
+    3 => Some("ve"),
+    4 => Some("ma"),
+    5 => Some("ju"),
+    6 => Some("sa"),
+    7 => Some("ur"),
+    8 => Some("ne"),
+    9 => Some("pl"),
     //101 => "ra",
-    11 => "ra",
-    102 => "ke",
-    _ => ""
+    11 => Some("ra"),
+    102 => Some("ke"),
+    _ => None
+  }
+}
+
+pub fn is_valid_body_key(key: &str) -> bool {
+  BODY_KEYS.contains(&key.to_lowercase().as_str())
+}
+
+/*
+* Strict variant of Bodies::from_key for user input
+*/
+pub fn match_body(key: &str) -> Result<Bodies, CalcError> {
+  if is_valid_body_key(key) {
+    Ok(Bodies::from_key(key))
+  } else {
+    Err(CalcError::InvalidBodyKeys(vec![key.to_string()]))
   }
 }
 
+/*
+* Numbered asteroids are referenced as ast:NNN, e.g. ast:136199 for Eris, and map to
+* the Swiss Ephemeris body number offset by SE_AST_OFFSET
+*/
+pub const AST_OFFSET: i32 = 10000;
+pub const AST_KEY_PREFIX: &str = "ast:";
+
+pub fn asteroid_num(key: &str) -> Option<u32> {
+  let simple_key = key.trim().to_lowercase();
+  if let Some(num_str) = simple_key.strip_prefix(AST_KEY_PREFIX) {
+    match num_str.parse::<u32>() {
+      Ok(num) => if num > 0 { Some(num) } else { None },
+      _ => None,
+    }
+  } else {
+    None
+  }
+}
 
+/*
+* Swiss Ephemeris body number for standard body keys and numbered asteroids.
+* Unknown keys are rejected rather than mapped to the earth as by Bodies::from_key
+*/
+pub fn body_key_to_num(key: &str) -> Result<i32, CalcError> {
+  if let Some(num) = asteroid_num(key) {
+    Ok(AST_OFFSET + num as i32)
+  } else {
+    match key {
+      // the south node is not a Swiss Ephemeris body and, like Ketu, is derived from the true node
+      "sn" => Ok(Bodies::TrueNode as i32),
+      _ => match_body(key).map(|body| body as i32),
+    }
+  }
+}
@@ -0,0 +1,56 @@
+/*
+* Vimshottari lords with period lengths in years (120 in total). The sequence starts with the lord of Ashwini
+* and repeats every 9 nakshatras
+*/
+pub const VIMSHOTTARI_LORDS: [(&'static str, &'static str, f64); 9] = [
+  ("ke", "Ketu", 7f64),
+  ("ve", "Venus", 20f64),
+  ("su", "Sun", 6f64),
+  ("mo", "Moon", 10f64),
+  ("ma", "Mars", 7f64),
+  ("ra", "Rahu", 18f64),
+  ("ju", "Jupiter", 16f64),
+  ("sa", "Saturn", 19f64),
+  ("me", "Mercury", 17f64),
+];
+
+/*
+* Yogini names with their planetary rulers and period lengths in years (36 in total)
+*/
+pub const YOGINI_LORDS: [(&'static str, &'static str, f64); 8] = [
+  ("mo", "Mangala", 1f64),
+  ("su", "Pingala", 2f64),
+  ("ju", "Dhanya", 3f64),
+  ("ma", "Bhramari", 4f64),
+  ("me", "Bhadrika", 5f64),
+  ("sa", "Ulka", 6f64),
+  ("ve", "Siddha", 7f64),
+  ("ra", "Sankata", 8f64),
+];
+
+/*
+* Ashtottari lords with period lengths in years (108 in total)
+*/
+pub const ASHTOTTARI_LORDS: [(&'static str, &'static str, f64); 8] = [
+  ("su", "Sun", 6f64),
+  ("mo", "Moon", 15f64),
+  ("ma", "Mars", 8f64),
+  ("me", "Mercury", 17f64),
+  ("sa", "Saturn", 10f64),
+  ("ju", "Jupiter", 19f64),
+  ("ra", "Rahu", 12f64),
+  ("ve", "Venus", 21f64),
+];
+
+/*
+* Number of nakshatras ruled by each Ashtottari lord, starting from Ardra (6).
+* Abhijit is not counted separately, so Saturn rules Purva Ashadha, Uttara Ashadha and Shravana
+*/
+pub const ASHTOTTARI_GROUP_SIZES: [u16; 8] = [4, 3, 4, 3, 3, 3, 4, 3];
+
+pub const ASHTOTTARI_START_NAKSHATRA: u16 = 6;
+
+/*
+* Julian year by default. Some traditions use a 360-day savana year
+*/
+pub const DEFAULT_DASHA_YEAR_LENGTH: f64 = 365.25f64;
@@ -1,4 +1,8 @@
 pub mod ayanamshas;
 pub mod body_values;
 pub mod house_systems;
-/* pub mod varga_values; */
\ No newline at end of file
+pub mod nakshatras;
+pub mod stars;
+pub mod dashas;
+pub mod varga_values;
+pub mod panchanga;
\ No newline at end of file
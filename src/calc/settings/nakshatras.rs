@@ -0,0 +1,103 @@
+pub const NAKSHATRA_SPAN: f64 = 360f64 / 27f64;
+pub const PADA_SPAN: f64 = NAKSHATRA_SPAN / 4f64;
+pub const SIGN_SPAN: f64 = 30f64;
+
+pub const NAKSHATRA_NAMES: [&'static str; 27] = [
+  "Ashwini",
+  "Bharani",
+  "Krittika",
+  "Rohini",
+  "Mrigashira",
+  "Ardra",
+  "Punarvasu",
+  "Pushya",
+  "Ashlesha",
+  "Magha",
+  "Purva Phalguni",
+  "Uttara Phalguni",
+  "Hasta",
+  "Chitra",
+  "Swati",
+  "Vishakha",
+  "Anuradha",
+  "Jyeshtha",
+  "Mula",
+  "Purva Ashadha",
+  "Uttara Ashadha",
+  "Shravana",
+  "Dhanishta",
+  "Shatabhisha",
+  "Purva Bhadrapada",
+  "Uttara Bhadrapada",
+  "Revati",
+];
+
+pub const SIGN_NAMES: [&'static str; 12] = [
+  "Aries",
+  "Taurus",
+  "Gemini",
+  "Cancer",
+  "Leo",
+  "Virgo",
+  "Libra",
+  "Scorpio",
+  "Sagittarius",
+  "Capricorn",
+  "Aquarius",
+  "Pisces",
+];
+
+/*
+* Sanskrit names of the sidereal signs, used for solar ingresses (sankrantis)
+*/
+pub const RASHI_NAMES: [&'static str; 12] = [
+  "Mesha",
+  "Vrishabha",
+  "Mithuna",
+  "Karka",
+  "Simha",
+  "Kanya",
+  "Tula",
+  "Vrishchika",
+  "Dhanu",
+  "Makara",
+  "Kumbha",
+  "Mina",
+];
+
+/*
+* 1-based index of a longitude within equal divisions of the zodiac
+*/
+pub fn division_num(lng: f64, span: f64) -> u16 {
+  ((lng.rem_euclid(360f64) / span).floor() as u16) + 1
+}
+
+pub fn sign_num(lng: f64) -> u16 {
+  division_num(lng, SIGN_SPAN)
+}
+
+pub fn nakshatra_num(lng: f64) -> u16 {
+  division_num(lng, NAKSHATRA_SPAN)
+}
+
+/*
+* Pada (quarter) within the current nakshatra, 1 to 4
+*/
+pub fn pada_num(lng: f64) -> u16 {
+  ((division_num(lng, PADA_SPAN) - 1) % 4) + 1
+}
+
+pub fn nakshatra_name(num: u16) -> String {
+  let index = if num > 0 { (num as usize - 1) % 27 } else { 0 };
+  NAKSHATRA_NAMES[index].to_string()
+}
+
+pub fn sign_name(num: u16) -> String {
+  let index = if num > 0 { (num as usize - 1) % 12 } else { 0 };
+  SIGN_NAMES[index].to_string()
+}
+
+pub fn rashi_name(num: u16) -> String {
+  let index = if num > 0 { (num as usize - 1) % 12 } else { 0 };
+  RASHI_NAMES[index].to_string()
+}
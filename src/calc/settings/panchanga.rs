@@ -0,0 +1,133 @@
+/*
+* Tithi names within each paksha. The 15th tithi is Purnima in the bright half and Amavasya in the dark half
+*/
+pub const TITHI_NAMES: [&'static str; 14] = [
+  "Pratipada",
+  "Dwitiya",
+  "Tritiya",
+  "Chaturthi",
+  "Panchami",
+  "Shashthi",
+  "Saptami",
+  "Ashtami",
+  "Navami",
+  "Dashami",
+  "Ekadashi",
+  "Dwadashi",
+  "Trayodashi",
+  "Chaturdashi",
+];
+
+pub const YOGA_NAMES: [&'static str; 27] = [
+  "Vishkambha",
+  "Priti",
+  "Ayushman",
+  "Saubhagya",
+  "Shobhana",
+  "Atiganda",
+  "Sukarma",
+  "Dhriti",
+  "Shula",
+  "Ganda",
+  "Vriddhi",
+  "Dhruva",
+  "Vyaghata",
+  "Harshana",
+  "Vajra",
+  "Siddhi",
+  "Vyatipata",
+  "Variyana",
+  "Parigha",
+  "Shiva",
+  "Siddha",
+  "Sadhya",
+  "Shubha",
+  "Shukla",
+  "Brahma",
+  "Indra",
+  "Vaidhriti",
+];
+
+/*
+* Movable karanas repeat 8 times from the 2nd to the 57th half-tithi
+*/
+pub const MOVABLE_KARANA_NAMES: [&'static str; 7] = [
+  "Bava",
+  "Balava",
+  "Kaulava",
+  "Taitila",
+  "Garaja",
+  "Vanija",
+  "Vishti",
+];
+
+/*
+* Weekday names and lords from Sunday
+*/
+pub const VARA_NAMES: [(&'static str, &'static str); 7] = [
+  ("Ravivara", "su"),
+  ("Somavara", "mo"),
+  ("Mangalavara", "ma"),
+  ("Budhavara", "me"),
+  ("Guruvara", "ju"),
+  ("Shukravara", "ve"),
+  ("Shanivara", "sa"),
+];
+
+/*
+* Lunar month names from Chaitra, which starts with the new moon while the sidereal Sun is in Mina (Pisces)
+*/
+pub const MASA_NAMES: [&'static str; 12] = [
+  "Chaitra",
+  "Vaishakha",
+  "Jyeshtha",
+  "Ashadha",
+  "Shravana",
+  "Bhadrapada",
+  "Ashvina",
+  "Kartika",
+  "Margashirsha",
+  "Pausha",
+  "Magha",
+  "Phalguna",
+];
+
+/*
+* 1-based tithi number within the lunar month (1-30)
+*/
+pub fn tithi_name(num: u16) -> String {
+  let paksha = if num <= 15 { "Shukla" } else { "Krishna" };
+  let day = ((num as usize).max(1) - 1) % 15;
+  let name = match num {
+    15 => "Purnima",
+    30 => "Amavasya",
+    _ => TITHI_NAMES[day],
+  };
+  format!("{} {}", paksha, name)
+}
+
+/*
+* 1-based lunar month number (1 = Chaitra)
+*/
+pub fn masa_name(num: u8) -> String {
+  let index = if num > 0 { (num as usize - 1) % 12 } else { 0 };
+  MASA_NAMES[index].to_string()
+}
+
+pub fn yoga_name(num: u16) -> String {
+  let index = if num > 0 { (num as usize - 1) % 27 } else { 0 };
+  YOGA_NAMES[index].to_string()
+}
+
+/*
+* 1-based karana number within the lunar month (1-60). The first and last three are fixed karanas
+*/
+pub fn karana_name(num: u16) -> String {
+  match num {
+    1 => "Kimstughna",
+    58 => "Shakuni",
+    59 => "Chatushpada",
+    60 => "Naga",
+    _ => MOVABLE_KARANA_NAMES[((num as usize).max(2) - 2) % 7],
+  }.to_string()
+}
@@ -0,0 +1,73 @@
+use serde::{Serialize, Deserialize};
+
+/*
+* Key, traditional name, Swiss Ephemeris nomenclature (Bayer designation) and alternative names incl. Indian star names
+*/
+pub const FIXED_STARS: [(&'static str, &'static str, &'static str, &'static [&'static str]); 26] = [
+  ("regulus", "Regulus", ",alLeo", &["magha"]),
+  ("spica", "Spica", ",alVir", &["citra", "chitra"]),
+  ("aldebaran", "Aldebaran", ",alTau", &["rohini"]),
+  ("antares", "Antares", ",alSco", &["jyeshtha"]),
+  ("fomalhaut", "Fomalhaut", ",alPsA", &[]),
+  ("sirius", "Sirius", ",alCMa", &[]),
+  ("algol", "Algol", ",bePer", &[]),
+  ("arcturus", "Arcturus", ",alBoo", &["swati"]),
+  ("vega", "Vega", ",alLyr", &["abhijit"]),
+  ("altair", "Altair", ",alAql", &["shravana"]),
+  ("betelgeuse", "Betelgeuse", ",alOri", &["ardra"]),
+  ("rigel", "Rigel", ",beOri", &[]),
+  ("castor", "Castor", ",alGem", &[]),
+  ("pollux", "Pollux", ",beGem", &["punarvasu"]),
+  ("procyon", "Procyon", ",alCMi", &[]),
+  ("capella", "Capella", ",alAur", &[]),
+  ("canopus", "Canopus", ",alCar", &["agastya"]),
+  ("deneb", "Deneb", ",alCyg", &[]),
+  ("denebola", "Denebola", ",beLeo", &[]),
+  ("alcyone", "Alcyone", ",etTau", &["krittika"]),
+  ("achernar", "Achernar", ",alEri", &[]),
+  ("polaris", "Polaris", ",alUMi", &["dhruva"]),
+  ("vindemiatrix", "Vindemiatrix", ",epVir", &[]),
+  ("hamal", "Hamal", ",alAri", &[]),
+  ("scheat", "Scheat", ",bePeg", &[]),
+  ("markab", "Markab", ",alPeg", &[]),
+];
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FixedStar {
+  pub key: String,
+  pub name: String,
+  pub nomenclature: String,
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub aliases: Vec<String>,
+}
+
+impl FixedStar {
+  pub fn new(key: &str, name: &str, nomenclature: &str, aliases: &[&str]) -> Self {
+    FixedStar {
+      key: key.to_string(),
+      name: name.to_string(),
+      nomenclature: nomenclature.to_string(),
+      aliases: aliases.iter().map(|a| a.to_string()).collect(),
+    }
+  }
+
+  pub fn matches(&self, ref_key: &str) -> bool {
+    let simple_key = ref_key.trim().to_lowercase();
+    self.key == simple_key || self.name.to_lowercase() == simple_key || self.aliases.contains(&simple_key)
+  }
+}
+
+pub fn all_fixed_stars() -> Vec<FixedStar> {
+  FIXED_STARS.iter().map(|(key, name, nomenclature, aliases)| FixedStar::new(key, name, nomenclature, aliases)).collect()
+}
+
+/*
+* Match a catalogue star by key, traditional name or alias
+*/
+pub fn match_fixed_star(key: &str) -> Option<FixedStar> {
+  all_fixed_stars().into_iter().find(|star| star.matches(key))
+}
+
+pub fn is_fixed_star_key(key: &str) -> bool {
+  key.len() > 2 && match_fixed_star(key).is_some()
+}
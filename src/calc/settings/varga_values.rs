@@ -0,0 +1,56 @@
+/*
+* Divisional charts by number of divisions per sign with their Sanskrit names
+*/
+pub const VARGAS: [(u8, &'static str); 16] = [
+  (1, "Rashi"),
+  (2, "Hora"),
+  (3, "Drekkana"),
+  (4, "Chaturthamsha"),
+  (7, "Saptamsha"),
+  (9, "Navamsha"),
+  (10, "Dashamsha"),
+  (12, "Dwadashamsha"),
+  (16, "Shodashamsha"),
+  (20, "Vimshamsha"),
+  (24, "Chaturvimshamsha"),
+  (27, "Bhamsha"),
+  (30, "Trimshamsha"),
+  (40, "Khavedamsha"),
+  (45, "Akshavedamsha"),
+  (60, "Shashtiamsha"),
+];
+
+/*
+* Shodashavarga core set as used in most Parashari charts
+*/
+pub const CORE_VARGAS: [u8; 6] = [1, 2, 3, 9, 12, 30];
+
+/*
+* Trimshamsha boundaries within odd signs and the 1-based signs of their rulers (Mars, Saturn, Jupiter, Mercury, Venus).
+* The order is reversed in even signs
+*/
+pub const TRIMSHAMSHA_ODD: [(f64, u16); 5] = [(5f64, 1), (10f64, 11), (18f64, 9), (25f64, 3), (30f64, 7)];
+
+pub const TRIMSHAMSHA_EVEN: [(f64, u16); 5] = [(5f64, 2), (12f64, 6), (20f64, 12), (25f64, 10), (30f64, 8)];
+
+pub fn varga_name(num: u8) -> String {
+  VARGAS.iter().find(|(n, _)| *n == num).map(|(_, name)| name.to_string()).unwrap_or("".to_string())
+}
+
+pub fn is_varga_num(num: u8) -> bool {
+  VARGAS.iter().any(|(n, _)| *n == num)
+}
+
+/*
+* Match all, core or comma-separated division numbers with or without a d prefix, e.g. d9,d10 or 9,10
+*/
+pub fn match_varga_nums(key_string: &str) -> Vec<u8> {
+  match key_string.to_lowercase().as_str() {
+    "all" => VARGAS.iter().map(|(n, _)| *n).collect(),
+    "core" => CORE_VARGAS.to_vec(),
+    _ => key_string.split(",")
+      .filter_map(|k| k.trim().to_lowercase().trim_start_matches("d").parse::<u8>().ok())
+      .filter(|n| is_varga_num(*n))
+      .collect(),
+  }
+}
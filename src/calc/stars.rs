@@ -0,0 +1,234 @@
+use serde::{Serialize, Deserialize};
+use libswe_sys::sweconst::OptionalFlag;
+use crate::extensions::swe::{azalt, fixstar_mag, fixstar_ut, heliacal_ut, rise_trans_star, set_topo, HeliacalEvent};
+use super::{
+  aspects::{match_aspect, AspectPoint, AspectResult, AspectType},
+  math_funcs::subtract_360,
+  models::{general::{FlexiValue, KeyNumValue, KeyNumValueSet}, geo_pos::GeoPos, graha_pos::GrahaPos},
+  rise_set_phases::{start_jd_geo, TransitionGroup, TransitionMode, TransitionParams, TransitionSet},
+  settings::stars::{match_fixed_star, FixedStar},
+};
+
+/*
+* Fixed stars move less than 1' per century, so only close conjunctions are meaningful
+*/
+pub const DEFAULT_STAR_ORB: f64 = 1.5f64;
+
+/**
+ * Match a star in the catalogue or, failing that, any star known to the Swiss Ephemeris star file by name
+ */
+pub fn resolve_star(key: &str) -> Option<FixedStar> {
+  if let Some(star) = match_fixed_star(key) {
+    Some(star)
+  } else if key.len() > 2 && fixstar_mag(key).is_some() {
+    let simple_key = key.trim().to_lowercase();
+    Some(FixedStar::new(&simple_key, key.trim(), key.trim(), &[]))
+  } else {
+    None
+  }
+}
+
+/*
+* Catalogue stars only, so that 2-letter body keys and star names can be mixed in one list
+*/
+pub fn match_star_keys(key_string: &str) -> Vec<FixedStar> {
+  key_string.split(",").filter(|k| k.len() > 2).filter_map(|k| match_fixed_star(k)).collect()
+}
+
+fn star_flag(topo: bool, equatorial: bool) -> i32 {
+  let mut combo = OptionalFlag::SwissEph as i32 | OptionalFlag::Speed as i32;
+  if topo {
+    combo = combo | OptionalFlag::TopocentricPosition as i32;
+  }
+  if equatorial {
+    combo = combo | OptionalFlag::EquatorialPosition as i32;
+  }
+  combo
+}
+
+/**
+ * Ecliptic and equatorial coordinates of a fixed star with altitude and azimuth if a location is given.
+ * The ayanamsha offset is only subtracted from the ecliptic longitude. None if the star is missing from the star file
+ */
+pub fn calc_star_pos(jd: f64, star: &FixedStar, topo: bool, geo_opt: Option<GeoPos>, aya_offset: f64) -> Option<GrahaPos> {
+  let is_topo = topo && geo_opt.is_some();
+  if let Some(geo) = geo_opt {
+    if is_topo {
+      set_topo(geo.lat, geo.lng, geo.alt);
+    }
+  }
+  let result_ec = fixstar_ut(jd, star.nomenclature.as_str(), star_flag(is_topo, false));
+  let result = fixstar_ut(jd, star.nomenclature.as_str(), star_flag(is_topo, true));
+  if !result_ec.is_valid() || !result.is_valid() {
+    return None;
+  }
+  let altitude_set = match geo_opt {
+    Some(geo) => Some(azalt(jd, true, geo.lat, geo.lng, result.longitude, result.latitude)),
+    _ => None,
+  };
+  Some(GrahaPos::new_extended(
+    star.key.as_str(),
+    subtract_360(result_ec.longitude, aya_offset),
+    result_ec.latitude,
+    result.longitude,
+    result.latitude,
+    result_ec.speed_longitude,
+    result_ec.speed_latitude,
+    result.speed_longitude,
+    result.speed_latitude,
+    None,
+    altitude_set.map(|a_set| a_set.value),
+    altitude_set.map(|a_set| a_set.azimuth),
+  ))
+}
+
+pub fn calc_stars_pos(jd: f64, stars: &Vec<FixedStar>, topo: bool, geo_opt: Option<GeoPos>, aya_offset: f64) -> Vec<GrahaPos> {
+  stars.iter().filter_map(|star| calc_star_pos(jd, star, topo, geo_opt, aya_offset)).collect()
+}
+
+/**
+ * Next rise, MC, set and IC of a fixed star from the start of the local solar day.
+ * Circumpolar stars have no rise and set times
+ */
+pub fn calc_star_transition_set(jd: f64, star: &FixedStar, geo: GeoPos, mode: TransitionMode) -> TransitionSet {
+  let ref_jd = start_jd_geo(jd, geo.lng);
+  let (alignment, no_refrac) = mode.to_options();
+  let name = star.nomenclature.as_str();
+  let rise = rise_trans_star(ref_jd, name, geo.lat, geo.lng, TransitionParams::rise(alignment, no_refrac));
+  let set_start = if rise > 0f64 { rise } else { ref_jd };
+  let set = rise_trans_star(set_start, name, geo.lat, geo.lng, TransitionParams::set(alignment, no_refrac));
+  let mc = rise_trans_star(ref_jd, name, geo.lat, geo.lng, TransitionParams::mc());
+  let ic = rise_trans_star(mc, name, geo.lat, geo.lng, TransitionParams::ic());
+  TransitionSet { rise, mc, set, ic }
+}
+
+pub fn get_star_transition_sets(jd: f64, stars: &Vec<FixedStar>, geo: GeoPos, mode: TransitionMode) -> Vec<KeyNumValueSet> {
+  stars.iter().map(|star| KeyNumValueSet::new(star.key.as_str(), calc_star_transition_set(jd, star, geo, mode).to_key_nums())).collect()
+}
+
+/**
+ * Heliacal rising (first morning visibility) and setting (last evening visibility) after the reference date
+ */
+pub fn calc_star_heliacal_events(jd: f64, star: &FixedStar, geo: GeoPos, iso_mode: bool) -> Vec<FlexiValue> {
+  let mut items: Vec<FlexiValue> = Vec::new();
+  let name = star.nomenclature.as_str();
+  if let Some(rise_jd) = heliacal_ut(jd, name, geo.lat, geo.lng, geo.alt, HeliacalEvent::Rising) {
+    items.push(KeyNumValue::new("rising", rise_jd).as_flexi_value(iso_mode));
+  }
+  if let Some(set_jd) = heliacal_ut(jd, name, geo.lat, geo.lng, geo.alt, HeliacalEvent::Setting) {
+    items.push(KeyNumValue::new("setting", set_jd).as_flexi_value(iso_mode));
+  }
+  items
+}
+
+/**
+ * Conjunctions of a star with planets within orb. The star is treated as stationary
+ */
+pub fn calc_star_conjunctions(star_pos: &GrahaPos, bodies: &Vec<GrahaPos>, orb: Option<f64>) -> Vec<AspectResult> {
+  let conjunction = vec![AspectType::Conjunction];
+  let max_orb = Some(orb.unwrap_or(DEFAULT_STAR_ORB));
+  let star_point = AspectPoint::new(star_pos.key.as_str(), star_pos.lng, 0f64);
+  bodies.iter()
+    .map(|b| AspectPoint::new(b.key.as_str(), b.lng, b.lng_speed))
+    .filter_map(|p| match_aspect(&p, &star_point, &conjunction, max_orb))
+    .collect()
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StarInfo {
+  pub key: String,
+  pub name: String,
+  pub nomenclature: String,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub magnitude: Option<f64>,
+  pub position: GrahaPos,
+  #[serde(rename = "riseSets", skip_serializing_if = "Vec::is_empty")]
+  pub rise_sets: Vec<FlexiValue>,
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub heliacal: Vec<FlexiValue>,
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub conjunctions: Vec<AspectResult>,
+}
+
+impl StarInfo {
+  pub fn new(star: &FixedStar, position: GrahaPos) -> Self {
+    StarInfo {
+      key: star.key.clone(),
+      name: star.name.clone(),
+      nomenclature: star.nomenclature.clone(),
+      magnitude: fixstar_mag(star.nomenclature.as_str()),
+      position,
+      rise_sets: vec![],
+      heliacal: vec![],
+      conjunctions: vec![],
+    }
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use libswe_sys::swerust::handler_swe02::set_ephe_path;
+  use crate::ephemeris::with_ephemeris;
+  use crate::calc::settings::stars::is_fixed_star_key;
+
+  /*
+  * Star positions require sefstars.txt, which is not part of the repository. Tests depending on it are ignored by default
+  * and run with the path to the star file, e.g. SE_EPHE_PATH=/path/to/ephe cargo test -- --ignored
+  */
+  fn has_star_file() -> bool {
+    set_ephe_path(std::env::var("SE_EPHE_PATH").unwrap_or_default().as_str());
+    fixstar_mag(",alTau").is_some()
+  }
+
+  #[test]
+  fn test_resolve_star() {
+    with_ephemeris(|| {
+      assert_eq!(resolve_star("Rohini").map(|star| star.key), Some("aldebaran".to_string()));
+      assert!(is_fixed_star_key("spica"));
+      assert!(!is_fixed_star_key("su"));
+      assert!(resolve_star("nostar").is_none());
+      if has_star_file() {
+        // stars outside the catalogue are looked up in the star file
+        assert_eq!(resolve_star("Mizar").map(|star| star.nomenclature), Some("Mizar".to_string()));
+      }
+    });
+  }
+
+  #[test]
+  #[ignore = "requires sefstars.txt in SE_EPHE_PATH"]
+  fn test_stars_pos() {
+    with_ephemeris(|| {
+      assert!(has_star_file(), "sefstars.txt not found in SE_EPHE_PATH");
+      let stars = vec![match_fixed_star("aldebaran").unwrap(), match_fixed_star("regulus").unwrap()];
+      let items = calc_stars_pos(2451545f64, &stars, false, None, 0f64);
+      // J2000: Aldebaran at 9º47' Gemini, Regulus at 29º50' Leo
+      assert_eq!(items.len(), 2);
+      assert!((items[0].lng - 69.79).abs() < 0.01);
+      assert!((items[0].lat + 5.47).abs() < 0.01);
+      assert!((items[1].lng - 149.83).abs() < 0.01);
+    });
+  }
+
+  #[test]
+  #[ignore = "requires sefstars.txt in SE_EPHE_PATH"]
+  fn test_star_heliacal_events() {
+    with_ephemeris(|| {
+      assert!(has_star_file(), "sefstars.txt not found in SE_EPHE_PATH");
+      // Sirius from Cairo after 1 January 2024: last evening visibility on 25 May, first morning visibility on 6 August
+      let sirius = match_fixed_star("sirius").unwrap();
+      let cairo = GeoPos::new(30.0444, 31.2357, 23f64);
+      let items = calc_star_heliacal_events(2460310.5, &sirius, cairo, false);
+      let jds: Vec<(String, f64)> = items.iter().filter_map(|item| match item {
+        FlexiValue::NumValue(kv) => Some((kv.key.clone(), kv.value)),
+        _ => None,
+      }).collect();
+      assert_eq!(jds.len(), 2);
+      assert_eq!(jds[0].0, "rising");
+      assert!((jds[0].1 - 2460528.61).abs() < 1f64);
+      assert_eq!(jds[1].0, "setting");
+      assert!((jds[1].1 - 2460456.21).abs() < 1f64);
+    });
+  }
+}
@@ -0,0 +1,175 @@
+use chrono::{Duration, FixedOffset, LocalResult, NaiveDateTime, Offset, TimeZone};
+use chrono_tz::Tz;
+use julian_day_converter::*;
+use serde_json::{Map, Value};
+use super::{dates::iso_string_to_datetime, errors::CalcError, models::date_info::DateInfo};
+
+/*
+* Julian days in responses are recognised by range, roughly from 2000 BCE to 9000 CE.
+* Unix timestamps, longitudes and other numeric values fall outside this range
+*/
+pub const MIN_LOCAL_JD: f64 = 1_000_000f64;
+pub const MAX_LOCAL_JD: f64 = 5_000_000f64;
+
+/*
+* IANA zone with historical DST rules from the bundled tz database, or a fixed offset in seconds
+*/
+#[derive(Debug, Copy, Clone)]
+pub enum TimeZoneRef {
+  Iana(Tz),
+  Fixed(i32),
+}
+
+impl TimeZoneRef {
+  pub fn name(&self) -> String {
+    match self {
+      TimeZoneRef::Iana(tz) => tz.name().to_string(),
+      TimeZoneRef::Fixed(secs) => format_offset(*secs),
+    }
+  }
+
+  /*
+  * Offset from UTC in seconds applicable at the given UTC time
+  */
+  pub fn offset_secs_utc(&self, dt: &NaiveDateTime) -> i32 {
+    match self {
+      TimeZoneRef::Iana(tz) => tz.offset_from_utc_datetime(dt).fix().local_minus_utc(),
+      TimeZoneRef::Fixed(secs) => *secs,
+    }
+  }
+
+  pub fn offset_secs_jd(&self, jd: f64) -> i32 {
+    match julian_day_to_datetime(jd) {
+      Ok(dt) => self.offset_secs_utc(&dt),
+      _ => 0,
+    }
+  }
+
+  /*
+  * Resolve local wall-clock time to UTC. Ambiguous times at the end of DST resolve to the earlier instant,
+  * and non-existent times at the start of DST are moved forward by the gap
+  */
+  pub fn local_to_utc(&self, local: &NaiveDateTime) -> NaiveDateTime {
+    match self {
+      TimeZoneRef::Iana(tz) => match tz.from_local_datetime(local) {
+        LocalResult::Single(dt) | LocalResult::Ambiguous(dt, _) => dt.naive_utc(),
+        LocalResult::None => {
+          // skipped local times in a DST gap are shifted forward by applying the offset in force before the transition
+          let offset = tz.offset_from_utc_datetime(&(*local - Duration::days(1))).fix().local_minus_utc();
+          *local - Duration::seconds(offset as i64)
+        },
+      },
+      TimeZoneRef::Fixed(secs) => *local - Duration::seconds(*secs as i64),
+    }
+  }
+
+  /*
+  * Local ISO 8601 datetime with the applicable offset, e.g. 2023-07-01T14:00:00+02:00
+  */
+  pub fn to_local_iso(&self, jd: f64) -> String {
+    match julian_day_to_datetime(jd) {
+      Ok(dt) => {
+        let offset = self.offset_secs_utc(&dt);
+        match FixedOffset::east_opt(offset) {
+          Some(fixed) => fixed.from_utc_datetime(&dt).format("%Y-%m-%dT%H:%M:%S%:z").to_string(),
+          _ => "".to_string(),
+        }
+      },
+      _ => "".to_string(),
+    }
+  }
+}
+
+pub fn format_offset(secs: i32) -> String {
+  let sign = if secs < 0 { "-" } else { "+" };
+  let abs_secs = secs.abs();
+  format!("{}{:02}:{:02}", sign, abs_secs / 3600, (abs_secs % 3600) / 60)
+}
+
+pub fn match_time_zone(name: &str) -> Result<TimeZoneRef, CalcError> {
+  match name.trim().parse::<Tz>() {
+    Ok(tz) => Ok(TimeZoneRef::Iana(tz)),
+    _ => Err(CalcError::InvalidTimeZone(name.to_string())),
+  }
+}
+
+/**
+ * Parse a local date string and convert it to UTC with the time zone rules applicable on that date
+ */
+pub fn local_date_to_date_info(dateref: &str, tz: &TimeZoneRef) -> Result<DateInfo, CalcError> {
+  let local = iso_string_to_datetime(dateref)?;
+  Ok(DateInfo::new_from_jd(tz.local_to_utc(&local).to_jd()))
+}
+
+/*
+* Match ISO UTC datetime strings as generated by julian_day_to_iso_datetime
+*/
+fn is_iso_utc_string(value: &str) -> bool {
+  value.len() == 19 && value.chars().nth(10) == Some('T') && NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S").is_ok()
+}
+
+fn to_local_value(value: &Value, tz: &TimeZoneRef) -> Option<Value> {
+  match value {
+    Value::String(s) => if is_iso_utc_string(s) {
+      NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S").ok().map(|dt| Value::String(tz.to_local_iso(dt.to_jd())))
+    } else {
+      None
+    },
+    Value::Number(n) => match n.as_f64() {
+      Some(jd) => if jd > MIN_LOCAL_JD && jd < MAX_LOCAL_JD && !n.is_i64() { Some(Value::String(tz.to_local_iso(jd))) } else { None },
+      _ => None,
+    },
+    _ => None,
+  }
+}
+
+/**
+ * Mirror every julian day and ISO UTC datetime in a JSON response as local time.
+ * Each matching field gets a sibling field with the Local suffix, e.g. jd => jdLocal
+ */
+pub fn add_local_times(value: Value, tz: &TimeZoneRef) -> Value {
+  match value {
+    Value::Object(map) => {
+      let mut new_map = Map::new();
+      for (key, item) in map.into_iter() {
+        let local_opt = to_local_value(&item, tz);
+        new_map.insert(key.clone(), add_local_times(item, tz));
+        if let Some(local) = local_opt {
+          new_map.insert(format!("{}Local", key), local);
+        }
+      }
+      Value::Object(new_map)
+    },
+    Value::Array(items) => Value::Array(items.into_iter().map(|item| add_local_times(item, tz)).collect()),
+    _ => value,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_dst_offsets() {
+    let tz = match_time_zone("Europe/Rome").unwrap();
+    // 1 January 2000 (CET) and 1 July 2000 (CEST)
+    assert_eq!(tz.offset_secs_jd(2451545f64), 3600);
+    assert_eq!(tz.offset_secs_jd(2451727f64), 7200);
+    assert!(match_time_zone("Europe/Nowhere").is_err());
+  }
+
+  #[test]
+  fn test_local_to_utc() {
+    let tz = match_time_zone("America/New_York").unwrap();
+    let local = NaiveDateTime::parse_from_str("2023-07-01T12:00:00", "%Y-%m-%dT%H:%M:%S").unwrap();
+    assert_eq!(tz.local_to_utc(&local).format("%H:%M").to_string(), "16:00");
+  }
+
+  #[test]
+  fn test_local_to_utc_dst_gap() {
+    // 01:30 does not exist in London on 31 March 2024 and becomes 01:30 UTC (02:30 BST)
+    let tz = match_time_zone("Europe/London").unwrap();
+    let local = NaiveDateTime::parse_from_str("2024-03-31T01:30:00", "%Y-%m-%dT%H:%M:%S").unwrap();
+    assert_eq!(tz.local_to_utc(&local).format("%Y-%m-%d %H:%M").to_string(), "2024-03-31 01:30");
+  }
+}
@@ -0,0 +1,174 @@
+use serde::{Serialize, Deserialize};
+use super::{
+  core::calc_body_jd_geo,
+  dates::julian_day_to_iso_datetime,
+  math_funcs::normalize_360,
+  search::{find_angle_roots, interpolate_linear, min_search_step},
+};
+
+/*
+* A transit is measured against another moving body or a fixed ecliptic longitude, e.g. a natal position
+*/
+#[derive(Debug, Clone)]
+pub enum TransitRef {
+  Body(String),
+  Point(f64),
+}
+
+impl TransitRef {
+  pub fn key(&self) -> String {
+    match self {
+      TransitRef::Body(key) => key.clone(),
+      _ => "".to_string(),
+    }
+  }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TransitEvent {
+  pub key: String,
+  #[serde(rename = "refKey", skip_serializing_if = "String::is_empty")]
+  pub ref_key: String,
+  #[serde(rename = "refLng")]
+  pub ref_lng: f64,
+  pub angle: f64,
+  pub jd: f64,
+  #[serde(skip_serializing_if = "String::is_empty")]
+  pub dt: String,
+  pub lng: f64,
+  #[serde(rename = "lngSpeed")]
+  pub lng_speed: f64,
+  pub retro: bool,
+}
+
+/*
+* Ayanamsha values change very slowly and almost linearly over the search range
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct AyanamshaRange {
+  pub start_jd: f64,
+  pub start: f64,
+  pub end_jd: f64,
+  pub end: f64,
+}
+
+impl AyanamshaRange {
+  pub fn new(start_jd: f64, start: f64, end_jd: f64, end: f64) -> Self {
+    AyanamshaRange { start_jd, start, end_jd, end }
+  }
+
+  pub fn tropical() -> Self {
+    AyanamshaRange { start_jd: 0f64, start: 0f64, end_jd: 0f64, end: 0f64 }
+  }
+
+  pub fn value(&self, jd: f64) -> f64 {
+    interpolate_linear(jd, self.start_jd, self.start, self.end_jd, self.end)
+  }
+}
+
+/*
+* Exact aspects other than the conjunction and opposition occur on either side of the reference
+*/
+pub fn to_target_angles(angles: &Vec<f64>) -> Vec<f64> {
+  let mut targets: Vec<f64> = Vec::new();
+  for angle in angles {
+    let deg = normalize_360(*angle);
+    if !targets.contains(&deg) {
+      targets.push(deg);
+    }
+    let opp_deg = normalize_360(360f64 - deg);
+    if !targets.contains(&opp_deg) {
+      targets.push(opp_deg);
+    }
+  }
+  targets
+}
+
+fn ref_longitude(jd: f64, reference: &TransitRef, aya: &AyanamshaRange) -> f64 {
+  match reference {
+    TransitRef::Body(key) => calc_body_jd_geo(jd, key.as_str(), aya.value(jd)).lng,
+    TransitRef::Point(lng) => *lng,
+  }
+}
+
+/**
+ * Find the exact times when a body reaches the given angle from the reference body or point
+ * between start_jd and end_jd. Retrograde bodies may reach the same angle up to three times.
+ */
+pub fn calc_transits(key: &str, reference: &TransitRef, target: f64, start_jd: f64, end_jd: f64, aya: &AyanamshaRange, iso_mode: bool) -> Vec<TransitEvent> {
+  let mut step_keys = vec![key];
+  let ref_key = reference.key();
+  if ref_key.len() > 0 {
+    step_keys.push(ref_key.as_str());
+  }
+  let step = min_search_step(&step_keys);
+  let roots = find_angle_roots(start_jd, end_jd, step, |jd| {
+    let pos = calc_body_jd_geo(jd, key, aya.value(jd));
+    pos.lng - ref_longitude(jd, reference, aya) - target
+  });
+  roots.into_iter().map(|jd| {
+    let pos = calc_body_jd_geo(jd, key, aya.value(jd));
+    let ref_lng = ref_longitude(jd, reference, aya);
+    let dt = if iso_mode { julian_day_to_iso_datetime(jd) } else { "".to_string() };
+    TransitEvent {
+      key: key.to_string(),
+      ref_key: ref_key.clone(),
+      ref_lng,
+      angle: target,
+      jd,
+      dt,
+      lng: pos.lng,
+      lng_speed: pos.lng_speed,
+      retro: pos.lng_speed < 0f64,
+    }
+  }).collect()
+}
+
+/**
+ * Find the exact times when the body reaches a target longitude
+ */
+pub fn calc_longitude_transits(key: &str, lng: f64, start_jd: f64, end_jd: f64, aya: &AyanamshaRange, iso_mode: bool) -> Vec<TransitEvent> {
+  calc_transits(key, &TransitRef::Point(0f64), normalize_360(lng), start_jd, end_jd, aya, iso_mode)
+}
+
+/**
+ * Find exact aspects for each angle, e.g. 90º for squares matched on both sides of the reference,
+ * sorted in chronological order
+ */
+pub fn calc_aspect_transits(key: &str, reference: &TransitRef, angles: &Vec<f64>, start_jd: f64, end_jd: f64, aya: &AyanamshaRange, iso_mode: bool) -> Vec<TransitEvent> {
+  let mut items: Vec<TransitEvent> = Vec::new();
+  for target in to_target_angles(angles) {
+    let mut rows = calc_transits(key, reference, target, start_jd, end_jd, aya, iso_mode);
+    items.append(&mut rows);
+  }
+  items.sort_by(|a, b| a.jd.total_cmp(&b.jd));
+  items
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::ephemeris::with_ephemeris;
+
+  #[test]
+  fn test_sun_aries_ingress() {
+    // March equinox on 20 March 2024 at 03:06 UT
+    let items = with_ephemeris(|| calc_longitude_transits("su", 0f64, 2460380.5, 2460400.5, &AyanamshaRange::tropical(), true));
+    assert_eq!(items.len(), 1);
+    assert!((items[0].jd - 2460389.629).abs() < 2.0 / 1440.0);
+    assert!(items[0].dt.starts_with("2024-03-20T03:0"));
+    assert!(!items[0].retro);
+  }
+
+  #[test]
+  fn test_mars_station_passes() {
+    // Mars entered Leo in November 2024, retrograded back into Cancer in January 2025 and re-entered Leo in April
+    let items = with_ephemeris(|| calc_longitude_transits("ma", 120f64, 2460584.5, 2460827.5, &AyanamshaRange::tropical(), false));
+    assert_eq!(items.len(), 3);
+    let retro: Vec<bool> = items.iter().map(|item| item.retro).collect();
+    assert_eq!(retro, vec![false, true, false]);
+    assert!((items[0].jd - 2460618.5).abs() < 1.5);
+    assert!((items[1].jd - 2460681.5).abs() < 1.5);
+    assert!((items[2].jd - 2460783.5).abs() < 1.5);
+  }
+}
@@ -1,4 +1,17 @@
-use std::{fs, path::Path};
+use std::{fs, path::Path, sync::OnceLock};
+
+/*
+* Ephemeris path validated at startup, empty if the server reverts to the Moshier formula
+*/
+static VALIDATED_EPHEMERIS_PATH: OnceLock<String> = OnceLock::new();
+
+pub fn set_validated_ephemeris_path(path: &str) {
+  let _ = VALIDATED_EPHEMERIS_PATH.set(path.to_string());
+}
+
+pub fn validated_ephemeris_path() -> Option<&'static str> {
+  VALIDATED_EPHEMERIS_PATH.get().map(|path| path.as_str()).filter(|path| path.len() > 0)
+}
 
 pub fn validate_directory(ephemeris_path: &String) -> (bool, String) {
   let ephe_directory = Path::new(ephemeris_path);
@@ -32,3 +45,16 @@ fn validate_files(validated_path: &str) -> bool {
   }
   num_matched > 12
 }
+
+/*
+* Swiss Ephemeris asteroid files are named se01234.se1 (or s136199.se1 above 99999) with an s suffix
+* for short 600-year files and are stored in astNNN subdirectories grouped by thousands or directly in the ephemeris path
+*/
+pub fn asteroid_file_exists(ephemeris_path: &str, num: u32) -> bool {
+  let base = if num > 99999 { format!("s{:06}", num) } else { format!("se{:05}", num) };
+  let sub_dir = format!("ast{}", num / 1000);
+  let ephe_directory = Path::new(ephemeris_path);
+  vec![format!("{}.se1", base), format!("{}s.se1", base)].into_iter().any(|file_name| {
+    ephe_directory.join(&sub_dir).join(&file_name).is_file() || ephe_directory.join(&file_name).is_file()
+  })
+}
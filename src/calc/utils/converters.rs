@@ -1,18 +1,34 @@
-use julian_day_converter::julian_day_to_datetime;
 use crate::calc::models::geo_pos::GeoPos;
+use crate::calc::seasons::calc_equinoxes_solstices;
+use crate::calc::settings::body_values::asteroid_num;
+use crate::calc::utils::check_directory::{asteroid_file_exists, validated_ephemeris_path};
 
 pub fn to_str_refs(strings: &Vec<String>) -> Vec<&str> {
   let strs: Vec<&str> = strings.iter().map(|s| s.as_ref()).collect();
   strs
 }
 
+/*
+* Asteroid keys (ast:NNN) are only accepted if the matching file exists in the ephemeris path validated at startup
+*/
+pub fn is_available_asteroid_key(key: &str) -> bool {
+  match (asteroid_num(key), validated_ephemeris_path()) {
+    (Some(num), Some(path)) => asteroid_file_exists(path, num),
+    _ => false,
+  }
+}
+
+pub fn is_body_key(key: &str) -> bool {
+  key.len() == 2 || is_available_asteroid_key(key)
+}
+
 pub fn body_keys_str_to_keys(key_string: String) -> Vec<String> {
-  key_string.split(",").into_iter().filter(|p| p.len() == 2).map(|p| p.to_string()).collect()
+  key_string.split(",").into_iter().filter(|p| is_body_key(p)).map(|p| p.to_string()).collect()
 }
 
 pub fn body_keys_str_to_keys_or(key_string: String, default_keys: Vec<&str>) -> Vec<String> {
   let keys: Vec<String> = body_keys_str_to_keys(key_string);
-  if keys.len() > 0 { keys.into_iter().filter(|k| k.len() == 2 && !k.contains("as")).collect() } else { default_keys.into_iter().map(|p| p.to_string() ).collect() }
+  if keys.len() > 0 { keys.into_iter().filter(|k| k.as_str() != "as").collect() } else { default_keys.into_iter().map(|p| p.to_string() ).collect() }
 }
 
 pub fn loc_string_to_geo(loc: &str) -> Option<GeoPos> {
@@ -25,29 +41,20 @@ pub fn loc_string_to_geo(loc: &str) -> Option<GeoPos> {
   }
 }
 
-pub fn calc_circular_diff(ref_int: i32, target_int: i32, base: i32) -> i32 {
-  if ref_int < target_int {
-		target_int - ref_int
-	} else {
-		target_int + base - ref_int
-	}
-}
+/*
+* Equinoxes are never more than 187 days apart
+*/
+const MAX_EQUINOX_DAYS: f64 = 190f64;
 
+/*
+* Whole days until the next March or September equinox
+*/
 pub fn calc_days_to_next_prev_equinox(jd: f64) -> i32 {
-  let yd = if let Ok(dt) = julian_day_to_datetime(jd) { dt.format("%j").to_string().parse::<i32>().unwrap_or(0) } else { 0 };
-  let half_year_days = 183;
-  let autumn_eq = 266;
-  let spring_eq = autumn_eq - half_year_days;
-  let first = calc_circular_diff(yd, spring_eq, 366);
-  let mut second = half_year_days * 2;
-  if first >= half_year_days {
-    second = calc_circular_diff(yd, autumn_eq, 365);
-  }
-  if first < second {
-    first
-  } else {
-    second
-  }
+  calc_equinoxes_solstices(jd, jd + MAX_EQUINOX_DAYS, false)
+    .iter()
+    .find(|event| event.key.ends_with("equinox"))
+    .map(|event| (event.jd - jd).floor() as i32)
+    .unwrap_or(0)
 }
 
 fn logarithmic_progress_to_pole(lat: f64) -> f64 {
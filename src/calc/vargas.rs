@@ -0,0 +1,137 @@
+use serde::{Serialize, Deserialize};
+use utoipa::ToSchema;
+use super::{
+  core::{get_bodies_ecl_geo, get_bodies_ecl_topo},
+  math_funcs::subtract_360,
+  models::{general::KeyNumValue, geo_pos::GeoPos, houses::calc_ascendant},
+  settings::varga_values::*,
+};
+
+/*
+* 0-based index of the equal part of the sign and the fraction of the part traversed
+*/
+fn equal_part(deg: f64, num: u8) -> (u16, f64) {
+  let span = 30f64 / num as f64;
+  let part = (deg / span).floor();
+  (part as u16, deg / span - part)
+}
+
+/*
+* Trimshamsha parts are unequal and ruled by the five non-luminary planets
+*/
+fn trimshamsha_part(deg: f64, odd: bool) -> (u16, f64) {
+  let bounds = if odd { TRIMSHAMSHA_ODD } else { TRIMSHAMSHA_EVEN };
+  let mut start = 0f64;
+  for &(end, sign) in bounds.iter() {
+    if deg < end {
+      return (sign, (deg - start) / (end - start));
+    }
+    start = end;
+  }
+  (bounds[4].1, 1f64)
+}
+
+/**
+ * Divisional sign (1-12) and longitude of a sidereal longitude following the Parashari rules.
+ * The longitude within the divisional sign is proportional to the degrees traversed in the part
+ */
+pub fn calc_varga(lng: f64, num: u8) -> (u16, f64) {
+  let lng = lng.rem_euclid(360f64);
+  let sign = (lng / 30f64).floor() as u16;
+  let deg = lng - sign as f64 * 30f64;
+  // Aries, Gemini etc. are odd signs
+  let odd = sign % 2 == 0;
+  // 0 movable, 1 fixed, 2 dual
+  let modality = sign % 3;
+  if num == 30 {
+    let (varga_sign, fraction) = trimshamsha_part(deg, odd);
+    return (varga_sign, (varga_sign - 1) as f64 * 30f64 + fraction * 30f64);
+  }
+  let (part, fraction) = equal_part(deg, num);
+  let index = match num {
+    2 => if (part == 0) == odd { 4 } else { 3 },
+    3 => sign + part * 4,
+    4 => sign + part * 3,
+    7 => if odd { sign + part } else { sign + 6 + part },
+    9 => sign * 9 + part,
+    10 => if odd { sign + part } else { sign + 8 + part },
+    12 | 60 => sign + part,
+    16 | 45 => [0, 4, 8][modality as usize] + part,
+    20 => [0, 8, 4][modality as usize] + part,
+    24 => if odd { 4 + part } else { 3 + part },
+    27 => sign * 27 + part,
+    40 => if odd { part } else { 6 + part },
+    _ => sign,
+  } % 12;
+  (index + 1, index as f64 * 30f64 + fraction * 30f64)
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+pub struct VargaPosition {
+  pub key: String,
+  pub sign: u16,
+  pub lng: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+pub struct VargaChart {
+  pub num: u8,
+  pub key: String,
+  pub name: String,
+  pub items: Vec<VargaPosition>,
+}
+
+impl VargaChart {
+  pub fn new(num: u8, points: &Vec<KeyNumValue>) -> Self {
+    let items = points.iter().map(|p| {
+      let (sign, lng) = calc_varga(p.value, num);
+      VargaPosition { key: p.key.clone(), sign, lng }
+    }).collect();
+    VargaChart {
+      num,
+      key: format!("d{}", num),
+      name: varga_name(num),
+      items,
+    }
+  }
+}
+
+pub fn calc_vargas(points: &Vec<KeyNumValue>, nums: &Vec<u8>) -> Vec<VargaChart> {
+  nums.iter().map(|num| VargaChart::new(*num, points)).collect()
+}
+
+/**
+ * Divisional charts of the sidereal body longitudes and ascendant
+ */
+pub fn calc_body_vargas(jd: f64, keys: &Vec<&str>, geo: GeoPos, topo: bool, ayanamsha: f64, nums: &Vec<u8>) -> Vec<VargaChart> {
+  let bodies = if topo { get_bodies_ecl_topo(jd, keys, geo, ayanamsha) } else { get_bodies_ecl_geo(jd, keys, ayanamsha) };
+  let mut points: Vec<KeyNumValue> = vec![KeyNumValue::new("as", subtract_360(calc_ascendant(jd, geo), ayanamsha))];
+  points.extend(bodies.iter().map(|b| KeyNumValue::new(b.key.as_str(), b.lng)));
+  calc_vargas(&points, nums)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_navamsha() {
+    // 0º Taurus starts in Capricorn, 15º Leo is in the 5th navamsha from Aries, Leo
+    assert_eq!(calc_varga(30f64, 9).0, 10);
+    assert_eq!(calc_varga(135f64, 9).0, 5);
+  }
+
+  #[test]
+  fn test_drekkana_and_hora() {
+    // 25º Gemini is in the 3rd drekkana, Aquarius. The 2nd half of an even sign is in the Sun's hora (Leo)
+    assert_eq!(calc_varga(85f64, 3).0, 11);
+    assert_eq!(calc_varga(50f64, 2).0, 5);
+  }
+
+  #[test]
+  fn test_trimshamsha() {
+    // 7º Aries is ruled by Saturn (Aquarius), 7º Taurus by Mercury (Virgo)
+    assert_eq!(calc_varga(7f64, 30).0, 11);
+    assert_eq!(calc_varga(37f64, 30).0, 6);
+  }
+}
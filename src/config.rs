@@ -0,0 +1,195 @@
+use std::{fs::File, io::BufReader, net::ToSocketAddrs, str::FromStr, time::Duration};
+use actix_cors::Cors;
+use clap::Parser;
+use rustls::{Certificate, PrivateKey, ServerConfig};
+use rustls_pemfile::{read_all, Item};
+use crate::constants::*;
+
+/// Astrologic engine config
+#[derive(Parser, Debug)]
+#[clap(author, version, about, long_about = None)]
+pub struct Args {
+    // Ephemeris path
+    #[clap(short, long, value_parser, default_value_t = empty_string() )]
+    pub ephemeris: String,
+    #[clap(short, long, value_parser, default_value_t = 0u32 )]
+    pub port: u32,
+    // Bind address, e.g. 0.0.0.0 for all interfaces in containers
+    #[clap(long, value_parser)]
+    pub host: Option<String>,
+    // Number of worker threads, 0 for one per CPU core
+    #[clap(short, long, value_parser)]
+    pub workers: Option<String>,
+    // Maximum request body size in bytes
+    #[clap(long, value_parser)]
+    pub max_payload: Option<String>,
+    // Maximum time in seconds to receive the request head
+    #[clap(long, value_parser)]
+    pub timeout: Option<String>,
+    // Maximum time in seconds a request waits for its calculations
+    #[clap(long, value_parser)]
+    pub calc_timeout: Option<String>,
+    // Comma-separated allowed CORS origins or * for any origin
+    #[clap(long, value_parser)]
+    pub cors: Option<String>,
+    // TLS certificate chain (PEM)
+    #[clap(long, value_parser)]
+    pub tls_cert: Option<String>,
+    // TLS private key (PEM)
+    #[clap(long, value_parser)]
+    pub tls_key: Option<String>,
+}
+
+/*
+* Validated server options. Command line parameters override .env values, which override defaults
+*/
+pub struct ServerOptions {
+  pub host: String,
+  pub port: u16,
+  pub workers: usize,
+  pub max_payload: usize,
+  pub timeout: Duration,
+  pub calc_timeout: Duration,
+  pub cors_origins: Vec<String>,
+  pub tls: Option<ServerConfig>,
+}
+
+pub fn get_ephemeris_path() -> String {
+  let args = Args::parse();
+  let path = args.ephemeris;
+  if path.is_empty() {
+    dotenv::var("sweph_path").unwrap_or(SWEPH_PATH_DEFAULT.to_string())
+  } else {
+    path
+  }
+}
+
+/*
+* Command line value or the .env value with the given key, if not empty
+*/
+fn option_value(arg: Option<String>, env_key: &str) -> Option<String> {
+  arg.or(dotenv::var(env_key).ok()).map(|v| v.trim().to_string()).filter(|v| v.len() > 0)
+}
+
+fn parse_option<T: FromStr>(value: Option<String>, name: &str, default: T, errors: &mut Vec<String>) -> T {
+  match value {
+    Some(v) => match v.parse::<T>() {
+      Ok(num) => num,
+      Err(_) => {
+        errors.push(format!("invalid {}: {}", name, v));
+        default
+      }
+    },
+    None => default,
+  }
+}
+
+fn get_port_no(args: &Args, errors: &mut Vec<String>) -> u16 {
+  let port_str = if args.port > 0 { Some(args.port.to_string()) } else { option_value(None, "port") };
+  let port = parse_option(port_str, "port", DEFAULT_PORT as u16, errors);
+  if port < 1 {
+    errors.push("invalid port: 0".to_string());
+  }
+  port
+}
+
+fn match_cors_origins(value: Option<String>, errors: &mut Vec<String>) -> Vec<String> {
+  let origins: Vec<String> = match value {
+    Some(v) => v.split(",").map(|o| o.trim().trim_end_matches("/").to_string()).filter(|o| o.len() > 0).collect(),
+    None => vec![],
+  };
+  for origin in origins.iter() {
+    if origin != "*" && !origin.starts_with("http://") && !origin.starts_with("https://") {
+      errors.push(format!("invalid CORS origin: {}", origin));
+    }
+  }
+  origins
+}
+
+/*
+* Certificate chain and the first PKCS8, RSA or EC private key from PEM files
+*/
+fn load_tls_config(cert_path: &str, key_path: &str) -> Result<ServerConfig, String> {
+  let cert_file = File::open(cert_path).map_err(|e| format!("cannot read TLS certificate {}: {}", cert_path, e))?;
+  let certs: Vec<Certificate> = read_all(&mut BufReader::new(cert_file))
+    .map_err(|e| format!("invalid TLS certificate {}: {}", cert_path, e))?
+    .into_iter()
+    .filter_map(|item| match item { Item::X509Certificate(cert) => Some(Certificate(cert)), _ => None })
+    .collect();
+  if certs.is_empty() {
+    return Err(format!("no certificates found in {}", cert_path));
+  }
+  let key_file = File::open(key_path).map_err(|e| format!("cannot read TLS key {}: {}", key_path, e))?;
+  let key = read_all(&mut BufReader::new(key_file))
+    .map_err(|e| format!("invalid TLS key {}: {}", key_path, e))?
+    .into_iter()
+    .find_map(|item| match item { Item::PKCS8Key(key) | Item::RSAKey(key) | Item::ECKey(key) => Some(PrivateKey(key)), _ => None })
+    .ok_or(format!("no private key found in {}", key_path))?;
+  ServerConfig::builder()
+    .with_safe_defaults()
+    .with_no_client_auth()
+    .with_single_cert(certs, key)
+    .map_err(|e| format!("invalid TLS certificate or key: {}", e))
+}
+
+/**
+ * Read and validate all server options, so the server does not start with silently replaced values.
+ * All invalid options are reported together
+ */
+pub fn load_server_options() -> Result<ServerOptions, Vec<String>> {
+  let args = Args::parse();
+  let mut errors: Vec<String> = Vec::new();
+  let port = get_port_no(&args, &mut errors);
+  let host = option_value(args.host.clone(), "host").unwrap_or(DEFAULT_HOST.to_string());
+  if port > 0 && (host.as_str(), port).to_socket_addrs().is_err() {
+    errors.push(format!("invalid host: {}", host));
+  }
+  let workers = parse_option(option_value(args.workers.clone(), "workers"), "workers", 0usize, &mut errors);
+  if workers > MAX_WORKERS {
+    errors.push(format!("too many workers: {}, the maximum is {}", workers, MAX_WORKERS));
+  }
+  let max_payload = parse_option(option_value(args.max_payload.clone(), "max_payload"), "max_payload", DEFAULT_MAX_PAYLOAD_BYTES, &mut errors);
+  if max_payload < 1 {
+    errors.push("invalid max_payload: 0".to_string());
+  }
+  let timeout_secs = parse_option(option_value(args.timeout.clone(), "timeout"), "timeout", DEFAULT_REQUEST_TIMEOUT_SECS, &mut errors);
+  if timeout_secs < 1 {
+    errors.push("invalid timeout: 0".to_string());
+  }
+  let calc_timeout_secs = parse_option(option_value(args.calc_timeout.clone(), "calc_timeout"), "calc_timeout", DEFAULT_CALC_TIMEOUT_SECS, &mut errors);
+  if calc_timeout_secs < 1 {
+    errors.push("invalid calc_timeout: 0".to_string());
+  }
+  let cors_origins = match_cors_origins(option_value(args.cors.clone(), "cors_origins"), &mut errors);
+  let tls = match (option_value(args.tls_cert.clone(), "tls_cert"), option_value(args.tls_key.clone(), "tls_key")) {
+    (Some(cert_path), Some(key_path)) => match load_tls_config(&cert_path, &key_path) {
+      Ok(config) => Some(config),
+      Err(error) => {
+        errors.push(error);
+        None
+      }
+    },
+    (None, None) => None,
+    _ => {
+      errors.push("both tls_cert and tls_key are required for TLS".to_string());
+      None
+    }
+  };
+  if errors.len() > 0 {
+    Err(errors)
+  } else {
+    Ok(ServerOptions { host, port, workers, max_payload, timeout: Duration::from_secs(timeout_secs), calc_timeout: Duration::from_secs(calc_timeout_secs), cors_origins, tls })
+  }
+}
+
+/*
+* CORS headers for the configured origins. Only applied if at least one origin is configured
+*/
+pub fn build_cors(origins: &Vec<String>) -> Cors {
+  let cors = Cors::default().allowed_methods(vec!["GET", "POST"]).allow_any_header().max_age(3600);
+  if origins.iter().any(|o| o == "*") {
+    cors.allow_any_origin()
+  } else {
+    origins.iter().fold(cors, |c, origin| c.allowed_origin(origin))
+  }
+}
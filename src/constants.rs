@@ -5,6 +5,20 @@
 
 pub const SWEPH_PATH_DEFAULT: &str = "/usr/share/libswe/ephe";
 pub const DEFAULT_PORT: u32 = 8087;
+pub const DEFAULT_HOST: &str = "127.0.0.1";
+pub const DEFAULT_REQUEST_TIMEOUT_SECS: u64 = 5;
+pub const DEFAULT_CALC_TIMEOUT_SECS: u64 = 60;
+pub const MAX_WORKERS: usize = 256;
+
+/*
+* Maximum request body size, large enough for batch requests via POST /chart-data
+*/
+pub const DEFAULT_MAX_PAYLOAD_BYTES: usize = 4_194_304;
+
+/*
+* Limit for batch requests via POST /chart-data
+*/
+pub const MAX_BATCH_CHARTS: usize = 1000;
 
 
 pub fn empty_string() -> String {
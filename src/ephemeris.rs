@@ -0,0 +1,110 @@
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use actix_web::{rt::time::timeout, web};
+use crate::calc::errors::{CalcError, CalcErrors};
+use crate::extensions::swe::set_sid_mode;
+use crate::constants::DEFAULT_CALC_TIMEOUT_SECS;
+use crate::metrics::observe_calculation;
+
+/*
+* The Swiss Ephemeris keeps the topocentric location, sidereal mode and open ephemeris files in global state
+* shared by all actix workers. Calculations hold this lock from start to finish, so settings applied
+* by one request can never leak into a concurrent one
+*/
+static EPHEMERIS_LOCK: Mutex<()> = Mutex::new(());
+
+/*
+* Maximum time a request waits for its calculations, set once at startup
+*/
+static CALC_TIMEOUT: OnceLock<Duration> = OnceLock::new();
+
+pub fn set_calc_timeout(duration: Duration) {
+  let _ = CALC_TIMEOUT.set(duration);
+}
+
+fn calc_timeout() -> Duration {
+  CALC_TIMEOUT.get().map(|d| d.to_owned()).unwrap_or(Duration::from_secs(DEFAULT_CALC_TIMEOUT_SECS))
+}
+
+/**
+ * Run a calculation with exclusive access to the Swiss Ephemeris.
+ * The sidereal mode is reset first, while topocentric locations are always set before topocentric calculations
+ */
+pub fn with_ephemeris<T, F: FnOnce() -> T>(calc: F) -> T {
+  // a panic in an earlier calculation leaves no state that is not reset here
+  let _guard = EPHEMERIS_LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+  let start = Instant::now();
+  set_sid_mode(0);
+  let result = calc();
+  observe_calculation(start.elapsed().as_secs_f64());
+  result
+}
+
+/**
+ * Run a calculation on the blocking thread pool with exclusive access to the Swiss Ephemeris,
+ * so actix workers can keep serving other requests while it waits for the lock.
+ * Requests still waiting after the calculation timeout fail with a timeout error
+ */
+pub async fn run_ephemeris<T, F>(calc: F) -> Result<T, CalcErrors>
+where
+  T: Send + 'static,
+  F: FnOnce() -> Result<T, CalcErrors> + Send + 'static,
+{
+  let max_duration = calc_timeout();
+  match timeout(max_duration, web::block(move || with_ephemeris(calc))).await {
+    Ok(Ok(result)) => result,
+    Ok(Err(e)) => {
+      log::error!("ephemeris calculation failed: {}", e);
+      Err(CalcError::CalculationFailed.into())
+    },
+    Err(_) => {
+      log::warn!("ephemeris calculation exceeded {} seconds", max_duration.as_secs());
+      Err(CalcError::TimedOut(max_duration.as_secs()).into())
+    },
+  }
+}
+
+/**
+ * Run the same calculation for many items on the blocking thread pool. The lock is taken per item,
+ * so single requests are not held up until a long batch has completed.
+ * The whole batch shares one calculation timeout and remaining items are skipped once it has passed
+ */
+pub async fn run_ephemeris_batch<I, T, F>(items: Vec<I>, calc: F) -> Result<Vec<T>, CalcErrors>
+where
+  I: Send + 'static,
+  T: Send + 'static,
+  F: Fn(&I) -> T + Send + 'static,
+{
+  let max_duration = calc_timeout();
+  let start = Instant::now();
+  let batch = web::block(move || items.iter().take_while(|_| start.elapsed() < max_duration).map(|item| with_ephemeris(|| calc(item))).collect::<Vec<T>>());
+  match timeout(max_duration, batch).await {
+    Ok(Ok(results)) => Ok(results),
+    Ok(Err(e)) => {
+      log::error!("ephemeris batch calculation failed: {}", e);
+      Err(CalcError::CalculationFailed.into())
+    },
+    Err(_) => {
+      log::warn!("ephemeris batch calculation exceeded {} seconds", max_duration.as_secs());
+      Err(CalcError::TimedOut(max_duration.as_secs()).into())
+    },
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[actix_web::test]
+  async fn test_calc_timeout() {
+    // no other test runs calculations via run_ephemeris, so the shortened timeout does not affect them
+    set_calc_timeout(Duration::from_secs(1));
+    let result = run_ephemeris(|| {
+      std::thread::sleep(Duration::from_millis(1500));
+      Ok(())
+    }).await;
+    let errors = result.unwrap_err();
+    assert!(errors.is_timed_out());
+    assert_eq!(actix_web::ResponseError::status_code(&errors), actix_web::http::StatusCode::SERVICE_UNAVAILABLE);
+  }
+}
@@ -0,0 +1,83 @@
+use serde::Serialize;
+use serde_json::*;
+use utoipa::ToSchema;
+use actix_web::{error::QueryPayloadError, web::Query, Error, HttpRequest, HttpResponse, ResponseError, http::StatusCode};
+use crate::calc::errors::{CalcError, CalcErrors};
+use crate::metrics::count_error;
+use crate::query_params::InputOptions;
+
+/*
+* Invalid query parameters are reported as JSON with HTTP 400 rather than silently replaced with defaults.
+* Calculations that could not be completed are reported with HTTP 500 and those exceeding the calculation timeout with 503
+*/
+impl ResponseError for CalcErrors {
+  fn status_code(&self) -> StatusCode {
+    if self.is_timed_out() {
+      StatusCode::SERVICE_UNAVAILABLE
+    } else if self.is_internal() {
+      StatusCode::INTERNAL_SERVER_ERROR
+    } else {
+      StatusCode::BAD_REQUEST
+    }
+  }
+
+  fn error_response(&self) -> HttpResponse {
+    HttpResponse::build(self.status_code()).json(calc_errors_to_json(self))
+  }
+}
+
+/*
+* Error body of 400, 500 and 503 responses, also used for invalid items in batch requests
+*/
+#[derive(Serialize, Debug, Clone, ToSchema)]
+pub struct ErrorResponse {
+  pub valid: bool,
+  pub error: String,
+  #[serde(rename = "invalidKeys")]
+  pub invalid_keys: Vec<String>,
+  #[serde(rename = "invalidLocations")]
+  pub invalid_locations: Vec<String>,
+  #[serde(rename = "invalidDates")]
+  pub invalid_dates: Vec<String>,
+  #[serde(rename = "invalidTimeZones")]
+  pub invalid_time_zones: Vec<String>,
+  #[serde(rename = "invalidParams")]
+  pub invalid_params: Vec<String>,
+}
+
+impl ErrorResponse {
+  pub fn new(errors: &CalcErrors) -> Self {
+    ErrorResponse {
+      valid: false,
+      error: errors.to_string(),
+      invalid_keys: errors.invalid_keys(),
+      invalid_locations: errors.invalid_locations(),
+      invalid_dates: errors.invalid_dates(),
+      invalid_time_zones: errors.invalid_time_zones(),
+      invalid_params: errors.invalid_params(),
+    }
+  }
+}
+
+pub fn calc_errors_to_json(errors: &CalcErrors) -> Value {
+  count_error(if errors.is_timed_out() { "timed_out" } else if errors.is_internal() { "calculation_failed" } else { "invalid_input" });
+  json!(ErrorResponse::new(errors))
+}
+
+/*
+* Query strings with values of the wrong type, e.g. days=abc, are reported with the same JSON error body.
+* Each parameter is parsed on its own to name the offending ones
+*/
+pub fn query_error_handler(err: QueryPayloadError, req: &HttpRequest) -> Error {
+  let mut errors = CalcErrors::new();
+  for pair in req.query_string().split("&").filter(|p| p.len() > 0) {
+    if let Err(e) = Query::<InputOptions>::from_query(pair) {
+      let key = pair.split("=").next().unwrap_or("").to_string();
+      errors.add(CalcError::InvalidParam(key, e.to_string()));
+    }
+  }
+  if errors.is_empty() {
+    errors.add(CalcError::InvalidParam("query".to_string(), err.to_string()));
+  }
+  errors.into()
+}
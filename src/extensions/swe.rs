@@ -72,6 +72,94 @@ extern "C" {
   pub fn swe_set_sid_mode(sid_mode: i32, t9: f64, ayan_t0: f64);
 
 
+  pub fn swe_calc_ut(
+    tjd_ut: c_double,
+    ipl: c_int,
+    iflag: c_int,
+    xx: *mut [c_double; 6],
+    serr: *mut c_char
+  ) -> c_int;
+
+  pub fn swe_fixstar2_ut(
+    star: *mut c_char,
+    tjd_ut: c_double,
+    iflag: c_int,
+    xx: *mut [c_double; 6],
+    serr: *mut c_char
+  ) -> c_int;
+
+  pub fn swe_fixstar2_mag(
+    star: *mut c_char,
+    mag: *mut c_double,
+    serr: *mut c_char
+  ) -> c_int;
+
+  pub fn swe_heliacal_ut(
+    tjdstart_ut: c_double,
+    geopos: *mut [c_double; 3],
+    datm: *mut [c_double; 4],
+    dobs: *mut [c_double; 6],
+    object_name: *mut c_char,
+    type_event: c_int,
+    iflag: c_int,
+    dret: *mut [c_double; 50],
+    serr: *mut c_char
+  ) -> c_int;
+
+  pub fn swe_sol_eclipse_when_glob(
+    tjd_start: c_double,
+    ifl: c_int,
+    ifltype: c_int,
+    tret: *mut [c_double; 10],
+    backward: c_int,
+    serr: *mut c_char
+  ) -> c_int;
+
+  pub fn swe_sol_eclipse_when_loc(
+    tjd_start: c_double,
+    ifl: c_int,
+    geopos: *mut [c_double; 3],
+    tret: *mut [c_double; 10],
+    attr: *mut [c_double; 20],
+    backward: c_int,
+    serr: *mut c_char
+  ) -> c_int;
+
+  pub fn swe_sol_eclipse_where(
+    tjd_ut: c_double,
+    ifl: c_int,
+    geopos: *mut [c_double; 10],
+    attr: *mut [c_double; 20],
+    serr: *mut c_char
+  ) -> c_int;
+
+  pub fn swe_lun_eclipse_when(
+    tjd_start: c_double,
+    ifl: c_int,
+    ifltype: c_int,
+    tret: *mut [c_double; 10],
+    backward: c_int,
+    serr: *mut c_char
+  ) -> c_int;
+
+  pub fn swe_lun_eclipse_when_loc(
+    tjd_start: c_double,
+    ifl: c_int,
+    geopos: *mut [c_double; 3],
+    tret: *mut [c_double; 10],
+    attr: *mut [c_double; 20],
+    backward: c_int,
+    serr: *mut c_char
+  ) -> c_int;
+
+  pub fn swe_lun_eclipse_how(
+    tjd_ut: c_double,
+    ifl: c_int,
+    geopos: *mut [c_double; 3],
+    attr: *mut [c_double; 20],
+    serr: *mut c_char
+  ) -> c_int;
+
   /* // convert ecliptic to equatorial
   pub fn swe_cotrans(xin: *mut [c_double; 3], xout: *mut [c_double; 3], eps: c_double);
  */
@@ -82,6 +170,13 @@ extern "C" {
  * Call via sleep
  */
 pub fn rise_trans_raw(tjd_ut: f64, ipl: Bodies, lat: f64, lng: f64, iflag: i32) -> [f64; 3] {
+  rise_trans_num_raw(tjd_ut, ipl as i32, lat, lng, iflag)
+}
+
+/**
+ * As above with a body number, e.g. for asteroids that cannot be referenced via the Bodies enum
+ */
+pub fn rise_trans_num_raw(tjd_ut: f64, ipl: i32, lat: f64, lng: f64, iflag: i32) -> [f64; 3] {
   let mut serr = [0; 255];
   let geopos = &mut [lng, lat, 0f64];
   let star_ref = &mut ['\0' as i8]; // set to \0 cast as i8 to ignore *starname
@@ -93,7 +188,7 @@ pub fn rise_trans_raw(tjd_ut: f64, ipl: Bodies, lat: f64, lng: f64, iflag: i32)
     let p_serr = serr.as_mut_ptr();
     swe_rise_trans(
         tjd_ut,
-        ipl as i32,
+        ipl,
         star_ref,
         epheflag,
         iflag,
@@ -112,6 +207,146 @@ pub fn rise_trans(tjd_ut: f64, ipl: Bodies, lat: f64, lng: f64, iflag: i32) -> f
   rise_trans_raw(tjd_ut, ipl, lat, lng, iflag)[0]
 }
 
+pub fn rise_trans_num(tjd_ut: f64, ipl: i32, lat: f64, lng: f64, iflag: i32) -> f64 {
+  rise_trans_num_raw(tjd_ut, ipl, lat, lng, iflag)[0]
+}
+
+/*
+* Swiss Ephemeris expects a writable star name buffer of at least SE_MAX_STNAME (256) characters
+*/
+const STAR_NAME_BUFFER_SIZE: usize = 256;
+
+fn to_star_name_buffer(name: &str) -> [c_char; STAR_NAME_BUFFER_SIZE] {
+  let mut buffer: [c_char; STAR_NAME_BUFFER_SIZE] = [0; STAR_NAME_BUFFER_SIZE];
+  for (index, byte) in name.bytes().take(STAR_NAME_BUFFER_SIZE - 1).enumerate() {
+    buffer[index] = byte as c_char;
+  }
+  buffer
+}
+
+/*
+  Rise, set, MC or IC times of a fixed star. The ipl number is ignored when a star name is given.
+*/
+pub fn rise_trans_star(tjd_ut: f64, star: &str, lat: f64, lng: f64, iflag: i32) -> f64 {
+  let mut serr = [0; 255];
+  let mut star_name = to_star_name_buffer(star);
+  let geopos = &mut [lng, lat, 0f64];
+  let epheflag: i32 = 1;
+  let result = unsafe {
+    let p_xx: &mut [f64; 3] = &mut [0f64, 0f64, 0f64];
+    let p_serr = serr.as_mut_ptr();
+    swe_rise_trans(
+        tjd_ut,
+        -1,
+        star_name.as_mut_ptr() as *mut [c_char; 1],
+        epheflag,
+        iflag,
+        geopos,
+        0f64,
+        0f64,
+        p_xx,
+        p_serr,
+    );
+    *p_xx
+  };
+  result[0]
+}
+
+/*
+* Ecliptic or equatorial coordinates with distances and speeds as returned by swe_calc_ut and swe_fixstar2_ut
+*/
+#[derive(Serialize, Deserialize, Debug, Copy, Clone)]
+pub struct SweCalcResult {
+  pub longitude: f64,
+  pub latitude: f64,
+  pub distance: f64,
+  pub speed_longitude: f64,
+  pub speed_latitude: f64,
+  pub speed_distance: f64,
+  pub flag: i32,
+}
+
+impl SweCalcResult {
+  pub fn new(xx: [f64; 6], flag: i32) -> Self {
+    SweCalcResult {
+      longitude: xx[0],
+      latitude: xx[1],
+      distance: xx[2],
+      speed_longitude: xx[3],
+      speed_latitude: xx[4],
+      speed_distance: xx[5],
+      flag,
+    }
+  }
+
+  pub fn is_valid(&self) -> bool {
+    self.flag >= 0
+  }
+}
+
+/*
+  Wrapper for swe_calc_ut with any body number including asteroid numbers
+*/
+pub fn calc_ut_raw(tjd_ut: f64, ipl: i32, iflag: i32) -> SweCalcResult {
+  let mut serr = [0; 255];
+  let mut xx: [f64; 6] = [0f64; 6];
+  let flag = unsafe {
+    let p_serr = serr.as_mut_ptr();
+    swe_calc_ut(tjd_ut, ipl, iflag, &mut xx, p_serr)
+  };
+  SweCalcResult::new(xx, flag)
+}
+
+/*
+  Wrapper for swe_fixstar2_ut. The star may be a traditional name or Bayer designation such as ,alVir
+*/
+pub fn fixstar_ut(tjd_ut: f64, star: &str, iflag: i32) -> SweCalcResult {
+  let mut serr = [0; 255];
+  let mut star_name = to_star_name_buffer(star);
+  let mut xx: [f64; 6] = [0f64; 6];
+  let flag = unsafe {
+    let p_serr = serr.as_mut_ptr();
+    swe_fixstar2_ut(star_name.as_mut_ptr(), tjd_ut, iflag, &mut xx, p_serr)
+  };
+  SweCalcResult::new(xx, flag)
+}
+
+pub fn fixstar_mag(star: &str) -> Option<f64> {
+  let mut serr = [0; 255];
+  let mut star_name = to_star_name_buffer(star);
+  let mut mag: f64 = 0f64;
+  let flag = unsafe {
+    let p_serr = serr.as_mut_ptr();
+    swe_fixstar2_mag(star_name.as_mut_ptr(), &mut mag, p_serr)
+  };
+  if flag >= 0 { Some(mag) } else { None }
+}
+
+pub enum HeliacalEvent {
+  Rising = 1, // morning first
+  Setting = 2, // evening last
+}
+
+/*
+  Next heliacal rising or setting of a star or planet after tjd_start for an observer with normal eyesight
+  under average atmospheric conditions. The first value of dret is the start of visibility.
+*/
+pub fn heliacal_ut(tjd_start: f64, object_name: &str, lat: f64, lng: f64, alt: f64, event: HeliacalEvent) -> Option<f64> {
+  let mut serr = [0; 255];
+  let mut name = to_star_name_buffer(object_name);
+  let geopos = &mut [lng, lat, alt];
+  // pressure (mbar), temperature (ºC), relative humidity (%), meteorological range (0 = calculated)
+  let datm = &mut [1013.25f64, 15f64, 40f64, 0f64];
+  // observer age and Snellen ratio, further optical parameters only apply to telescopes
+  let dobs = &mut [36f64, 1f64, 0f64, 0f64, 0f64, 0f64];
+  let mut dret: [f64; 50] = [0f64; 50];
+  let flag = unsafe {
+    let p_serr = serr.as_mut_ptr();
+    swe_heliacal_ut(tjd_start, geopos, datm, dobs, name.as_mut_ptr(), event as i32, 2i32, &mut dret, p_serr)
+  };
+  if flag >= 0 && dret[0] > 0f64 { Some(dret[0]) } else { None }
+}
+
 /*
   Wrapper for swe_azalt.
   tjd_jd: Julian Day,
@@ -183,4 +418,74 @@ pub fn set_sid_mode(iflag: i32) {
   }
 }
 
+/*
+* Eclipse search results with event times (tret) and attributes (attr) as documented for the Swiss Ephemeris eclipse functions
+*/
+#[derive(Debug, Copy, Clone)]
+pub struct EclipseResult {
+  pub flag: i32,
+  pub times: [f64; 10],
+  pub attrs: [f64; 20],
+}
+
+impl EclipseResult {
+  fn new(flag: i32, times: [f64; 10], attrs: [f64; 20]) -> Self {
+    EclipseResult { flag, times, attrs }
+  }
+
+  pub fn is_valid(&self) -> bool {
+    self.flag > 0
+  }
+}
+
+const ECLIPSE_EPHE_FLAG: i32 = 2; // SEFLG_SWIEPH
+
+/*
+  Next global solar or lunar eclipse of any type after tjd_start.
+  Attributes with magnitudes are calculated for the time of maximum eclipse
+*/
+pub fn eclipse_when_glob(tjd_start: f64, lunar: bool) -> EclipseResult {
+  let mut serr = [0; 255];
+  let mut tret: [f64; 10] = [0f64; 10];
+  let mut attr: [f64; 20] = [0f64; 20];
+  let flag = unsafe {
+    let p_serr = serr.as_mut_ptr();
+    if lunar {
+      swe_lun_eclipse_when(tjd_start, ECLIPSE_EPHE_FLAG, 0, &mut tret, 0, p_serr)
+    } else {
+      swe_sol_eclipse_when_glob(tjd_start, ECLIPSE_EPHE_FLAG, 0, &mut tret, 0, p_serr)
+    }
+  };
+  if flag > 0 {
+    unsafe {
+      let p_serr = serr.as_mut_ptr();
+      if lunar {
+        let geopos = &mut [0f64, 0f64, 0f64];
+        swe_lun_eclipse_how(tret[0], ECLIPSE_EPHE_FLAG, geopos, &mut attr, p_serr);
+      } else {
+        let mut geopos: [f64; 10] = [0f64; 10];
+        swe_sol_eclipse_where(tret[0], ECLIPSE_EPHE_FLAG, &mut geopos, &mut attr, p_serr);
+      }
+    }
+  }
+  EclipseResult::new(flag, tret, attr)
+}
 
+/*
+  Next solar or lunar eclipse observable from the given location after tjd_start
+*/
+pub fn eclipse_when_loc(tjd_start: f64, lunar: bool, lat: f64, lng: f64, alt: f64) -> EclipseResult {
+  let mut serr = [0; 255];
+  let mut tret: [f64; 10] = [0f64; 10];
+  let mut attr: [f64; 20] = [0f64; 20];
+  let geopos = &mut [lng, lat, alt];
+  let flag = unsafe {
+    let p_serr = serr.as_mut_ptr();
+    if lunar {
+      swe_lun_eclipse_when_loc(tjd_start, ECLIPSE_EPHE_FLAG, geopos, &mut tret, &mut attr, 0, p_serr)
+    } else {
+      swe_sol_eclipse_when_loc(tjd_start, ECLIPSE_EPHE_FLAG, geopos, &mut tret, &mut attr, 0, p_serr)
+    }
+  };
+  EclipseResult::new(flag, tret, attr)
+}
@@ -4,6 +4,11 @@ mod constants;
 mod query_params;
 mod routes;
 mod help;
+mod errors;
+mod ephemeris;
+mod metrics;
+mod config;
+mod openapi;
 
 extern crate libc;
 extern crate serde_derive;
@@ -15,40 +20,27 @@ extern crate chrono;
 use libswe_sys::sweconst::{
     Bodies, Calandar, OptionalFlag,
 }; */
-use std::{thread, time};
 use libswe_sys::swerust::handler_swe02::*;
 use serde::{Serialize, Deserialize};
 use serde_json::*;
-use clap::Parser;
-use calc::{models::date_info::*, utils::check_directory::validate_directory};
+use calc::{models::date_info::*, utils::check_directory::{validate_directory, set_validated_ephemeris_path}};
 use extensions::swe::set_sid_mode;
-use actix_web::{App, HttpServer, Responder, web::{self, Json}};
-use constants::*;
+use actix_web::{App, HttpServer, Responder, middleware::{Condition, Logger}, web::{self, Json}};
+use config::{build_cors, get_ephemeris_path, load_server_options};
+use ephemeris::set_calc_timeout;
+use errors::query_error_handler;
 use help::*;
-use routes::{chart_data::*, rise_set_times::*, planet_stations::*, datetime::*, appendix::*};
-
-/// Astrologic engine config
-#[derive(Parser, Debug)]
-#[clap(author, version, about, long_about = None)]
-struct Args {
-    // Ephemeris path
-    #[clap(short, long, value_parser, default_value_t = empty_string() )]
-    ephemeris: String,
-    #[clap(short, long, value_parser, default_value_t = 0u32 )]
-    port: u32,
-}
+use openapi::{route_list, show_openapi};
+use metrics::{init_metrics, show_metrics, RequestMetrics, REQUEST_LOG_FORMAT};
+use routes::{chart_data::*, rise_set_times::*, planet_stations::*, datetime::*, appendix::*, aspects::*, transits::*, ingresses::*, eclipses::*, stars::*, dashas::*, vargas::*, panchanga::*, progressions::*, lunar_events::*, seasons::*};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 struct AppData {
   path: String,
 }
 
-async fn date_now() -> impl Responder {
-  Json(json!(DateInfo::now()))
-}
-
 async fn welcome() -> impl Responder {
-  Json(json!({ "message": "Welcome to Astro API", "time": DateInfo::now(), "routes": endpoint_help(), "options": rise_set_option_help(), "ephemerisPath": get_ephemeris_path() }))
+  Json(json!({ "message": "Welcome to Astro API", "time": DateInfo::now(), "routes": route_list(), "options": rise_set_option_help(), "ephemerisPath": get_ephemeris_path() }))
 }
 
 async fn welcome_not_configured() -> impl Responder {
@@ -59,59 +51,50 @@ async fn route_not_found() -> impl Responder {
   Json( json!({ "valid": false, "error": "route not found" }))
 }
 
-fn get_ephemeris_path() -> String {
-  let args = Args::parse();
-  let path = args.ephemeris;
-  if path.is_empty() {
-    dotenv::var("sweph_path").unwrap_or(SWEPH_PATH_DEFAULT.to_string())
-  } else {
-    path
-  }
-}
-
-fn get_port_no() -> u16 {
-  let args = Args::parse();
-  let cmd_port = args.port as u16;
-  if cmd_port < 1 {
-    let env_port = dotenv::var("port").unwrap_or(format!("{:04}", DEFAULT_PORT));
-    if env_port.chars().all(char::is_numeric) {
-      env_port.parse::<u16>().unwrap_or(DEFAULT_PORT as u16)
-    } else {
-      DEFAULT_PORT as u16
-    }
-  } 
-  else {
-    cmd_port
-  }
-}
-
 pub fn set_ephemeris_path() {
   let ep = get_ephemeris_path();
   set_ephe_path(ep.as_str());
 }
 
-pub fn reset_ephemeris_path() {
-  let micro_interval = time::Duration::from_millis(10);
-  set_ephemeris_path();
-  thread::sleep(micro_interval);
-}
-
 #[actix_web::main]
 async fn main()  -> std::io::Result<()> {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+    init_metrics();
+    let options = match load_server_options() {
+      Ok(options) => options,
+      Err(errors) => {
+        for error in errors.iter() {
+          log::error!("{}", error);
+        }
+        return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, errors.join("; ")));
+      }
+    };
     let ephemeris_path = get_ephemeris_path();
-    let port = get_port_no();
+    let port = options.port;
+    let max_payload = options.max_payload;
+    let cors_origins = options.cors_origins.clone();
+    set_calc_timeout(options.calc_timeout);
     let (has_path , validated_sweph_path) = validate_directory(&ephemeris_path);
     if  has_path {
       set_ephe_path(validated_sweph_path.as_str());
+      set_validated_ephemeris_path(validated_sweph_path.as_str());
       set_sid_mode(0);
     }
     let path_msg = if has_path { format!("using Swiss Ephemeris path: {}", validated_sweph_path) } else { "without extended Swiss Ephemeris data sources, reverting to default Moshier formula".to_string() };
-    println!("started on port: {}, {}", port, path_msg);
-    HttpServer::new(move || {
+    let protocol = if options.tls.is_some() { "https" } else { "http" };
+    log::info!("started on {}://{}:{}, {}", protocol, options.host, port, path_msg);
+    let server = HttpServer::new(move || {
       if has_path {
         App::new()
+          .wrap(RequestMetrics)
+          .wrap(Condition::new(cors_origins.len() > 0, build_cors(&cors_origins)))
+          .wrap(Logger::new(REQUEST_LOG_FORMAT))
+          .app_data(web::JsonConfig::default().limit(max_payload))
+          .app_data(web::QueryConfig::default().error_handler(query_error_handler))
           .route("/", web::get().to(welcome))
-          .route("/jd", web::get().to(date_now))
+          .route("/metrics", web::get().to(show_metrics))
+          .route("/openapi.json", web::get().to(show_openapi))
+          .service(date_now)
           .service(date_info)
           .service(date_info_geo)
           .service(test_geo_start)
@@ -119,15 +102,29 @@ async fn main()  -> std::io::Result<()> {
           .service(body_positions)
           .service(ascendant_progress)
           .service(chart_data_flexi)
+          .service(chart_data_batch)
           .service(list_sun_rise_set_times)
           .service(pheno_data)
           .service(list_rise_set_times)
           .service(show_moon_phases)
+          .service(list_lunar_events)
           .service(body_transposed_transitions_range)
           .service(test_rise_set_times)
           .service(test_mcs)
           .service(planet_stations_progress)
+          .service(list_retrograde_cycles)
           .service(show_house_systems)
+          .service(list_aspects)
+          .service(list_transits)
+          .service(list_ingresses)
+          .service(list_seasons)
+          .service(list_eclipses)
+          .service(list_stars)
+          .service(list_dashas)
+          .service(list_vargas)
+          .service(show_panchanga)
+          .service(list_progressions)
+          .service(list_directions)
           .service(appendix_info)
           .route("/{sec1}", web::get().to(route_not_found))
           .route("/{sec1}/{sec2}", web::get().to(route_not_found))
@@ -135,14 +132,25 @@ async fn main()  -> std::io::Result<()> {
           .route("/{sec1}/{sec2}/{sec3}/{sec4}", web::get().to(route_not_found))
       } else {
         App::new()
+          .wrap(RequestMetrics)
+          .wrap(Condition::new(cors_origins.len() > 0, build_cors(&cors_origins)))
+          .wrap(Logger::new(REQUEST_LOG_FORMAT))
+          .app_data(web::JsonConfig::default().limit(max_payload))
+          .app_data(web::QueryConfig::default().error_handler(query_error_handler))
           .route("/", web::get().to(welcome_not_configured))
+          .route("/metrics", web::get().to(show_metrics))
+          .route("/openapi.json", web::get().to(show_openapi))
           .route("/{sec1}", web::get().to(welcome_not_configured))
           .route("/{sec1}/{sec2}", web::get().to(welcome_not_configured))
           .route("/{sec1}/{sec2}/{sec3}", web::get().to(welcome_not_configured))
           .route("/{sec1}/{sec2}/{sec3}/{sec4}", web::get().to(route_not_found))
       }
   })
-  .bind(("127.0.0.1", port))?
-  .run()
-  .await
+  .client_request_timeout(options.timeout);
+  let server = if options.workers > 0 { server.workers(options.workers) } else { server };
+  let server = match options.tls {
+    Some(tls_config) => server.bind_rustls((options.host.as_str(), port), tls_config)?,
+    None => server.bind((options.host.as_str(), port))?,
+  };
+  server.run().await
 }
@@ -0,0 +1,129 @@
+use std::future::{ready, Future, Ready};
+use std::pin::Pin;
+use std::sync::OnceLock;
+use std::time::Instant;
+use actix_web::{
+  dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+  Error, HttpResponse, Responder,
+};
+use prometheus::{register_histogram, register_histogram_vec, register_int_counter_vec, Encoder, Histogram, HistogramVec, IntCounterVec, TextEncoder};
+
+/*
+* Request log line with key=value pairs, e.g. remote=127.0.0.1 request="GET /chart-data?dt=2023-07-01 HTTP/1.1" status=200 bytes=5120 duration_ms=12.3
+*/
+pub const REQUEST_LOG_FORMAT: &str = r#"remote=%a request="%r" status=%s bytes=%b duration_ms=%D"#;
+
+struct ApiMetrics {
+  requests: HistogramVec,
+  calculations: Histogram,
+  errors: IntCounterVec,
+}
+
+static API_METRICS: OnceLock<ApiMetrics> = OnceLock::new();
+
+/*
+* All metrics are registered once with the default Prometheus registry
+*/
+fn api_metrics() -> &'static ApiMetrics {
+  API_METRICS.get_or_init(|| ApiMetrics {
+    requests: register_histogram_vec!(
+      "http_request_duration_seconds",
+      "HTTP request latency by method, route pattern and status code",
+      &["method", "route", "status"]
+    ).expect("valid request metric"),
+    calculations: register_histogram!(
+      "ephemeris_calculation_duration_seconds",
+      "Duration of calculations with exclusive Swiss Ephemeris access"
+    ).expect("valid calculation metric"),
+    errors: register_int_counter_vec!(
+      "calculation_errors_total",
+      "Invalid input and failed calculations, incl. invalid batch items",
+      &["kind"]
+    ).expect("valid error metric"),
+  })
+}
+
+pub fn init_metrics() {
+  api_metrics();
+}
+
+pub fn observe_request(method: &str, route: &str, status: u16, seconds: f64) {
+  api_metrics().requests.with_label_values(&[method, route, status.to_string().as_str()]).observe(seconds);
+}
+
+pub fn observe_calculation(seconds: f64) {
+  api_metrics().calculations.observe(seconds);
+}
+
+pub fn count_error(kind: &str) {
+  api_metrics().errors.with_label_values(&[kind]).inc();
+}
+
+/// Request latency, calculation and error metrics in Prometheus text format.
+#[utoipa::path(
+  get,
+  path = "/metrics",
+  responses(
+    (status = 200, description = "Prometheus text exposition format", content_type = "text/plain"),
+  ),
+)]
+pub async fn show_metrics() -> impl Responder {
+  let encoder = TextEncoder::new();
+  let mut buffer: Vec<u8> = Vec::new();
+  match encoder.encode(&prometheus::gather(), &mut buffer) {
+    Ok(_) => HttpResponse::Ok().content_type(encoder.format_type()).body(buffer),
+    Err(e) => HttpResponse::InternalServerError().body(e.to_string()),
+  }
+}
+
+/**
+ * Middleware recording the latency of every request by route pattern rather than full path,
+ * so path parameters and unknown routes do not create new time series
+ */
+pub struct RequestMetrics;
+
+impl<S, B> Transform<S, ServiceRequest> for RequestMetrics
+where
+  S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+  S::Future: 'static,
+  B: 'static,
+{
+  type Response = ServiceResponse<B>;
+  type Error = Error;
+  type InitError = ();
+  type Transform = RequestMetricsMiddleware<S>;
+  type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+  fn new_transform(&self, service: S) -> Self::Future {
+    ready(Ok(RequestMetricsMiddleware { service }))
+  }
+}
+
+pub struct RequestMetricsMiddleware<S> {
+  service: S,
+}
+
+impl<S, B> Service<ServiceRequest> for RequestMetricsMiddleware<S>
+where
+  S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+  S::Future: 'static,
+  B: 'static,
+{
+  type Response = ServiceResponse<B>;
+  type Error = Error;
+  type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+
+  forward_ready!(service);
+
+  fn call(&self, req: ServiceRequest) -> Self::Future {
+    let start = Instant::now();
+    let method = req.method().to_string();
+    let route = req.match_pattern().unwrap_or("unmatched".to_string());
+    let fut = self.service.call(req);
+    Box::pin(async move {
+      let res = fut.await?;
+      observe_request(&method, &route, res.status().as_u16(), start.elapsed().as_secs_f64());
+      Ok(res)
+    })
+  }
+}
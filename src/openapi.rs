@@ -0,0 +1,99 @@
+use std::collections::BTreeMap;
+use actix_web::{Responder, web::Json};
+use utoipa::{OpenApi, openapi::PathItemType};
+use crate::calc::{
+  aspects::AspectResult,
+  models::{date_info::DateInfo, general::*, geo_pos::GeoPos, graha_pos::*, houses::*},
+  lunar_events::LunarEvent,
+  planet_stations::{BodySpeedSet, PlanetSample, RetrogradeCycle, RetrogradeCycleSet},
+  progressions::{PrimaryDirection, ProgressedChart, SolarArcChart},
+  seasons::SeasonEvent,
+  vargas::{VargaChart, VargaPosition},
+};
+use crate::errors::ErrorResponse;
+use crate::query_params::InputOptions;
+use crate::routes::{chart_data::ChartDataResult, *};
+
+/*
+* OpenAPI 3 document generated from the annotated route handlers, the query options and the response types
+*/
+#[derive(OpenApi)]
+#[openapi(
+  info(title = "Astro API", description = "Astronomical and astrological calculations with the Swiss Ephemeris"),
+  paths(
+    datetime::date_now,
+    datetime::date_info,
+    datetime::date_info_geo,
+    datetime::test_geo_start,
+    crate::metrics::show_metrics,
+    appendix::appendix_info,
+    chart_data::body_positions,
+    chart_data::ascendant_progress,
+    chart_data::show_moon_phases,
+    lunar_events::list_lunar_events,
+    chart_data::chart_data_flexi,
+    chart_data::chart_data_batch,
+    chart_data::show_house_systems,
+    chart_data::bodies_progress,
+    rise_set_times::list_rise_set_times,
+    rise_set_times::list_sun_rise_set_times,
+    rise_set_times::pheno_data,
+    rise_set_times::body_transposed_transitions_range,
+    rise_set_times::test_rise_set_times,
+    rise_set_times::test_mcs,
+    planet_stations::planet_stations_progress,
+    planet_stations::list_retrograde_cycles,
+    aspects::list_aspects,
+    transits::list_transits,
+    ingresses::list_ingresses,
+    seasons::list_seasons,
+    eclipses::list_eclipses,
+    stars::list_stars,
+    dashas::list_dashas,
+    vargas::list_vargas,
+    panchanga::show_panchanga,
+    progressions::list_progressions,
+    progressions::list_directions,
+  ),
+  components(schemas(
+    InputOptions, ErrorResponse, ChartDataResult, DateInfo, GeoPos,
+    GrahaPos, BodyPos, XyzPos, FlexiBodyPos, PhenoResult, PhenoItem,
+    HouseSet, HouseSetData, AscMc, AspectResult, BodySpeedSet, PlanetSample, RetrogradeCycleSet, RetrogradeCycle, VargaChart, VargaPosition,
+    ProgressedChart, SolarArcChart, PrimaryDirection, LunarEvent, SeasonEvent,
+    KeyNumValue, KeyNumIdValue, KeyStringValue, KeyFlexiValueSet, FlexiValue, LngLat, LngLatKey, SunPeriod,
+  )),
+)]
+pub struct ApiDoc;
+
+fn method_name(method: &PathItemType) -> &'static str {
+  match method {
+    PathItemType::Get => "GET",
+    PathItemType::Post => "POST",
+    PathItemType::Put => "PUT",
+    PathItemType::Delete => "DELETE",
+    PathItemType::Options => "OPTIONS",
+    PathItemType::Head => "HEAD",
+    PathItemType::Patch => "PATCH",
+    PathItemType::Trace => "TRACE",
+    PathItemType::Connect => "CONNECT",
+  }
+}
+
+/**
+ * Route summaries keyed by method and path, e.g. GET /chart-data, derived from the OpenAPI document,
+ * so the welcome page lists exactly the documented routes
+ */
+pub fn route_list() -> BTreeMap<String, String> {
+  let mut routes: BTreeMap<String, String> = BTreeMap::new();
+  for (path, item) in ApiDoc::openapi().paths.paths.iter() {
+    for (method, operation) in item.operations.iter() {
+      let summary = operation.summary.clone().unwrap_or("".to_string());
+      routes.insert(format!("{} {}", method_name(method), path), summary);
+    }
+  }
+  routes
+}
+
+pub async fn show_openapi() -> impl Responder {
+  Json(ApiDoc::openapi())
+}
@@ -1,45 +1,118 @@
+use std::fmt::Display;
 use serde::Deserialize;
-use actix_web::web::Query;
-use crate::calc::{utils::converters::loc_string_to_geo, models::geo_pos::GeoPos};
+use utoipa::{IntoParams, ToSchema};
+use serde_json::Value;
+use crate::calc::{
+  errors::{CalcError, CalcErrors},
+  models::{general::{CoordinateSystem, TopoMode}, geo_pos::GeoPos},
+  rise_set_phases::{CentricMode, TransitionMode},
+  settings::{body_values::{is_valid_body_key, BODY_GROUP_KEYS}, stars::is_fixed_star_key},
+  timezones::{add_local_times, local_date_to_date_info, match_time_zone, TimeZoneRef},
+  utils::converters::{is_available_asteroid_key, loc_string_to_geo},
+};
 use super::calc::{models::date_info::DateInfo, dates::current_datetime_string};
 
-#[derive(Deserialize)]
+/*
+* Query string options shared by all endpoints. Field docs are used in the OpenAPI parameter descriptions
+*/
+#[derive(Deserialize, IntoParams, ToSchema)]
+#[into_params(parameter_in = Query)]
 pub struct InputOptions {
-  pub dt: Option<String>, // primary UTC date string
-  pub dtl: Option<String>, // primary date string in local time (requires offset)
-  pub jd: Option<f64>, // primary jd as a float
-  pub dt2: Option<String>, // secondary UTC date string 
-  pub dtl2: Option<String>, // secondary date string in local time (requires offset)
-  pub jd2: Option<f64>, // secondary jd as a float
-  pub bodies: Option<String>, // either a comma separated list of required 2-letter celestial body keys or body group keys
-  pub trbs: Option<String>, // body keys for transitions where shown. If not specified, the body keys above will be used
-  pub topo: Option<u8>, // 0 = geocentric, 1 topocentric, 2 both, default 0
-  pub eq: Option<u8>, // 0 = ecliptic, 1 equatorial, 2 both, both 3 with altitude/azimuth, 4 with inline planetary phenomena
-  pub ph: Option<u8>, // 0 = none (except via eq=4 in /chart-data), 1 = show pheno(nema) as separate array
-  pub days: Option<u16>, // duration in days where applicable
-  pub pd: Option<u8>, // number per day or per specified multiple of days (dspan), 2 => every 12 hours
-  pub dspan: Option<u16>, // multiple of days in the current base unit 
-  pub years: Option<u16>, // duration in years where applicable
-  pub loc: Option<String>, // comma-separated lat,lng(,alt) numeric string
-  pub loc2: Option<String>, // comma-separated lat,lng(,alt) numeric string for second reference location
-  pub body: Option<String>, // primary celestial body key
-  pub ct: Option<u8>, // show contemporary rise/set times 
-  pub sp: Option<u8>, // show sun-up/sun-down period with sun lat/long at sunrise/sunset
-  pub aya: Option<String>, // comma-separated list ayanamshas to be calculated. The first may be applied to ecliptic longitudes via sid=1
-  //pub amode: Option<String>, // apply referenced sidereal type (ayanamsha) to all longitudes
-  pub sid: Option<u8>, // 0 tropical longitudes, 1 sidereal longitudes of first reference ayanamsha (via aya)
-  pub hsys: Option<String>, // comma-separated list of letters representing house systems to be returned. Defaults to W for whole house system
-  pub retro: Option<u8>, // show planet stations (retrograde, peak), 0 no, 1 yes
-  pub iso: Option<u8>, // 0 show JD, 1 show ISO UTC
-  //pub offset: Option<i32>, // offset is seconds from UTC
-  pub tzs: Option<i32>, // offset in seconds from UTC
-  pub full: Option<u8>, // 0 default, 1 full / extended
-  pub num: Option<u32>, // integer number
-  pub orb: Option<f64>, // reference degree two
-  pub mode: Option<u8>, // response mode, depends on endpoint
-}
-
-pub fn to_ayanamsha_keys(params: &Query<InputOptions>, def_val: &str) -> (Vec<String>, String, String) {
+  /// Primary UTC date-time string, e.g. 2024-02-28T12:00:00
+  pub dt: Option<String>,
+  /// Primary date string in local time, resolved with tz or tzs
+  pub dtl: Option<String>,
+  /// Primary julian day as a decimal, alternative to dt
+  pub jd: Option<f64>,
+  /// Secondary UTC date string
+  pub dt2: Option<String>,
+  /// Secondary date string in local time, resolved with tz or tzs
+  pub dtl2: Option<String>,
+  /// Secondary julian day as a decimal, alternative to dt2
+  pub jd2: Option<f64>,
+  /// Either a comma separated list of required 2-letter celestial body keys or body group keys
+  pub bodies: Option<String>,
+  /// Body keys for transitions where shown. If not specified, the body keys above will be used
+  pub trbs: Option<String>,
+  /// 0 = geocentric, 1 topocentric, 2 both, default 0
+  pub topo: Option<u8>,
+  /// Reference point of body positions: geo (default), helio or bary. Heliocentric and barycentric positions cannot be combined with topo=1
+  pub centric: Option<String>,
+  /// 0 = ecliptic, 1 equatorial, 2 both, both 3 with altitude/azimuth, 4 with inline planetary phenomena, 5 ecliptic XYZ, 6 equatorial XYZ
+  pub eq: Option<u8>,
+  /// 0 = none (except via eq=4 in /chart-data), 1 = show pheno(nema) as separate array
+  pub ph: Option<u8>,
+  /// Duration in days where applicable
+  pub days: Option<u16>,
+  /// Number per day or per specified multiple of days (dspan), 2 => every 12 hours
+  pub pd: Option<u8>,
+  /// Multiple of days in the current base unit
+  pub dspan: Option<u16>,
+  /// Duration in years where applicable
+  pub years: Option<u16>,
+  /// Comma-separated lat,lng(,alt) numeric string
+  pub loc: Option<String>,
+  /// Comma-separated lat,lng(,alt) numeric string for second reference location
+  pub loc2: Option<String>,
+  /// Primary celestial body key
+  pub body: Option<String>,
+  /// Show contemporary rise/set times
+  pub ct: Option<u8>,
+  /// Show sun-up/sun-down period with sun lat/long at sunrise/sunset
+  pub sp: Option<u8>,
+  /// Comma-separated list ayanamshas to be calculated. The first may be applied to ecliptic longitudes via sid=1
+  pub aya: Option<String>,
+  /// 0 tropical longitudes, 1 sidereal longitudes of first reference ayanamsha (via aya)
+  pub sid: Option<u8>,
+  /// Comma-separated list of letters representing house systems to be returned. Defaults to W for whole house system
+  pub hsys: Option<String>,
+  /// Show planet stations (retrograde, peak), 0 no, 1 yes
+  pub retro: Option<u8>,
+  /// 0 show JD, 1 show ISO UTC
+  pub iso: Option<u8>,
+  /// Offset in seconds from UTC
+  pub tzs: Option<i32>,
+  /// 0 default, 1 full / extended
+  pub full: Option<u8>,
+  /// Integer number
+  pub num: Option<u32>,
+  /// Reference degree: maximum orb for aspects or target angle / longitude for transits
+  pub orb: Option<f64>,
+  /// 0 = signs only, 1 = signs and nakshatras, 2 = signs, nakshatras and padas
+  pub nak: Option<u8>,
+  /// Fixed reference ecliptic longitude, e.g. a natal position
+  pub lng: Option<f64>,
+  /// Aspect set key (major, minor, all) or comma-separated aspect keys
+  pub asp: Option<String>,
+  /// Response mode, depends on endpoint
+  pub mode: Option<u8>,
+  /// Dasha system key: vimshottari (default), yogini or ashtottari. Lunar month scheme in /moon-phases: amanta (default) or purnimanta
+  pub sys: Option<String>,
+  /// Primary direction method in /directions: placidus (default) or regiomontanus
+  pub method: Option<String>,
+  /// Rate of primary directions in degrees per year: naibod (default) or ptolemy
+  pub rate: Option<String>,
+  /// Moon phase boundaries: 4 = quarters (default), 8 = octants, 30 = tithis
+  pub div: Option<u8>,
+  /// Year length in days for dasha periods, default 365.25, e.g. 360 for savana years
+  pub yl: Option<f64>,
+  /// Number of nested period levels, e.g. 1 mahadashas, 2 with antardashas, 3 with pratyantardashas
+  pub depth: Option<u8>,
+  /// IANA time zone name, e.g. Europe/Rome, for local dates (dtl, dtl2) and local time output (lt)
+  pub tz: Option<String>,
+  /// 1 = mirror julian days and ISO UTC datetimes as local time (requires tz or tzs)
+  pub lt: Option<u8>,
+  /// Comma-separated divisional chart numbers, e.g. 9,10 or d9,d10, core or all
+  pub vargas: Option<String>,
+}
+
+impl InputOptions {
+  pub fn has_date_2(&self) -> bool {
+    self.dt2.is_some() || self.jd2.is_some() || self.dtl2.is_some()
+  }
+}
+
+pub fn to_ayanamsha_keys(params: &InputOptions, def_val: &str) -> (Vec<String>, String, String) {
   let aya: String = params.aya.clone().unwrap_or(def_val.to_string());
   let aya_keys: Vec<String> = match aya.as_str() {
     "all" => vec![],
@@ -55,13 +128,34 @@ pub fn to_ayanamsha_keys(params: &Query<InputOptions>, def_val: &str) -> (Vec<St
   (aya_keys, mode, first)
 }
 
-pub fn to_date_object_by_num(params: &Query<InputOptions>, num: u8) -> DateInfo {
+/*
+* An IANA zone name takes precedence over a fixed offset in seconds
+*/
+pub fn to_time_zone(params: &InputOptions) -> Result<Option<TimeZoneRef>, CalcError> {
+  if let Some(tz_name) = params.tz.clone() {
+    match_time_zone(tz_name.as_str()).map(|tz| Some(tz))
+  } else if let Some(tzs) = params.tzs {
+    Ok(Some(TimeZoneRef::Fixed(tzs)))
+  } else {
+    Ok(None)
+  }
+}
+
+pub fn to_date_object_by_num(params: &InputOptions, num: u8) -> Result<DateInfo, CalcError> {
   let jd = match num {
     2 => params.jd2.clone().unwrap_or(0f64),
     _ => params.jd.clone().unwrap_or(0f64)
   };
+  let (dt_opt, dtl_opt) = match num {
+    2 => (params.dt2.clone(), params.dtl2.clone()),
+    _ => (params.dt.clone(), params.dtl.clone()),
+  };
   if jd > 1_000_000f64 {
-    DateInfo::new_from_jd(jd)
+    DateInfo::try_from_jd(jd)
+  } else if let (None, Some(dtl)) = (dt_opt, dtl_opt) {
+    // local dates without a time zone are treated as UTC
+    let tz = to_time_zone(&params)?.unwrap_or(TimeZoneRef::Fixed(0));
+    local_date_to_date_info(dtl.as_str(), &tz)
   } else {
     let dateref: String = match num {
       2 => params.dt2.clone().unwrap_or(current_datetime_string()),
@@ -71,19 +165,187 @@ pub fn to_date_object_by_num(params: &Query<InputOptions>, num: u8) -> DateInfo
   }
 }
 
-pub fn to_date_object(params: &Query<InputOptions>) -> DateInfo {
+pub fn to_date_object(params: &InputOptions) -> Result<DateInfo, CalcError> {
   to_date_object_by_num(&params, 1)
 }
 
-pub fn to_date_object_2(params: &Query<InputOptions>) -> DateInfo {
+pub fn to_date_object_2(params: &InputOptions) -> Result<DateInfo, CalcError> {
   to_date_object_by_num(&params, 2)
 }
 
-pub fn to_geopos_object(params: &Query<InputOptions>) -> GeoPos {
-  let loc: String = params.loc.clone().unwrap_or("0,0".to_string());
-  if let Some(geo_pos) = loc_string_to_geo(loc.as_str()) {
-    geo_pos
+/*
+* A missing location defaults to 0,0, but an unparseable one is an error
+*/
+pub fn to_geopos_object_by_num(params: &InputOptions, num: u8) -> Result<GeoPos, CalcError> {
+  let loc_opt = match num {
+    2 => params.loc2.clone(),
+    _ => params.loc.clone(),
+  };
+  if let Some(loc) = loc_opt {
+    loc_string_to_geo(loc.as_str()).ok_or(CalcError::InvalidLocation(loc))
+  } else {
+    Ok(GeoPos::zero())
+  }
+}
+
+pub fn to_geopos_object(params: &InputOptions) -> Result<GeoPos, CalcError> {
+  to_geopos_object_by_num(&params, 1)
+}
+
+pub fn to_geopos_object_2(params: &InputOptions) -> Result<GeoPos, CalcError> {
+  to_geopos_object_by_num(&params, 2)
+}
+
+/*
+* Body keys, group keys, the ascendant, available asteroids and catalogue stars are accepted in body lists
+*/
+pub fn is_valid_input_key(key: &str) -> bool {
+  let simple_key = key.trim().to_lowercase();
+  simple_key.is_empty()
+    || is_valid_body_key(&simple_key)
+    || BODY_GROUP_KEYS.contains(&simple_key.as_str())
+    || simple_key == "as"
+    || is_available_asteroid_key(&simple_key)
+    || is_fixed_star_key(&simple_key)
+}
+
+fn invalid_keys(key_string: &Option<String>, is_valid: &dyn Fn(&str) -> bool) -> Vec<String> {
+  match key_string {
+    Some(keys) => keys.split(",").filter(|k| !is_valid(k)).map(|k| k.to_string()).collect(),
+    _ => vec![],
+  }
+}
+
+/*
+* Collect all invalid dates, locations and body keys so they can be reported together
+*/
+pub fn input_errors_with(params: &InputOptions, is_valid_key: &dyn Fn(&str) -> bool) -> CalcErrors {
+  let mut errors = CalcErrors::new();
+  errors.check(to_time_zone(&params));
+  errors.check(to_date_object(&params));
+  if params.has_date_2() {
+    errors.check(to_date_object_2(&params));
+  }
+  errors.check(to_geopos_object(&params));
+  errors.check(to_geopos_object_2(&params));
+  let mut keys = invalid_keys(&params.bodies, is_valid_key);
+  keys.extend(invalid_keys(&params.trbs, is_valid_key));
+  keys.extend(invalid_keys(&params.body, is_valid_key));
+  if keys.len() > 0 {
+    keys.sort();
+    keys.dedup();
+    errors.add(CalcError::InvalidBodyKeys(keys));
+  }
+  errors
+}
+
+pub fn input_errors(params: &InputOptions) -> CalcErrors {
+  input_errors_with(params, &is_valid_input_key)
+}
+
+pub fn validate_input_with(params: &InputOptions, is_valid_key: &dyn Fn(&str) -> bool) -> Result<(), CalcErrors> {
+  input_errors_with(params, is_valid_key).into_result()
+}
+
+pub fn validate_input(params: &InputOptions) -> Result<(), CalcErrors> {
+  validate_input_with(params, &is_valid_input_key)
+}
+
+/*
+* Numeric options with endpoint-specific limits are rejected rather than clamped
+*/
+pub fn to_num_in_range<T: PartialOrd + Display + Copy>(key: &str, value: Option<T>, default: T, min: T, max: T) -> Result<T, CalcError> {
+  let num = value.unwrap_or(default);
+  if num < min || num > max {
+    Err(CalcError::InvalidParam(key.to_string(), format!("{} is not between {} and {}", num, min, max)))
   } else {
-    GeoPos::zero()
+    Ok(num)
+  }
+}
+
+/*
+* eq: 0 ecliptic, 1 equatorial, 2 dual, 3 horizontal, 5 ecliptic XYZ, 6 equatorial XYZ, limited to the systems supported by the endpoint
+*/
+pub fn to_coordinate_system(params: &InputOptions, default: CoordinateSystem, supported: &[CoordinateSystem]) -> Result<CoordinateSystem, CalcError> {
+  match params.eq {
+    Some(num) => match CoordinateSystem::from_u8(num) {
+      Some(cs) if supported.contains(&cs) => Ok(cs),
+      _ => {
+        let nums: Vec<String> = supported.iter().map(|cs| (*cs as u8).to_string()).collect();
+        Err(CalcError::InvalidParam("eq".to_string(), format!("{} is not one of {}", num, nums.join(", "))))
+      },
+    },
+    None => Ok(default),
+  }
+}
+
+/*
+* topo: 0 geocentric, 1 topocentric, 2 both where supported
+*/
+pub fn to_topo_mode(params: &InputOptions, allow_both: bool) -> Result<TopoMode, CalcError> {
+  let max = if allow_both { 2 } else { 1 };
+  let num = to_num_in_range("topo", params.topo, 0, 0, max)?;
+  Ok(TopoMode::from_u8(num).unwrap_or(TopoMode::Geocentric))
+}
+
+/*
+* centric: geo (default), helio or bary. Topocentric positions are selected via topo
+*/
+pub fn to_centric_mode(params: &InputOptions, topo: TopoMode) -> Result<CentricMode, CalcError> {
+  let mode = match params.centric.clone() {
+    Some(key) => match CentricMode::from_key(key.as_str()) {
+      Some(CentricMode::Topo) | None => return Err(CalcError::InvalidParam("centric".to_string(), format!("{} is not one of geo, helio, bary", key))),
+      Some(mode) => mode,
+    },
+    None => CentricMode::Geo,
+  };
+  match (mode, topo) {
+    (CentricMode::Geo, TopoMode::Topocentric) => Ok(CentricMode::Topo),
+    (CentricMode::Geo, _) => Ok(CentricMode::Geo),
+    (_, TopoMode::Geocentric) => Ok(mode),
+    _ => Err(CalcError::InvalidParam("centric".to_string(), format!("{} positions cannot be combined with topo", mode.to_key()))),
+  }
+}
+
+/*
+* mode: rise / set alignment and refraction options 0 to 7, default 3 (centre disc with refraction)
+*/
+pub fn to_transition_mode(params: &InputOptions) -> Result<TransitionMode, CalcError> {
+  to_num_in_range("mode", params.mode, 3, 0, 7).map(|num| TransitionMode::from_u8(num))
+}
+
+/*
+* Add local time variants of all julian days and ISO UTC datetimes if requested via lt=1
+*/
+pub fn with_local_times(params: &InputOptions, value: Value) -> Value {
+  match (params.lt.unwrap_or(0) > 0, to_time_zone(&params)) {
+    (true, Ok(Some(tz))) => add_local_times(value, &tz),
+    _ => value,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn to_options(value: Value) -> InputOptions {
+    serde_json::from_value(value).unwrap()
+  }
+
+  #[test]
+  fn test_centric_mode_with_topo() {
+    let geo = to_options(serde_json::json!({ "topo": 1 }));
+    assert_eq!(to_centric_mode(&geo, TopoMode::Topocentric).unwrap(), CentricMode::Topo);
+    for key in ["helio", "bary"] {
+      let params = to_options(serde_json::json!({ "centric": key, "topo": 1 }));
+      assert!(to_centric_mode(&params, TopoMode::Geocentric).is_ok());
+      assert!(to_centric_mode(&params, TopoMode::Topocentric).is_err());
+    }
+  }
+
+  #[test]
+  fn test_invalid_keys_listed_once() {
+    let params = to_options(serde_json::json!({ "bodies": "xx,su,yy,xx", "trbs": "yy" }));
+    assert_eq!(input_errors(&params).invalid_keys(), vec!["xx", "yy"]);
   }
 }
@@ -1,10 +1,17 @@
 use serde_json::json;
-use crate::calc::settings::{ayanamshas::{all_ayanamsha_keys, AynamshaInfo}, house_systems::houses_as_key_map};
+use crate::calc::settings::{ayanamshas::{all_ayanamsha_keys, AynamshaInfo}, house_systems::houses_as_key_map, stars::all_fixed_stars};
 use actix_web::{get, Responder,web::Json};
 
+/// Names, keys, numbers and attributes of celestial objects (grahas), houses and ayanamshas.
+#[utoipa::path(
+  responses(
+    (status = 200, description = "Success"),
+  ),
+)]
 #[get("/appendix")]
 pub async fn appendix_info() -> impl Responder {
   let ayanamsha_details: Vec<AynamshaInfo> = all_ayanamsha_keys().into_iter().map(|ak| AynamshaInfo::new(ak)).collect();
   let houses = houses_as_key_map();
-  Json(json!({ "ayanamshas": ayanamsha_details, "houseSystems": houses }))
+  let stars = all_fixed_stars();
+  Json(json!({ "ayanamshas": ayanamsha_details, "houseSystems": houses, "fixedStars": stars }))
 }
@@ -0,0 +1,64 @@
+use serde_json::json;
+use actix_web::{get, Responder, web::{Query, Json}};
+use crate::{query_params::*, ephemeris::run_ephemeris};
+use crate::calc::{
+  aspects::{calc_aspects, match_aspect_types, AspectResult},
+  core::{get_ayanamsha_value, get_bodies_ecl_geo, get_bodies_ecl_topo},
+  errors::CalcErrors,
+  models::{general::TopoMode, houses::get_house_systems},
+  settings::ayanamshas::match_ayanamsha_key,
+  utils::converters::*,
+};
+
+/*
+* Typed /aspects options
+*/
+struct AspectOptions {
+  topo: TopoMode,
+}
+
+impl AspectOptions {
+  fn from_input(params: &InputOptions) -> Result<Self, CalcErrors> {
+    let mut errors = input_errors(params);
+    let topo = errors.check(to_topo_mode(params, false)).unwrap_or(TopoMode::Geocentric);
+    errors.into_result()?;
+    Ok(AspectOptions { topo })
+  }
+}
+
+/// Aspects between celestial bodies, the ascendant and MC with applying / separating state.
+#[utoipa::path(
+  params(InputOptions),
+  responses(
+    (status = 200, description = "Success"),
+    (status = 400, description = "Invalid parameters", body = crate::errors::ErrorResponse),
+  ),
+)]
+#[get("/aspects")]
+async fn list_aspects(params: Query<InputOptions>) -> Result<impl Responder, CalcErrors> {
+  let options = AspectOptions::from_input(&params)?;
+  let result = run_ephemeris(move || {
+    let date = to_date_object(&params)?;
+    let geo = to_geopos_object(&params)?;
+    let def_keys = vec!["su", "mo", "ma", "me", "ju", "ve", "sa", "ur", "ne", "pl"];
+    let key_string: String = params.bodies.clone().unwrap_or("".to_string());
+    let keys = body_keys_str_to_keys_or(key_string, def_keys);
+    let aya: String = params.aya.clone().unwrap_or("tropical".to_string());
+    let sidereal: bool = params.sid.unwrap_or(0) > 0;
+    let aya_key = match_ayanamsha_key(aya.as_str());
+    let ayanamsha = get_ayanamsha_value(date.jd, aya.as_str());
+    let aya_offset = if sidereal { ayanamsha } else { 0f64 };
+    let aspect_types = match_aspect_types(params.asp.clone().unwrap_or("major".to_string()).as_str());
+    let bodies = if options.topo == TopoMode::Topocentric {
+      get_bodies_ecl_topo(date.jd, &to_str_refs(&keys), geo, aya_offset)
+    } else {
+      get_bodies_ecl_geo(date.jd, &to_str_refs(&keys), aya_offset)
+    };
+    let house = get_house_systems(date.jd, geo, vec!['W'], aya_offset);
+    let aspects: Vec<AspectResult> = calc_aspects(&bodies, Some(house.points), &aspect_types, params.orb);
+    let valid = bodies.len() > 0;
+    let aspect_keys: Vec<String> = aspect_types.iter().map(|a| a.as_string()).collect();
+    Ok(with_local_times(&params, json!({ "valid": valid, "date": date, "geo": geo, "ayanamsha": { "key": aya_key, "value": ayanamsha, "applied": sidereal }, "aspectTypes": aspect_keys, "aspects": aspects })))
+  }).await?;
+  Ok(Json(result))
+}
@@ -1,28 +1,34 @@
 use crate::calc::math_funcs::subtract_360;
 use crate::calc::settings::{ayanamshas::match_ayanamsha_num, house_systems::houses_as_key_map};
 use crate::calc::{
+  aspects::{calc_aspects, match_aspect_types, AspectResult},
   core::*,
+  errors::{CalcError, CalcErrors},
   models::{date_info::*, general::*, geo_pos::*, graha_pos::*, houses::*},
   planet_stations::{match_all_nextprev_planet_stations, BodySpeedSet},
-  settings::ayanamshas::match_ayanamsha_key,
+  settings::{ayanamshas::{match_ayanamsha_key, match_sidereal_ayanamsha_key}, varga_values::match_varga_nums},
   rise_set_phases::*,
   utils::converters::*,
-  lunar_cycles::{calc_moon_phases,calc_sun_moon_angle,MoonPhase},
+  lunar_cycles::{calc_lunar_months, calc_moon_phases, calc_sun_moon_angle, MoonPhase, MonthScheme, PhaseDivision, MEDIAN_LUNAR_MONTH},
+  timezones::TimeZoneRef,
+  stars::{calc_stars_pos, get_star_transition_sets, match_star_keys},
+  vargas::{calc_body_vargas, VargaChart},
 };
 use crate::query_params::*;
-use crate::reset_ephemeris_path;
+use crate::{constants::MAX_BATCH_CHARTS, errors::calc_errors_to_json, ephemeris::{run_ephemeris, run_ephemeris_batch}};
 use actix_web::{
   get,
+  post,
   web::{Json, Query},
   Responder,
 };
 use serde::{Deserialize, Serialize};
-use serde_json::*;
+use utoipa::ToSchema;
+use serde_json::{json, Value};
 use std::collections::HashMap;
-use std::{thread, time};
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
-struct ChartDataResult {
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+pub struct ChartDataResult {
   valid: bool,
   date: DateInfo,
   geo: GeoPos,
@@ -42,193 +48,389 @@ struct ChartDataResult {
   sun_positions: Vec<KeyNumValue>,
   #[serde(rename = "sunPeriod", skip_serializing_if = "Option::is_none")]
   sun_period: Option<SunPeriod>,
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  aspects: Vec<AspectResult>,
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  vargas: Vec<VargaChart>,
 }
 
-#[get("/positions")]
-async fn body_positions(params: Query<InputOptions>) -> impl Responder {
-  reset_ephemeris_path();
-  let micro_interval = time::Duration::from_millis(20);
-  let date = to_date_object(&params);
-  let geo = to_geopos_object(&params);
-  let aya: String = params.aya.clone().unwrap_or("tropical".to_string());
-  let eq: u8 = params.eq.clone().unwrap_or(2); // 0 ecliptic, 1 equatorial, 2 both
-  let sidereal: bool = params.sid.unwrap_or(0) > 0 && eq < 1; // omly applied if ecliptic
-  let topo: u8 = params.topo.clone().unwrap_or(0);
-  let def_keys = vec![
-    "su", "mo", "ma", "me", "ju", "ve", "sa", "ur", "ne", "pl", "ra", "ke",
-  ];
-  let key_string: String = params.bodies.clone().unwrap_or("".to_string());
-  let keys = body_keys_str_to_keys_or(key_string, def_keys);
-  let aya_key = match_ayanamsha_key(aya.as_str());
-  let ayanamsha = get_ayanamsha_value(date.jd, aya.as_str());
-  let aya_offset = if sidereal { ayanamsha } else { 0f64 };
-  let iso_mode: bool = params.iso.clone().unwrap_or(0) > 0;
-  let mode = TransitionMode::from_u8(params.mode.unwrap_or(3));
-  let longitudes = get_body_longitudes_contextual(date.jd, geo, eq, topo, aya_offset, &to_str_refs(&keys));
-  let valid = longitudes.len() > 0;
-  let sun_rise_sets = calc_transition_sun(date.jd, geo, true, mode).to_value_set(iso_mode);
-  let moon_rise_sets = calc_transition_moon(date.jd, geo, true, mode).to_value_set(iso_mode);
-  let coord_system = build_coord_system_label(eq, topo > 0);
+const MAX_ASCENDANT_DAYS: u16 = 366;
+const MAX_ASCENDANT_PER_DAY: u8 = 240;
+const MAX_PROGRESS_DAYS: u16 = 36525;
+const MAX_PROGRESS_PER_DAY: u8 = 24;
+const MAX_PROGRESS_DAY_SPAN: u16 = 3660;
+const MAX_PROGRESS_SAMPLES: f64 = 1000f64;
+const MAX_MOON_PHASE_CYCLES: u32 = 44;
 
-  thread::sleep(micro_interval);
-  Json(
-    json!({ "valid": valid, "date": date, "geo": geo, "longitudes": longitudes, "ayanamsha": { "key": aya_key, "value": ayanamsha, "applied": sidereal }, "coordinateSystem": coord_system, "sunRiseSets": sun_rise_sets, "moonRiseSets": moon_rise_sets }),
-  )
+/*
+* Typed /positions options
+*/
+struct PositionOptions {
+  coord_system: CoordinateSystem,
+  centric: CentricMode,
+  mode: TransitionMode,
+}
+
+impl PositionOptions {
+  fn from_input(params: &InputOptions) -> Result<Self, CalcErrors> {
+    let mut errors = input_errors(params);
+    let coord_system = errors.check(to_coordinate_system(params, CoordinateSystem::Dual, &[CoordinateSystem::Ecliptic, CoordinateSystem::Equatorial, CoordinateSystem::Dual, CoordinateSystem::Rectangular, CoordinateSystem::RectangularEq])).unwrap_or(CoordinateSystem::Dual);
+    let topo = errors.check(to_topo_mode(params, false)).unwrap_or(TopoMode::Geocentric);
+    let centric = errors.check(to_centric_mode(params, topo)).unwrap_or(CentricMode::Geo);
+    let mode = errors.check(to_transition_mode(params)).unwrap_or(TransitionMode::CenterOnly);
+    errors.into_result()?;
+    Ok(PositionOptions { coord_system, centric, mode })
+  }
 }
 
+/*
+* Typed /ascendant options. pd is the number of samples per day
+*/
+struct AscendantOptions {
+  days: u16,
+  per_day: u8,
+  coord_system: CoordinateSystem,
+  topo: TopoMode,
+  mode: TransitionMode,
+}
 
+impl AscendantOptions {
+  fn from_input(params: &InputOptions) -> Result<Self, CalcErrors> {
+    let mut errors = input_errors(params);
+    let days = errors.check(to_num_in_range("days", params.days, 1, 1, MAX_ASCENDANT_DAYS)).unwrap_or(1);
+    let per_day = errors.check(to_num_in_range("pd", params.pd, 24, 1, MAX_ASCENDANT_PER_DAY)).unwrap_or(24);
+    let coord_system = errors.check(to_coordinate_system(params, CoordinateSystem::Ecliptic, &[CoordinateSystem::Ecliptic, CoordinateSystem::Equatorial])).unwrap_or(CoordinateSystem::Ecliptic);
+    let topo = errors.check(to_topo_mode(params, false)).unwrap_or(TopoMode::Geocentric);
+    let mode = errors.check(to_transition_mode(params)).unwrap_or(TransitionMode::CenterOnly);
+    errors.into_result()?;
+    Ok(AscendantOptions { days, per_day, coord_system, topo, mode })
+  }
+}
+
+/*
+* Typed /progress options. Samples are taken pd times per day or pd times per dspan days
+*/
+struct ProgressOptions {
+  days: u16,
+  per_day: f64,
+  day_span: u16,
+  coord_system: CoordinateSystem,
+  centric: CentricMode,
+}
+
+impl ProgressOptions {
+  fn from_input(params: &InputOptions) -> Result<Self, CalcErrors> {
+    let mut errors = input_errors(params);
+    let days = errors.check(to_num_in_range("days", params.days, 28, 1, MAX_PROGRESS_DAYS)).unwrap_or(28);
+    let pd = errors.check(to_num_in_range("pd", params.pd, 2, 1, MAX_PROGRESS_PER_DAY)).unwrap_or(2);
+    let day_span = errors.check(to_num_in_range("dspan", params.dspan, 1, 1, MAX_PROGRESS_DAY_SPAN)).unwrap_or(1);
+    let coord_system = errors.check(to_coordinate_system(params, CoordinateSystem::Ecliptic, &[CoordinateSystem::Ecliptic, CoordinateSystem::Equatorial, CoordinateSystem::Horizontal, CoordinateSystem::Rectangular, CoordinateSystem::RectangularEq])).unwrap_or(CoordinateSystem::Ecliptic);
+    let topo = errors.check(to_topo_mode(params, false)).unwrap_or(TopoMode::Geocentric);
+    let centric = errors.check(to_centric_mode(params, topo)).unwrap_or(CentricMode::Geo);
+    if centric.is_solar_system() && coord_system == CoordinateSystem::Horizontal {
+      errors.add(CalcError::InvalidParam("eq".to_string(), format!("horizontal coordinates are not available with {} positions", centric.to_key())));
+    }
+    let per_day = pd as f64 / day_span as f64;
+    if days as f64 * per_day > MAX_PROGRESS_SAMPLES {
+      errors.add(CalcError::InvalidParam("days".to_string(), format!("{} days with {} samples per {} day(s) exceed the maximum of {} samples", days, pd, day_span, MAX_PROGRESS_SAMPLES)));
+    }
+    errors.into_result()?;
+    Ok(ProgressOptions { days, per_day, day_span, coord_system, centric })
+  }
+}
+
+/*
+* Typed /chart-data options. eq=4 shows dual coordinates with inline planetary phenomena,
+* while eq=5 and eq=6 show ecliptic or equatorial XYZ coordinates
+*/
+struct ChartOptions {
+  coord_system: CoordinateSystem,
+  pheno_inline: bool,
+  topo: TopoMode,
+  centric: CentricMode,
+  mode: TransitionMode,
+}
+
+impl ChartOptions {
+  fn from_input(params: &InputOptions) -> Result<Self, CalcErrors> {
+    let mut errors = input_errors(params);
+    let eq = errors.check(to_num_in_range("eq", params.eq, 2, 0, 6)).unwrap_or(2);
+    let coord_system = CoordinateSystem::from_u8(eq).unwrap_or(CoordinateSystem::Dual);
+    let topo = errors.check(to_topo_mode(params, true)).unwrap_or(TopoMode::Geocentric);
+    let centric = errors.check(to_centric_mode(params, topo)).unwrap_or(CentricMode::Geo);
+    let mode = errors.check(to_transition_mode(params)).unwrap_or(TransitionMode::CenterOnly);
+    errors.into_result()?;
+    Ok(ChartOptions { coord_system, pheno_inline: eq == 4, topo, centric, mode })
+  }
+}
+
+/// Longitudes of referenced celestial bodies and the ascendant with sun and moon rise/set times.
+#[utoipa::path(
+  params(InputOptions),
+  responses(
+    (status = 200, description = "Success"),
+    (status = 400, description = "Invalid parameters", body = crate::errors::ErrorResponse),
+  ),
+)]
+#[get("/positions")]
+async fn body_positions(params: Query<InputOptions>) -> Result<impl Responder, CalcErrors> {
+  let options = PositionOptions::from_input(&params)?;
+  let result = run_ephemeris(move || {
+    let date = to_date_object(&params)?;
+    let geo = to_geopos_object(&params)?;
+    let aya: String = params.aya.clone().unwrap_or("tropical".to_string());
+    let eq = options.coord_system as u8;
+    let sidereal: bool = params.sid.unwrap_or(0) > 0 && options.coord_system == CoordinateSystem::Ecliptic; // only applied if ecliptic
+    let centric = options.centric;
+    let def_keys = vec![
+      "su", "mo", "ma", "me", "ju", "ve", "sa", "ur", "ne", "pl", "ra", "ke",
+    ];
+    let key_string: String = params.bodies.clone().unwrap_or("".to_string());
+    let keys = filter_centric_keys(&body_keys_str_to_keys_or(key_string, def_keys), centric);
+    let aya_key = match_ayanamsha_key(aya.as_str());
+    let ayanamsha = get_ayanamsha_value(date.jd, aya.as_str());
+    let aya_offset = if sidereal { ayanamsha } else { 0f64 };
+    let iso_mode: bool = params.iso.clone().unwrap_or(0) > 0;
+    let mode = options.mode;
+    let equatorial = options.coord_system == CoordinateSystem::Equatorial || options.coord_system == CoordinateSystem::RectangularEq;
+    let longitudes = get_body_longitudes(date.jd, geo, centric, equatorial, aya_offset, &to_str_refs(&keys));
+    let valid = longitudes.len() > 0;
+    let sun_rise_sets = calc_transition_sun(date.jd, geo, true, mode).to_value_set(iso_mode);
+    let moon_rise_sets = calc_transition_moon(date.jd, geo, true, mode).to_value_set(iso_mode);
+    let coord_system = build_coord_system_label(eq, centric);
+    let mut result = json!({ "valid": valid, "date": date, "geo": geo, "longitudes": longitudes, "ayanamsha": { "key": aya_key, "value": ayanamsha, "applied": sidereal }, "coordinateSystem": coord_system, "sunRiseSets": sun_rise_sets, "moonRiseSets": moon_rise_sets });
+    // rectangular coordinates are only included with eq=5 or 6
+    if options.coord_system.is_rectangular() {
+      let rectangular = get_bodies_xyz(date.jd, &to_str_refs(&keys), centric, Some(geo), options.coord_system == CoordinateSystem::RectangularEq);
+      result["rectangular"] = json!(rectangular);
+    }
+    Ok(with_local_times(&params, result))
+  }).await?;
+  Ok(Json(result))
+}
+
+
+/// Progress of the ascendant or body longitudes over one or more days at regular intervals.
+#[utoipa::path(
+  params(InputOptions),
+  responses(
+    (status = 200, description = "Success"),
+    (status = 400, description = "Invalid parameters", body = crate::errors::ErrorResponse),
+  ),
+)]
 #[get("/ascendant")]
-async fn ascendant_progress(params: Query<InputOptions>) -> impl Responder {
-  reset_ephemeris_path();
-  let mut micro_interval_millis = 30;
+async fn ascendant_progress(params: Query<InputOptions>) -> Result<impl Responder, CalcErrors> {
+  let options = AscendantOptions::from_input(&params)?;
+  let result = run_ephemeris(move || {
+    let pd = options.per_day;
+    let num_days = options.days;
+    let day_span = num_days as f64;
+    let date = to_date_object(&params)?;
+    let start_jd = date.jd - 0.5;
+    let end_jd = start_jd + day_span;
+    let start = DateInfo::new_from_jd(start_jd);
+    let end = DateInfo::new_from_jd(end_jd);
+    let geo = to_geopos_object(&params)?;
+    let aya: String = params.aya.clone().unwrap_or("tropical".to_string());
+    let sidereal: bool = params.sid.unwrap_or(0) > 0;
+    let aya_key = match_ayanamsha_key(aya.as_str());
 
-  let pd = params.pd.unwrap_or(24);
-  let days_val = params.days.unwrap_or(1);
-  let num_days = if days_val < 1 {
-    1
-  } else if days_val > 366 {
-    366
-  } else {
-    days_val
-  };
-  let day_span = num_days as f64;
-  let date = to_date_object(&params);
-  let start_jd = date.jd - 0.5;
-  let end_jd = start_jd + day_span;
-  let start = DateInfo::new_from_jd(start_jd);
-  let end = DateInfo::new_from_jd(end_jd);
-  let geo = to_geopos_object(&params);
-  let aya: String = params.aya.clone().unwrap_or("tropical".to_string());
-  let sidereal: bool = params.sid.unwrap_or(0) > 0;
-  let aya_key = match_ayanamsha_key(aya.as_str());
-  
-  let ayanamsha = get_ayanamsha_value(date.jd, aya.as_str());
-  let show_aya = !aya_key.contains("tropical") && !sidereal;
-  let aya_offset = if sidereal { ayanamsha } else { 0f64 };
-  let num_items = pd as usize * num_days as usize;
-  let increment = 1f64 / pd as f64;
-  let zero_tolerance = increment / 32.0;
-  let mut current_index = -1;
-  let mut has_bodies = false;
-  let mut result: HashMap<&str, Value> = HashMap::new();
-  let mut mode_key = "none".to_string();
-  let mut valid = false;
-  let mut sun_moon_angle:Option<(f64, bool, u8)> = None;
-  let interval = json!({
-    "time": time_interval_format(increment),
-    "days": increment
-  });
-  if let Some(key_string) = params.bodies.clone() {
-    let keys = body_keys_str_to_keys_or(key_string, vec![]);
-    has_bodies = keys.len() > 0;
-    let eq = params.eq.unwrap_or(0);
-    let topo = params.topo.unwrap_or(0);
-    let mut positions: Vec<HashMap<String,f64>> = Vec::new();
-    if has_bodies {
-      let key_refs = to_str_refs(&keys);
-      let has_sun_and_moon = key_refs.contains(&"su") && key_refs.contains(&"mo");
-      for i in 0..num_items {
-        let ref_jd = start_jd + (increment * i as f64);
-        let body_set = get_body_longitudes_contextual(ref_jd, geo, eq, topo, aya_offset, &key_refs);
-        if current_index < 0 && (ref_jd - date.jd).abs() < zero_tolerance {
-          current_index = i as i32;
-          if has_sun_and_moon {
-            if let Some(moon_lng) = body_set.get("mo") {
-              if let Some(sun_lng) = body_set.get("su") {
-                sun_moon_angle = Some(calc_sun_moon_angle(*moon_lng, *sun_lng));
+    let ayanamsha = get_ayanamsha_value(date.jd, aya.as_str());
+    let show_aya = !aya_key.contains("tropical") && !sidereal;
+    let aya_offset = if sidereal { ayanamsha } else { 0f64 };
+    let num_items = pd as usize * num_days as usize;
+    let increment = 1f64 / pd as f64;
+    let zero_tolerance = increment / 32.0;
+    let mut current_index = -1;
+    let mut has_bodies = false;
+    let mut result: HashMap<&str, Value> = HashMap::new();
+    let mut mode_key = "none".to_string();
+    let mut valid = false;
+    let mut sun_moon_angle:Option<(f64, bool, u8)> = None;
+    let interval = json!({
+      "time": time_interval_format(increment),
+      "days": increment
+    });
+    if let Some(key_string) = params.bodies.clone() {
+      let keys = body_keys_str_to_keys_or(key_string, vec![]);
+      has_bodies = keys.len() > 0;
+      let eq = options.coord_system as u8;
+      let topo = options.topo as u8;
+      let mut positions: Vec<HashMap<String,f64>> = Vec::new();
+      if has_bodies {
+        let key_refs = to_str_refs(&keys);
+        let has_sun_and_moon = key_refs.contains(&"su") && key_refs.contains(&"mo");
+        for i in 0..num_items {
+          let ref_jd = start_jd + (increment * i as f64);
+          let body_set = get_body_longitudes_contextual(ref_jd, geo, eq, topo, aya_offset, &key_refs);
+          if current_index < 0 && (ref_jd - date.jd).abs() < zero_tolerance {
+            current_index = i as i32;
+            if has_sun_and_moon {
+              if let Some(moon_lng) = body_set.get("mo") {
+                if let Some(sun_lng) = body_set.get("su") {
+                  sun_moon_angle = Some(calc_sun_moon_angle(*moon_lng, *sun_lng));
+                }
               }
             }
           }
+          positions.push(body_set);
         }
-        positions.push(body_set);
+        result.insert("values", json!(positions));
+        valid = positions.len() >= pd as usize;
+        mode_key = build_coord_system_label(eq, if topo > 0 { CentricMode::Topo } else { CentricMode::Geo });
       }
-      result.insert("values", json!(positions));
-      valid = positions.len() >= pd as usize;
-      mode_key = build_coord_system_label(eq, topo > 0);
     }
-  }
-  if !has_bodies {
-    let mut items: Vec<f64> = Vec::with_capacity(num_items);
-    for i in 0..num_items {
-      let ref_jd = start_jd + (increment * i as f64);
-      if current_index < 0 && (ref_jd - date.jd).abs() < zero_tolerance {
-        current_index = i as i32;
+    if !has_bodies {
+      let mut items: Vec<f64> = Vec::with_capacity(num_items);
+      for i in 0..num_items {
+        let ref_jd = start_jd + (increment * i as f64);
+        if current_index < 0 && (ref_jd - date.jd).abs() < zero_tolerance {
+          current_index = i as i32;
+        }
+        let asc_val = calc_ascendant(ref_jd, geo);
+        let asc_adjusted = subtract_360(asc_val, aya_offset);
+        items.push(asc_adjusted);
       }
-      let asc_val = calc_ascendant(ref_jd, geo);
-      let asc_adjusted = subtract_360(asc_val, aya_offset);
-      items.push(asc_adjusted);
+      result.insert("values", json!(items));
+      valid = items.len() >= pd as usize;
+      mode_key = "ascendants".to_string();
     }
-    result.insert("values", json!(items));
-    valid = items.len() >= pd as usize;
-    mode_key = "ascendants".to_string();
-  }
-  result.insert("valid", Value::Bool(valid));
-  result.insert("mode", Value::String(mode_key));
-  result.insert("geo", json!(geo));
-  result.insert("date", json!(date));
-  result.insert("start", json!(start));
-  result.insert("end", json!(end));
-  result.insert("interval", json!(interval));
-  result.insert("currentIndex", json!(current_index));
-  if show_aya {
-    result.insert("ayanamsha", json!({ "key": aya_key, "value": ayanamsha, "applied": sidereal }));
-  }
-  let full_mode = params.full.unwrap_or(0) > 0;
-  let show_sun_rise_sets = full_mode || params.ct.unwrap_or(0) > 0;
-  if show_sun_rise_sets {
-    let iso_mode = params.iso.unwrap_or(0) > 0;
-    let mode = TransitionMode::from_u8(params.mode.unwrap_or(3));
-    let sun_transitions_jd = calc_transitions_sun(date.jd, num_days, geo, mode);
-    let sun_transitions: Vec<FlexiValue> = sun_transitions_jd.iter().filter(|item| item.value != 0f64).map(|item| item.as_flexi_value(iso_mode)).collect();
-    if sun_transitions.len() > 0 {
-      result.insert("sunRiseSets", json!(sun_transitions));
+    result.insert("valid", Value::Bool(valid));
+    result.insert("mode", Value::String(mode_key));
+    result.insert("geo", json!(geo));
+    result.insert("date", json!(date));
+    result.insert("start", json!(start));
+    result.insert("end", json!(end));
+    result.insert("interval", json!(interval));
+    result.insert("currentIndex", json!(current_index));
+    if show_aya {
+      result.insert("ayanamsha", json!({ "key": aya_key, "value": ayanamsha, "applied": sidereal }));
+    }
+    let full_mode = params.full.unwrap_or(0) > 0;
+    let show_sun_rise_sets = full_mode || params.ct.unwrap_or(0) > 0;
+    if show_sun_rise_sets {
+      let iso_mode = params.iso.unwrap_or(0) > 0;
+      let sun_transitions_jd = calc_transitions_sun(date.jd, num_days, geo, options.mode);
+      let sun_transitions: Vec<FlexiValue> = sun_transitions_jd.iter().filter(|item| item.value != 0f64).map(|item| item.as_flexi_value(iso_mode)).collect();
+      if sun_transitions.len() > 0 {
+        result.insert("sunRiseSets", json!(sun_transitions));
+      }
     }
-  }
 
-  if has_bodies {
-    if let Some((angle, waxing, phase)) = sun_moon_angle {
-      let mut phases: Vec<MoonPhase> = vec![];
-      micro_interval_millis = 60;
-      if full_mode {
-        // start 8 days ago
-        phases = calc_moon_phases(date.jd - 8.0, geo, 2);
+    if has_bodies {
+      if let Some((angle, waxing, phase)) = sun_moon_angle {
+        let mut phases: Vec<MoonPhase> = vec![];
+        if full_mode {
+          // start 8 days ago
+          phases = calc_moon_phases(date.jd - 8.0, 2, PhaseDivision::Quarters, &TimeZoneRef::Fixed(0));
+        }
+        result.insert("moon", json!({ "sunAngle": angle, "waxing": waxing, "phase": phase, "phases": phases }));
       }
-      result.insert("moon", json!({ "sunAngle": angle, "waxing": waxing, "phase": phase, "phases": phases }));
     }
-  }
-  thread::sleep(time::Duration::from_millis(micro_interval_millis));
-  Json(json!(result))
+    Ok(with_local_times(&params, json!(result)))
+  }).await?;
+  Ok(Json(result))
 }
 
 
+/// Exact geocentric moon phases and lunar months from 8 days before the referenced date.
+///
+/// Phase boundaries are quarters, octants or tithis (div). New and full moons show the lunar distance, supermoons
+/// and blue or black moons. Lunar months are amanta or purnimanta (sys) and named after the sidereal Sun
+#[utoipa::path(
+  params(InputOptions),
+  responses(
+    (status = 200, description = "Success"),
+    (status = 400, description = "Invalid parameters", body = crate::errors::ErrorResponse),
+  ),
+)]
 #[get("/moon-phases")]
-async fn show_moon_phases(params: Query<InputOptions>) -> impl Responder {
-  reset_ephemeris_path();
-  let num = params.num.unwrap_or(3);
-  let num_cycles = if num < 1 { 1 } else if num > 44{ 44 } else { num };
-  let micro_interval_millis = 20 * num_cycles as u64;
-  let date = to_date_object(&params);
-  let geo = to_geopos_object(&params);
-  // start 8 days ago
-  let start_jd = date.jd - 8.0;
-  
-  let phases = calc_moon_phases(start_jd, geo, num_cycles as u8);
-  let valid = phases.len() > 3;
-  thread::sleep(time::Duration::from_millis(micro_interval_millis));
-  Json(json!({ "valid": valid, "date": date, "geo": geo, "phases": phases }))
+async fn show_moon_phases(params: Query<InputOptions>) -> Result<impl Responder, CalcErrors> {
+  let mut errors = input_errors(&params);
+  let num_cycles = errors.check(to_num_in_range("num", params.num, 3, 1, MAX_MOON_PHASE_CYCLES)).unwrap_or(3);
+  let div = params.div.unwrap_or(4);
+  let division = errors.check(PhaseDivision::from_u8(div).ok_or(CalcError::InvalidParam("div".to_string(), format!("{} is not one of 4, 8, 30", div)))).unwrap_or(PhaseDivision::Quarters);
+  let scheme_key = params.sys.clone().unwrap_or("amanta".to_string());
+  let scheme = errors.check(MonthScheme::from_key(scheme_key.as_str()).ok_or(CalcError::InvalidParam("sys".to_string(), format!("{} is not one of amanta, purnimanta", scheme_key)))).unwrap_or(MonthScheme::Amanta);
+  errors.into_result()?;
+  let result = run_ephemeris(move || {
+    let date = to_date_object(&params)?;
+    let tz = to_time_zone(&params)?.unwrap_or(TimeZoneRef::Fixed(0));
+    // start 8 days ago
+    let start_jd = date.jd - 8.0;
+    let end_jd = start_jd + num_cycles as f64 * MEDIAN_LUNAR_MONTH;
+    let aya_key = match_sidereal_ayanamsha_key(params.aya.clone().unwrap_or("true_citra".to_string()).as_str());
+    let ayanamsha = get_ayanamsha_value(date.jd, aya_key.as_str());
+    let phases = calc_moon_phases(start_jd, num_cycles as u8, division, &tz);
+    let months = calc_lunar_months(start_jd, end_jd, scheme, ayanamsha);
+    let valid = phases.len() > 3;
+    Ok(with_local_times(&params, json!({ "valid": valid, "date": date, "div": division.count(), "phases": phases, "months": { "scheme": scheme.to_key(), "ayanamsha": { "key": aya_key, "value": ayanamsha }, "items": months } })))
+  }).await?;
+  Ok(Json(result))
 }
 
-/*
-* Body lng/lat positions, rise/set phases, houses, ayanamsha + optionally special degrees, upgrahas and sunrise/set longitudes and special Indian astrology variant data.
-*/
+/// Body positions, houses, ayanamshas and optional rise/set times, planet stations, aspects and divisional charts.
+///
+/// Body lng/lat positions, rise/set phases, houses, ayanamsha + optionally special degrees, upgrahas and sunrise/set longitudes and special Indian astrology variant data.
+#[utoipa::path(
+  params(InputOptions),
+  responses(
+    (status = 200, description = "Success", body = ChartDataResult),
+    (status = 400, description = "Invalid parameters", body = crate::errors::ErrorResponse),
+  ),
+)]
 #[get("/chart-data")]
-pub async fn chart_data_flexi(params: Query<InputOptions>) -> impl Responder {
-  reset_ephemeris_path();
-  let micro_interval = time::Duration::from_millis(50);
-  let date = to_date_object(&params);
-  let geo = to_geopos_object(&params);
+pub async fn chart_data_flexi(params: Query<InputOptions>) -> Result<impl Responder, CalcErrors> {
+  let result = run_ephemeris(move || {
+    let chart = calc_chart_data(&params)?;
+    Ok(with_local_times(&params, json!(chart)))
+  }).await?;
+  Ok(Json(result))
+}
+
+/// Batch chart data for a JSON array of chart options with the same keys as GET /chart-data, up to 1000 items.
+///
+/// Many charts with the same options as /chart-data in one request. Invalid items are reported in place
+/// with the same error fields as a 400 response, so one bad item does not fail the whole batch
+#[utoipa::path(
+  request_body = Vec<InputOptions>,
+  responses(
+    (status = 200, description = "Success"),
+    (status = 400, description = "Invalid parameters", body = crate::errors::ErrorResponse),
+  ),
+)]
+#[post("/chart-data")]
+pub async fn chart_data_batch(items: Json<Vec<InputOptions>>) -> Result<impl Responder, CalcErrors> {
+  if items.len() > MAX_BATCH_CHARTS {
+    return Err(CalcError::TooManyItems(MAX_BATCH_CHARTS).into());
+  }
+  let results: Vec<Value> = run_ephemeris_batch(items.into_inner(), |params| match calc_chart_data(params) {
+    Ok(chart) => with_local_times(params, json!(chart)),
+    Err(errors) => calc_errors_to_json(&errors),
+  }).await?;
+  let num_valid = results.iter().filter(|item| item["valid"] == Value::Bool(true)).count();
+  Ok(Json(json!({ "valid": num_valid == results.len(), "num": results.len(), "numValid": num_valid, "items": results })))
+}
+
+/**
+ * Validate the chart options and calculate all requested chart data
+ */
+pub fn calc_chart_data(params: &InputOptions) -> Result<ChartDataResult, CalcErrors> {
+  calc_chart_data_jd(params, None)
+}
+
+/**
+ * Chart data with the same options cast for another moment, e.g. a cardinal ingress, instead of the requested date
+ */
+pub fn calc_chart_data_jd(params: &InputOptions, jd_opt: Option<f64>) -> Result<ChartDataResult, CalcErrors> {
+  let options = ChartOptions::from_input(params)?;
+  let date = match jd_opt {
+    Some(jd) => DateInfo::new_from_jd(jd),
+    None => to_date_object(params)?,
+  };
+  let geo = to_geopos_object(params)?;
   let show_rise_sets: bool = params.ct.clone().unwrap_or(0) > 0;
-  let (aya_keys, aya_mode, aya) = to_ayanamsha_keys(&params, "tropical");
+  let (aya_keys, aya_mode, aya) = to_ayanamsha_keys(params, "tropical");
   let hsys_str = params.hsys.clone().unwrap_or("W".to_string());
   let match_all_houses = hsys_str.to_lowercase().as_str() == "all";
   let h_systems: Vec<char> = if match_all_houses {
@@ -236,24 +438,29 @@ pub async fn chart_data_flexi(params: Query<InputOptions>) -> impl Responder {
   } else {
     match_house_systems_chars(hsys_str)
   };
-  let topo: u8 = params.topo.clone().unwrap_or(0);
+  let topo = options.topo;
   let show_sun_period: bool = params.sp.clone().unwrap_or(0) > 0;
-  let eq: u8 = params.eq.clone().unwrap_or(2); // 0 ecliptic, 1 equatorial, 2 both
-  let show_pheno_inline = eq == 4;
+  let eq = options.coord_system;
+  let show_pheno_inline = options.pheno_inline;
   let show_pheno_below = !show_pheno_inline && params.ph.clone().unwrap_or(0) > 0;
   let show_planet_stations = params.retro.clone().unwrap_or(0) > 0;
   let def_keys = vec!["su", "mo", "ma", "me", "ju", "ve", "sa", "ur", "ne", "pl"];
   let key_string: String = params.bodies.clone().unwrap_or("".to_string());
-  let keys = body_keys_str_to_keys_or(key_string, def_keys);
+  let keys = body_keys_str_to_keys_or(key_string.clone(), def_keys);
+  let stars = match_star_keys(&key_string);
   let iso_mode: bool = params.iso.clone().unwrap_or(0) > 0;
   let sidereal: bool = params.sid.unwrap_or(0) > 0;
-  let mode = TransitionMode::from_u8(params.mode.unwrap_or(3));
+  let mode = options.mode;
   let ayanamsha = get_ayanamsha_value(date.jd, aya.as_str());
   let aya_offset = if sidereal { ayanamsha } else { 0f64 };
-  let data = match topo {
-    1 => match eq {
-      0 => get_bodies_ecl_topo(date.jd, &to_str_refs(&keys), geo, aya_offset),
-      1 => get_bodies_eq_topo(date.jd, &to_str_refs(&keys), geo),
+  // heliocentric and barycentric options only apply to body positions and aspects between them
+  let centric = options.centric;
+  let pos_keys = filter_centric_keys(&keys, centric);
+  let mut data = match topo {
+    _ if centric.is_solar_system() => get_bodies_centric(date.jd, &to_str_refs(&pos_keys), centric, eq, aya_offset),
+    TopoMode::Topocentric => match eq {
+      CoordinateSystem::Ecliptic => get_bodies_ecl_topo(date.jd, &to_str_refs(&keys), geo, aya_offset),
+      CoordinateSystem::Equatorial => get_bodies_eq_topo(date.jd, &to_str_refs(&keys), geo),
       _ => get_bodies_dual_topo(
         date.jd,
         to_str_refs(&keys),
@@ -263,18 +470,21 @@ pub async fn chart_data_flexi(params: Query<InputOptions>) -> impl Responder {
       ),
     },
     _ => match eq {
-      0 => get_bodies_ecl_geo(date.jd, &to_str_refs(&keys), aya_offset),
-      1 => get_bodies_eq_geo(date.jd, &to_str_refs(&keys)),
+      CoordinateSystem::Ecliptic => get_bodies_ecl_geo(date.jd, &to_str_refs(&keys), aya_offset),
+      CoordinateSystem::Equatorial => get_bodies_eq_geo(date.jd, &to_str_refs(&keys)),
       _ => get_bodies_dual_geo(date.jd, &to_str_refs(&keys), show_pheno_inline, Some(geo), aya_offset),
     },
   };
+  // fixed stars are appended after the bodies with the same coordinate options
+  let star_positions = calc_stars_pos(date.jd, &stars, topo.is_topo(), Some(geo), aya_offset);
+  data.append(&mut star_positions.clone());
   let pheno_items = if show_pheno_below {
     get_pheno_results(date.jd, to_str_refs(&keys))
   } else {
     vec![]
   };
   let mut topo_variants: Vec<LngLatKey> = Vec::new();
-  if topo == 2 {
+  if topo == TopoMode::Both {
     topo_variants = get_bodies_ecl_topo(date.jd, &to_str_refs(&keys), geo, aya_offset)
       .into_iter()
       .map(|b| b.to_lng_lat_key())
@@ -282,7 +492,7 @@ pub async fn chart_data_flexi(params: Query<InputOptions>) -> impl Responder {
   }
   let valid = data.len() > 0;
   let aya_offset_val = match eq {
-    1 => 0f64,
+    CoordinateSystem::Equatorial => 0f64,
     _ => aya_offset,
   };
   let house = if match_all_houses {
@@ -296,8 +506,11 @@ pub async fn chart_data_flexi(params: Query<InputOptions>) -> impl Responder {
   };
   let rise_set_jds: Vec<KeyNumValueSet> = if show_rise_sets {
     let tr_keys_string = params.trbs.clone().unwrap_or("".to_owned());
-    let tr_keys = if tr_keys_string.len() > 1 { body_keys_str_to_keys_or(tr_keys_string, vec![]) } else { keys.clone() };
-    get_transition_sets(date.jd, to_str_refs(&tr_keys), geo, mode)
+    let tr_keys = if tr_keys_string.len() > 1 { body_keys_str_to_keys_or(tr_keys_string.clone(), vec![]) } else { keys.clone() };
+    let tr_stars = if tr_keys_string.len() > 1 { match_star_keys(&tr_keys_string) } else { stars.clone() };
+    let mut tr_sets = get_transition_sets(date.jd, to_str_refs(&tr_keys), geo, mode);
+    tr_sets.append(&mut get_star_transition_sets(date.jd, &tr_stars, geo, mode));
+    tr_sets
   } else {
     Vec::new()
   };
@@ -306,16 +519,21 @@ pub async fn chart_data_flexi(params: Query<InputOptions>) -> impl Responder {
     .map(|item| item.as_flexi_values(iso_mode))
     .collect();
 
+  // fixed stars have no meaningful rectangular coordinates in AU and are only included with other coordinate systems
   let bodies: FlexiBodyPos = match eq {
-    0 => FlexiBodyPos::Simple(data.clone().iter().map(|b| b.to_body(CoordinateSystem::Ecliptic)).collect()),
-    1 => FlexiBodyPos::Simple(data.clone().iter().map(|b| b.to_body(CoordinateSystem::Equatorial)).collect()),
+    CoordinateSystem::Ecliptic => FlexiBodyPos::Simple(data.clone().iter().map(|b| b.to_body(CoordinateSystem::Ecliptic)).collect()),
+    CoordinateSystem::Equatorial => FlexiBodyPos::Simple(data.clone().iter().map(|b| b.to_body(CoordinateSystem::Equatorial)).collect()),
+    CoordinateSystem::Rectangular | CoordinateSystem::RectangularEq => {
+      let xyz_keys = if centric.is_solar_system() { &pos_keys } else { &keys };
+      FlexiBodyPos::Rectangular(get_bodies_xyz(date.jd, &to_str_refs(xyz_keys), centric, Some(geo), eq == CoordinateSystem::RectangularEq))
+    },
     _ => FlexiBodyPos::Extended(data.clone()),
   };
-  thread::sleep(micro_interval);
-  let pl_keys = vec!["ma", "me", "ju", "ve", "sa", "ur", "ne", "pl"];
+  // the sun and moon are never retrograde
+  let non_station_keys = vec!["su", "mo", "ea"];
   let station_keys: Vec<&str> = keys
     .iter()
-    .filter(|k| pl_keys.contains(&k.as_str()))
+    .filter(|k| !non_station_keys.contains(&k.as_str()))
     .map(|k| k.as_str())
     .collect();
   let planet_stations = if show_planet_stations {
@@ -325,8 +543,35 @@ pub async fn chart_data_flexi(params: Query<InputOptions>) -> impl Responder {
   };
   let sun_positions = if show_sun_period { calc_sun_positions(&rise_set_jds, aya_offset)} else { vec![] };
   let sun_period = if show_sun_period { Some(calc_sun_period(&rise_set_jds, date.jd)) } else { None };
-  
-  Json(json!(ChartDataResult {
+  let aspects = if let Some(asp_str) = params.asp.clone() {
+    let aspect_types = match_aspect_types(asp_str.as_str());
+    // equatorial-only body sets have no ecliptic longitudes
+    let ecl_data = match eq {
+      CoordinateSystem::Equatorial => {
+        let mut ecl_bodies = match topo {
+          _ if centric.is_solar_system() => get_bodies_centric(date.jd, &to_str_refs(&pos_keys), centric, CoordinateSystem::Ecliptic, aya_offset),
+          TopoMode::Topocentric => get_bodies_ecl_topo(date.jd, &to_str_refs(&keys), geo, aya_offset),
+          _ => get_bodies_ecl_geo(date.jd, &to_str_refs(&keys), aya_offset),
+        };
+        ecl_bodies.append(&mut star_positions.clone());
+        ecl_bodies
+      },
+      _ => data.clone(),
+    };
+    let points = if eq == CoordinateSystem::Equatorial { get_house_systems(date.jd, geo, vec!['W'], aya_offset).points } else { house.points };
+    calc_aspects(&ecl_data, Some(points), &aspect_types, params.orb)
+  } else {
+    vec![]
+  };
+  // divisional charts always use sidereal longitudes regardless of sid
+  let vargas = if let Some(varga_str) = params.vargas.clone() {
+    let varga_aya = get_ayanamsha_value(date.jd, match_sidereal_ayanamsha_key(aya.as_str()).as_str());
+    calc_body_vargas(date.jd, &to_str_refs(&keys), geo, topo.is_topo(), varga_aya, &match_varga_nums(varga_str.as_str()))
+  } else {
+    vec![]
+  };
+
+  Ok(ChartDataResult {
     valid,
     date,
     geo,
@@ -339,114 +584,120 @@ pub async fn chart_data_flexi(params: Query<InputOptions>) -> impl Responder {
     planet_stations,
     sun_positions,
     sun_period,
-  }))
+    aspects,
+    vargas,
+  })
 }
 
+/// House cusps and angles for one or more house systems.
+#[utoipa::path(
+  params(InputOptions),
+  responses(
+    (status = 200, description = "Success"),
+    (status = 400, description = "Invalid parameters", body = crate::errors::ErrorResponse),
+  ),
+)]
 #[get("/houses")]
-pub async fn show_house_systems(params: Query<InputOptions>) -> impl Responder {
-  reset_ephemeris_path();
-  let micro_interval = time::Duration::from_millis(50);
-  let date = to_date_object(&params);
-  let aya: String = params.aya.clone().unwrap_or("tropical".to_string());
-  let aya_key = match_ayanamsha_key(&aya);
-  let geo = to_geopos_object(&params);
-  
-  let hsys_str = params.hsys.clone().unwrap_or("W".to_string());
-  let match_all_houses = hsys_str.to_lowercase().as_str() == "all";
-  let h_systems: Vec<char> = if match_all_houses {
-    vec![]
-  } else {
-    match_house_systems_chars(hsys_str)
-  };
-  let aya_offset_val = get_ayanamsha_value(date.jd, &aya_key);
-  let house = if match_all_houses {
-    get_all_house_systems(date.jd, geo, aya_offset_val)
-  } else {
-    get_house_systems(date.jd, geo, h_systems, aya_offset_val)
-  };
-  let ayanamsha = KeyNumIdValue::new(&aya, match_ayanamsha_num(&aya_key), aya_offset_val);
-  let systems = houses_as_key_map();
-  let valid = house.sets.len() > 0;
-  thread::sleep(micro_interval);
-  Json(json!({ "valid": valid, "date": date, "geo": geo, "ayanamsha": ayanamsha, "houseSets": house, "systems": systems }))
+pub async fn show_house_systems(params: Query<InputOptions>) -> Result<impl Responder, CalcErrors> {
+  validate_input(&params)?;
+  let result = run_ephemeris(move || {
+    let date = to_date_object(&params)?;
+    let aya: String = params.aya.clone().unwrap_or("tropical".to_string());
+    let aya_key = match_ayanamsha_key(&aya);
+    let geo = to_geopos_object(&params)?;
+
+    let hsys_str = params.hsys.clone().unwrap_or("W".to_string());
+    let match_all_houses = hsys_str.to_lowercase().as_str() == "all";
+    let h_systems: Vec<char> = if match_all_houses {
+      vec![]
+    } else {
+      match_house_systems_chars(hsys_str)
+    };
+    let aya_offset_val = get_ayanamsha_value(date.jd, &aya_key);
+    let house = if match_all_houses {
+      get_all_house_systems(date.jd, geo, aya_offset_val)
+    } else {
+      get_house_systems(date.jd, geo, h_systems, aya_offset_val)
+    };
+    let ayanamsha = KeyNumIdValue::new(&aya, match_ayanamsha_num(&aya_key), aya_offset_val);
+    let systems = houses_as_key_map();
+    let valid = house.sets.len() > 0;
+    Ok(with_local_times(&params, json!({ "valid": valid, "date": date, "geo": geo, "ayanamsha": ayanamsha, "houseSets": house, "systems": systems })))
+  }).await?;
+  Ok(Json(result))
 }
 
+/// Progress of celestial body positions over a range of days.
+#[utoipa::path(
+  params(InputOptions),
+  responses(
+    (status = 200, description = "Success"),
+    (status = 400, description = "Invalid parameters", body = crate::errors::ErrorResponse),
+  ),
+)]
 #[get("/progress")]
-async fn bodies_progress(params: Query<InputOptions>) -> impl Responder {
-  reset_ephemeris_path();
-  let date = to_date_object(&params);
-  let geo = to_geopos_object(&params);
-  let def_keys = vec![
-    "su", "mo", "ma", "me", "ju", "ve", "sa", "ur", "ne", "pl", "ke",
-  ];
-  let key_string: String = params.bodies.clone().unwrap_or("".to_string());
-  let topo: bool = params.topo.clone().unwrap_or(0) > 0;
-  let cs: u8 = params.eq.clone().unwrap_or(0); // 0 ecliptic, 1 equatorial, 3 horizontal
-  let horizontal_mode = cs == 3;
-  // let eq: bool = cs > 0; // 0 ecliptic, 1 equatorial, 3 horizontal
-  let iso_mode: bool = params.iso.clone().unwrap_or(0) > 0;
-  let days: u16 = params.days.unwrap_or(28);
-  let per_day = params.pd.clone().unwrap_or(1);
-  let day_span = params.dspan.clone().unwrap_or(1);
-  let per_day_f64 = if per_day > 1 && per_day < 24 && day_span < 2 {
-    per_day as f64
-  } else if day_span > 1 && per_day > 0 {
-    per_day as f64 / day_span as f64
-  } else {
-    2f64
-  };
-  let num_samples = (days as f64 * per_day_f64) as u16;
-  let days_spanned = if num_samples > 1000 {
-    (1000f64 / per_day_f64) as u16
-  } else {
-    days
-  };
-  let micro_interval = time::Duration::from_millis(20 + (num_samples / 4) as u64);
-  let keys = body_keys_str_to_keys_or(key_string, def_keys);
-  let geo_opt = if topo || horizontal_mode { Some(geo) } else { None };
-  let (aya_keys, aya_mode, aya) = to_ayanamsha_keys(&params, "");
-  let sidereal: bool = params.sid.unwrap_or(0) > 0;
-  let ayanamsha = get_ayanamsha_value(date.jd, aya.as_str());
-  let aya_offset = if sidereal { ayanamsha } else { 0f64 };
-  let ayanamshas = match aya_mode.as_str() {
-    "all" => get_all_ayanamsha_values(date.jd),
-    _ => get_ayanamsha_values(date.jd, to_str_refs(&aya_keys)),
-  };
-  let data = calc_bodies_positions_jd(
-    date.jd,
-    &to_str_refs(&keys),
-    days_spanned,
-    per_day_f64,
-    geo_opt,
-    cs,
-    iso_mode,
-    aya_offset,
-  );
-  let frequency = if per_day_f64 < 1f64 {
-    format!("{} days", day_span)
-  } else {
-    format!("{} per day", per_day_f64)
-  };
-  let coord_system = build_coord_system_label(cs, topo);
-  thread::sleep(micro_interval);
-  Json(json!(
-    json!({ "date": date, "geo": geo, "items": data, "num_samples": num_samples, "days": days, "frequency": frequency, "coordinateSystem": coord_system, "ayanamshas": ayanamshas })
-  ))
+async fn bodies_progress(params: Query<InputOptions>) -> Result<impl Responder, CalcErrors> {
+  let options = ProgressOptions::from_input(&params)?;
+  let result = run_ephemeris(move || {
+    let date = to_date_object(&params)?;
+    let geo = to_geopos_object(&params)?;
+    let def_keys = vec![
+      "su", "mo", "ma", "me", "ju", "ve", "sa", "ur", "ne", "pl", "ke",
+    ];
+    let key_string: String = params.bodies.clone().unwrap_or("".to_string());
+    let topo = options.centric == CentricMode::Topo;
+    let cs = options.coord_system as u8;
+    let horizontal_mode = options.coord_system == CoordinateSystem::Horizontal;
+    let iso_mode: bool = params.iso.clone().unwrap_or(0) > 0;
+    let days = options.days;
+    let per_day_f64 = options.per_day;
+    let day_span = options.day_span;
+    let num_samples = (days as f64 * per_day_f64) as u16;
+    let keys = filter_centric_keys(&body_keys_str_to_keys_or(key_string, def_keys), options.centric);
+    let geo_opt = if topo || horizontal_mode { Some(geo) } else { None };
+    let (aya_keys, aya_mode, aya) = to_ayanamsha_keys(&params, "");
+    let sidereal: bool = params.sid.unwrap_or(0) > 0;
+    let ayanamsha = get_ayanamsha_value(date.jd, aya.as_str());
+    let aya_offset = if sidereal { ayanamsha } else { 0f64 };
+    let ayanamshas = match aya_mode.as_str() {
+      "all" => get_all_ayanamsha_values(date.jd),
+      _ => get_ayanamsha_values(date.jd, to_str_refs(&aya_keys)),
+    };
+    let data = calc_bodies_positions_jd(
+      date.jd,
+      &to_str_refs(&keys),
+      days,
+      per_day_f64,
+      geo_opt,
+      options.centric,
+      cs,
+      iso_mode,
+      aya_offset,
+    );
+    let frequency = if per_day_f64 < 1f64 {
+      format!("{} days", day_span)
+    } else {
+      format!("{} per day", per_day_f64)
+    };
+    let coord_system = build_coord_system_label(cs, options.centric);
+    Ok(with_local_times(&params, json!(
+      json!({ "date": date, "geo": geo, "items": data, "num_samples": num_samples, "days": days, "frequency": frequency, "coordinateSystem": coord_system, "ayanamshas": ayanamshas })
+    )))
+  }).await?;
+  Ok(Json(result))
 }
 
 /**
- * Build simple coordinate system / topocentrice key for the API response
+ * Build simple coordinate system / centric mode key for the API response, e.g. ecliptic/heliocentric
  */
-fn build_coord_system_label(cs: u8, topo: bool) -> String {
+fn build_coord_system_label(cs: u8, centric: CentricMode) -> String {
   let eq_label = match cs {
     1 => "equatorial",
     2 => "ecliptic,equatorial",
     3 => "horizontal",
+    5 => "ecliptic-xyz",
+    6 => "equatorial-xyz",
     _ => "ecliptic",
   };
-  let topo_label = match topo {
-    true => "topocentric",
-    _ => "geocentric",
-  };
-  format!("{}/{}", eq_label, topo_label)
+  format!("{}/{}", eq_label, centric.to_key())
 }
@@ -0,0 +1,68 @@
+use serde_json::json;
+use actix_web::{get, Responder, web::{Query, Json}};
+use crate::{query_params::*, ephemeris::run_ephemeris};
+use crate::calc::{
+  core::{calc_body_jd_geo, calc_body_jd_topo, get_ayanamsha_value},
+  dashas::{calc_dashas, match_current_periods, DashaSystem, MAX_DASHA_DEPTH},
+  errors::CalcErrors,
+  models::{date_info::DateInfo, general::TopoMode},
+  settings::{ayanamshas::match_ayanamsha_key, dashas::DEFAULT_DASHA_YEAR_LENGTH},
+};
+
+const MIN_DASHA_YEAR_LENGTH: f64 = 300f64;
+const MAX_DASHA_YEAR_LENGTH: f64 = 400f64;
+
+/*
+* Typed /dashas options. yl is the length of a dasha year in days
+*/
+struct DashaOptions {
+  year_length: f64,
+  depth: u8,
+  topo: TopoMode,
+}
+
+impl DashaOptions {
+  fn from_input(params: &InputOptions) -> Result<Self, CalcErrors> {
+    let mut errors = input_errors(params);
+    let year_length = errors.check(to_num_in_range("yl", params.yl, DEFAULT_DASHA_YEAR_LENGTH, MIN_DASHA_YEAR_LENGTH, MAX_DASHA_YEAR_LENGTH)).unwrap_or(DEFAULT_DASHA_YEAR_LENGTH);
+    let depth = errors.check(to_num_in_range("depth", params.depth, 2, 1, MAX_DASHA_DEPTH)).unwrap_or(2);
+    let topo = errors.check(to_topo_mode(params, false)).unwrap_or(TopoMode::Geocentric);
+    errors.into_result()?;
+    Ok(DashaOptions { year_length, depth, topo })
+  }
+}
+
+/// Vimshottari, Yogini or Ashtottari dasha periods from the birth Moon's nakshatra.
+///
+/// Dashas are always derived from the sidereal Moon, by default with the True Citra ayanamsha
+#[utoipa::path(
+  params(InputOptions),
+  responses(
+    (status = 200, description = "Success"),
+    (status = 400, description = "Invalid parameters", body = crate::errors::ErrorResponse),
+  ),
+)]
+#[get("/dashas")]
+async fn list_dashas(params: Query<InputOptions>) -> Result<impl Responder, CalcErrors> {
+  let options = DashaOptions::from_input(&params)?;
+  let result = run_ephemeris(move || {
+    let date = to_date_object(&params)?;
+    let ref_date = if params.has_date_2() { to_date_object_2(&params)? } else { DateInfo::now() };
+    let topo: bool = options.topo == TopoMode::Topocentric && params.loc.is_some();
+    let iso_mode: bool = params.iso.clone().unwrap_or(0) > 0;
+    let system = DashaSystem::from_key(params.sys.clone().unwrap_or("vimshottari".to_string()).as_str());
+    let aya: String = params.aya.clone().unwrap_or("true_citra".to_string());
+    let aya_key = match_ayanamsha_key(aya.as_str());
+    let ayanamsha = get_ayanamsha_value(date.jd, aya.as_str());
+    let moon = if topo {
+      calc_body_jd_topo(date.jd, "mo", to_geopos_object(&params)?, ayanamsha)
+    } else {
+      calc_body_jd_geo(date.jd, "mo", ayanamsha)
+    };
+    let dasha_set = calc_dashas(date.jd, moon.lng, system, options.year_length, options.depth, iso_mode);
+    let current = match_current_periods(&dasha_set.periods, ref_date.jd);
+    let valid = dasha_set.periods.len() > 0;
+    Ok(with_local_times(&params, json!({ "valid": valid, "date": date, "refDate": ref_date, "ayanamsha": { "key": aya_key, "value": ayanamsha }, "topo": topo, "current": current, "dashas": dasha_set })))
+  }).await?;
+  Ok(Json(result))
+}
@@ -1,36 +1,78 @@
-use serde_json::*;
+use serde_json::json;
 use crate::calc::rise_set_phases::TransitionMode;
 
-use super::super::calc::{models::{date_info::*, geo_pos::*},rise_set_phases::{start_jd_geo, to_sun_rise_sets}, utils::{converters::*, validators::*}};
+use super::super::calc::{errors::CalcErrors, models::date_info::*, rise_set_phases::{start_jd_geo_tz, to_sun_rise_sets}, utils::validators::*};
 use actix_web::{get, Responder,web::{Query, Json, Path}};
-use super::super::query_params::*;
+use super::super::{query_params::*, ephemeris::run_ephemeris};
 
+/// Julian day, unix timestamp and UTC date-time string of the current time.
+#[utoipa::path(
+  responses(
+    (status = 200, description = "Success", body = DateInfo),
+  ),
+)]
+#[get("/jd")]
+async fn date_now() -> impl Responder {
+  Json(json!(DateInfo::now()))
+}
+
+/// Julian day, unix timestamp and UTC date-time string of an ISO date or julian day.
+#[utoipa::path(
+  params(("dateref" = String, Path, description = "ISO date string with optional time or julian day")),
+  responses(
+    (status = 200, description = "Success", body = DateInfo),
+    (status = 400, description = "Invalid parameters", body = crate::errors::ErrorResponse),
+  ),
+)]
 #[get("/jd/{dateref}")]
-async fn date_info(dateref: Path<String>) -> impl Responder {
+async fn date_info(dateref: Path<String>) -> Result<impl Responder, CalcErrors> {
   let date_str = dateref.as_str();
-  let info = if is_decimal_str(date_str) { DateInfo::new_from_jd(date_str.parse::<f64>().unwrap()) } else { DateInfo::new(date_str) };
-  Json(json!(info))
+  let info = if is_decimal_str(date_str) { DateInfo::try_from_jd(date_str.parse::<f64>().unwrap_or(0f64))? } else { DateInfo::new(date_str)? };
+  Ok(Json(json!(info)))
 }
 
+/// Date and time variants with current, previous and next sunrise and sunset.
+#[utoipa::path(
+  params(InputOptions),
+  responses(
+    (status = 200, description = "Success"),
+    (status = 400, description = "Invalid parameters", body = crate::errors::ErrorResponse),
+  ),
+)]
 #[get("/date")]
-async fn date_info_geo(params: Query<InputOptions>) -> impl Responder {
-  let date = to_date_object(&params);
-  let loc: String = params.loc.clone().unwrap_or("0,0".to_string());
-  let geo = if let Some(geo_pos) = loc_string_to_geo(loc.as_str()) { geo_pos } else { GeoPos::zero() };
-  let tz_secs =  params.tzs.clone().unwrap_or(0i32);
-  let iso_mode: bool = params.iso.clone().unwrap_or(0) > 0;
-  let mode = TransitionMode::from_u8(params.mode.unwrap_or(3));
-  let offset_secs = if tz_secs != 0i32 { Some(tz_secs) } else { None };
-  let (prev, base, next, calc_offset_secs) = to_sun_rise_sets(date.jd, geo, offset_secs, iso_mode, mode);
-  Json(json!({ "date": date, "offsetSecs": calc_offset_secs, "sun": { "prev": prev, "current": base, "next": next } }))
+async fn date_info_geo(params: Query<InputOptions>) -> Result<impl Responder, CalcErrors> {
+  let mut errors = input_errors(&params);
+  let mode = errors.check(to_transition_mode(&params)).unwrap_or(TransitionMode::CenterOnly);
+  errors.into_result()?;
+  let result = run_ephemeris(move || {
+    let date = to_date_object(&params)?;
+    let geo = to_geopos_object(&params)?;
+    let tz_opt = to_time_zone(&params)?;
+    let iso_mode: bool = params.iso.clone().unwrap_or(0) > 0;
+    // IANA zones resolve to the offset in force on the date, incl. daylight saving time
+    let offset_secs = tz_opt.map(|tz| tz.offset_secs_jd(date.jd));
+    let tz_name = tz_opt.map(|tz| tz.name());
+    let (prev, base, next, calc_offset_secs) = to_sun_rise_sets(date.jd, geo, offset_secs, iso_mode, mode);
+    Ok(with_local_times(&params, json!({ "date": date, "offsetSecs": calc_offset_secs, "timeZone": tz_name, "sun": { "prev": prev, "current": base, "next": next } })))
+  }).await?;
+  Ok(Json(result))
 }
 
+/// Start of the local day by time zone offset or longitude.
+#[utoipa::path(
+  params(InputOptions),
+  responses(
+    (status = 200, description = "Success"),
+    (status = 400, description = "Invalid parameters", body = crate::errors::ErrorResponse),
+  ),
+)]
 #[get("/test-geo-start")]
-async fn test_geo_start(params: Query<InputOptions>) -> impl Responder {
-  let loc: String = params.loc.clone().unwrap_or("0,0".to_string());
-  let geo = if let Some(geo_pos) = loc_string_to_geo(loc.as_str()) { geo_pos } else { GeoPos::zero() };
-  let date = to_date_object(&params);
-  let start_jd = start_jd_geo(date.jd, geo.lng);
+async fn test_geo_start(params: Query<InputOptions>) -> Result<impl Responder, CalcErrors> {
+  validate_input(&params)?;
+  let geo = to_geopos_object(&params)?;
+  let date = to_date_object(&params)?;
+  let offset_secs = to_time_zone(&params)?.map(|tz| tz.offset_secs_jd(date.jd));
+  let start_jd = start_jd_geo_tz(date.jd, geo.lng, offset_secs);
   let start = DateInfo::new_from_jd(start_jd);
-  Json(json!({ "date": date, "dayStart": start, "lng": geo.lng, "lat": geo.lat }))
+  Ok(Json(with_local_times(&params, json!({ "date": date, "dayStart": start, "lng": geo.lng, "lat": geo.lat }))))
 }
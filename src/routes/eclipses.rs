@@ -0,0 +1,64 @@
+use serde_json::json;
+use actix_web::{get, Responder, web::{Query, Json}};
+use crate::{query_params::*, ephemeris::run_ephemeris};
+use crate::calc::{
+  eclipses::calc_eclipses,
+  errors::{CalcError, CalcErrors},
+  models::date_info::DateInfo,
+  utils::converters::*,
+};
+
+const DEFAULT_ECLIPSE_DAYS: f64 = 366f64;
+const MAX_ECLIPSE_DAYS: f64 = 36525f64;
+
+/*
+* Typed /eclipses options. dt2 defaults to one year after dt and must be later than dt and within MAX_ECLIPSE_DAYS
+*/
+struct EclipseOptions {
+  start: DateInfo,
+  end: DateInfo,
+}
+
+impl EclipseOptions {
+  fn from_input(params: &InputOptions) -> Result<Self, CalcErrors> {
+    let mut errors = input_errors(params);
+    let start = to_date_object(params).unwrap_or(DateInfo::now());
+    let end = if params.has_date_2() { to_date_object_2(params).unwrap_or(start.clone()) } else { DateInfo::new_from_jd(start.jd + DEFAULT_ECLIPSE_DAYS) };
+    if end.jd <= start.jd || end.jd - start.jd > MAX_ECLIPSE_DAYS {
+      errors.add(CalcError::InvalidParam("dt2".to_string(), format!("the end date must be later than dt and within {} days", MAX_ECLIPSE_DAYS)));
+    }
+    errors.into_result()?;
+    Ok(EclipseOptions { start, end })
+  }
+}
+
+/// Solar and lunar eclipses with type, maximum, contact times and magnitude.
+///
+/// Solar eclipses are selected via the body key su and lunar eclipses via mo. Both are shown by default.
+/// Local circumstances are only calculated if a location is given
+#[utoipa::path(
+  params(InputOptions),
+  responses(
+    (status = 200, description = "Success"),
+    (status = 400, description = "Invalid parameters", body = crate::errors::ErrorResponse),
+  ),
+)]
+#[get("/eclipses")]
+async fn list_eclipses(params: Query<InputOptions>) -> Result<impl Responder, CalcErrors> {
+  let options = EclipseOptions::from_input(&params)?;
+  let result = run_ephemeris(move || {
+    let date = options.start;
+    let end_jd = options.end.jd;
+    let key_string: String = params.bodies.clone().unwrap_or("".to_string());
+    let keys = body_keys_str_to_keys_or(key_string, vec!["su", "mo"]);
+    let solar = keys.contains(&"su".to_string());
+    let lunar = keys.contains(&"mo".to_string());
+    let iso_mode: bool = params.iso.clone().unwrap_or(0) > 0;
+    let geo_opt = if params.loc.is_some() { Some(to_geopos_object(&params)?) } else { None };
+    let items = calc_eclipses(date.jd, end_jd, solar, lunar, geo_opt, iso_mode);
+    let valid = solar || lunar;
+    let end = options.end;
+    Ok(with_local_times(&params, json!({ "valid": valid, "start": date, "end": end, "geo": geo_opt, "items": items })))
+  }).await?;
+  Ok(Json(result))
+}
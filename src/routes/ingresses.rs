@@ -0,0 +1,74 @@
+use serde_json::json;
+use actix_web::{get, Responder, web::{Query, Json}};
+use crate::{query_params::*, ephemeris::run_ephemeris};
+use crate::calc::{
+  core::get_ayanamsha_value,
+  errors::{CalcError, CalcErrors},
+  ingresses::{calc_ingresses, IngressMode},
+  models::date_info::DateInfo,
+  settings::ayanamshas::match_ayanamsha_key,
+  transits::AyanamshaRange,
+  utils::converters::*,
+};
+
+const DEFAULT_INGRESS_DAYS: f64 = 28f64;
+const MAX_INGRESS_DAYS: f64 = 3660f64;
+
+/*
+* Typed /ingresses options. dt2 defaults to four weeks after dt and must be later than dt and within MAX_INGRESS_DAYS.
+* nak sets the ingress level
+*/
+struct IngressOptions {
+  start: DateInfo,
+  end: DateInfo,
+  level: u8,
+}
+
+impl IngressOptions {
+  fn from_input(params: &InputOptions) -> Result<Self, CalcErrors> {
+    let mut errors = input_errors(params);
+    let start = to_date_object(params).unwrap_or(DateInfo::now());
+    let end = if params.has_date_2() { to_date_object_2(params).unwrap_or(start.clone()) } else { DateInfo::new_from_jd(start.jd + DEFAULT_INGRESS_DAYS) };
+    if end.jd <= start.jd || end.jd - start.jd > MAX_INGRESS_DAYS {
+      errors.add(CalcError::InvalidParam("dt2".to_string(), format!("the end date must be later than dt and within {} days", MAX_INGRESS_DAYS)));
+    }
+    let level = errors.check(to_num_in_range("nak", params.nak, 0, 0, 2)).unwrap_or(0);
+    errors.into_result()?;
+    Ok(IngressOptions { start, end, level })
+  }
+}
+
+/// Exact times when celestial bodies enter a new sign and optionally a new nakshatra or pada.
+#[utoipa::path(
+  params(InputOptions),
+  responses(
+    (status = 200, description = "Success"),
+    (status = 400, description = "Invalid parameters", body = crate::errors::ErrorResponse),
+  ),
+)]
+#[get("/ingresses")]
+async fn list_ingresses(params: Query<InputOptions>) -> Result<impl Responder, CalcErrors> {
+  let options = IngressOptions::from_input(&params)?;
+  let result = run_ephemeris(move || {
+    let date = options.start;
+    let end_jd = options.end.jd;
+    let def_keys = vec!["su", "mo", "me", "ve", "ma", "ju", "sa"];
+    let key_string: String = params.bodies.clone().unwrap_or("".to_string());
+    let keys = body_keys_str_to_keys_or(key_string, def_keys);
+    let iso_mode: bool = params.iso.clone().unwrap_or(0) > 0;
+    let aya: String = params.aya.clone().unwrap_or("tropical".to_string());
+    let sidereal: bool = params.sid.unwrap_or(0) > 0;
+    let aya_key = match_ayanamsha_key(aya.as_str());
+    let aya_range = if sidereal {
+      AyanamshaRange::new(date.jd, get_ayanamsha_value(date.jd, aya.as_str()), end_jd, get_ayanamsha_value(end_jd, aya.as_str()))
+    } else {
+      AyanamshaRange::tropical()
+    };
+    let modes = IngressMode::from_level(options.level);
+    let items = calc_ingresses(&to_str_refs(&keys), &modes, date.jd, end_jd, &aya_range, iso_mode);
+    let valid = keys.len() > 0;
+    let end = options.end;
+    Ok(with_local_times(&params, json!({ "valid": valid, "start": date, "end": end, "ayanamsha": { "key": aya_key, "value": aya_range.value(date.jd), "applied": sidereal }, "items": items })))
+  }).await?;
+  Ok(Json(result))
+}
@@ -0,0 +1,40 @@
+use serde_json::json;
+use actix_web::{get, Responder, web::{Query, Json}};
+use crate::{query_params::*, ephemeris::run_ephemeris};
+use crate::calc::{
+  errors::{CalcError, CalcErrors},
+  lunar_events::{calc_lunar_events, MAX_LUNAR_EVENT_DAYS},
+  models::date_info::DateInfo,
+};
+
+const DEFAULT_LUNAR_EVENT_DAYS: f64 = 366f64;
+
+/// Lunar perigees and apogees, node crossings, declination extremes and standstills between two dates.
+///
+/// Apsides include the distance in km and declination extremes the declination. Major and minor standstills
+/// are dated by the mean ascending node at 0º Aries or 0º Libra
+#[utoipa::path(
+  params(InputOptions),
+  responses(
+    (status = 200, description = "Success"),
+    (status = 400, description = "Invalid parameters", body = crate::errors::ErrorResponse),
+  ),
+)]
+#[get("/lunar-events")]
+async fn list_lunar_events(params: Query<InputOptions>) -> Result<impl Responder, CalcErrors> {
+  let mut errors = input_errors(&params);
+  if let (Ok(start), Ok(end)) = (to_date_object(&params), to_date_object_2(&params)) {
+    if params.has_date_2() && (end.jd <= start.jd || end.jd - start.jd > MAX_LUNAR_EVENT_DAYS) {
+      errors.add(CalcError::InvalidParam("dt2".to_string(), format!("the end date must be later than dt and within {} days", MAX_LUNAR_EVENT_DAYS)));
+    }
+  }
+  errors.into_result()?;
+  let result = run_ephemeris(move || {
+    let date = to_date_object(&params)?;
+    let end_date = if params.has_date_2() { to_date_object_2(&params)? } else { DateInfo::new_from_jd(date.jd + DEFAULT_LUNAR_EVENT_DAYS) };
+    let iso_mode: bool = params.iso.clone().unwrap_or(0) > 0;
+    let items = calc_lunar_events(date.jd, end_date.jd, iso_mode);
+    Ok(with_local_times(&params, json!({ "start": date, "end": end_date, "items": items })))
+  }).await?;
+  Ok(Json(result))
+}
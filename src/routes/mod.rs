@@ -2,4 +2,15 @@ pub mod chart_data;
 pub mod rise_set_times;
 pub mod datetime;
 pub mod planet_stations;
-pub mod appendix;
\ No newline at end of file
+pub mod appendix;
+pub mod aspects;
+pub mod transits;
+pub mod ingresses;
+pub mod eclipses;
+pub mod stars;
+pub mod dashas;
+pub mod vargas;
+pub mod panchanga;
+pub mod progressions;
+pub mod lunar_events;
+pub mod seasons;
\ No newline at end of file
@@ -0,0 +1,41 @@
+use serde_json::json;
+use actix_web::{get, Responder, web::{Query, Json}};
+use crate::{query_params::*, ephemeris::run_ephemeris};
+use crate::calc::{
+  core::get_ayanamsha_value,
+  errors::CalcErrors,
+  panchanga::calc_panchanga_days,
+  rise_set_phases::TransitionMode,
+  settings::ayanamshas::match_sidereal_ayanamsha_key,
+};
+
+const MAX_PANCHANGA_DAYS: u16 = 62;
+
+/// Tithi, nakshatra, yoga, karana and weekday (vara) of the Vedic day with exact start and end times.
+///
+/// Vedic days run from sunrise to sunrise, so the first day may start on the previous calendar day
+#[utoipa::path(
+  params(InputOptions),
+  responses(
+    (status = 200, description = "Success"),
+    (status = 400, description = "Invalid parameters", body = crate::errors::ErrorResponse),
+  ),
+)]
+#[get("/panchanga")]
+async fn show_panchanga(params: Query<InputOptions>) -> Result<impl Responder, CalcErrors> {
+  let mut errors = input_errors(&params);
+  let num_days = errors.check(to_num_in_range("days", params.days, 1, 1, MAX_PANCHANGA_DAYS)).unwrap_or(1);
+  let mode = errors.check(to_transition_mode(&params)).unwrap_or(TransitionMode::CenterOnly);
+  errors.into_result()?;
+  let result = run_ephemeris(move || {
+    let date = to_date_object(&params)?;
+    let geo = to_geopos_object(&params)?;
+    let iso_mode: bool = params.iso.clone().unwrap_or(0) > 0;
+    let aya_key = match_sidereal_ayanamsha_key(params.aya.clone().unwrap_or("true_citra".to_string()).as_str());
+    let ayanamsha = get_ayanamsha_value(date.jd, aya_key.as_str());
+    let days = calc_panchanga_days(date.jd, geo, num_days, ayanamsha, mode, iso_mode);
+    let valid = days.len() > 0;
+    Ok(with_local_times(&params, json!({ "valid": valid, "date": date, "geo": geo, "ayanamsha": { "key": aya_key, "value": ayanamsha }, "days": days })))
+  }).await?;
+  Ok(Json(result))
+}
@@ -1,22 +1,79 @@
-use std::{thread, time};
-use serde_json::*;
-use super::super::calc::{ dates::current_datetime_string, models::date_info::*, planet_stations::{match_all_planet_stations_range, BodySpeedSet}, utils::converters::*};
+use serde_json::json;
+use super::super::calc::{ errors::{CalcError, CalcErrors}, models::date_info::*, planet_stations::{calc_all_retrograde_cycles, match_all_planet_stations_range, BodySpeedSet, RetrogradeCycleSet, MAX_STATION_DAYS}, utils::converters::*};
 use actix_web::{get, Responder,web::{Query, Json}};
-use super::super::{query_params::*, reset_ephemeris_path};
+use super::super::{query_params::*, ephemeris::run_ephemeris};
 
+/// Retrograde and peak stations of the planets between two dates.
+///
+/// Stations of any body are calculated from its longitude speed. Without a later end date the stations around the start date are shown
+#[utoipa::path(
+  params(InputOptions),
+  responses(
+    (status = 200, description = "Success"),
+    (status = 400, description = "Invalid parameters", body = crate::errors::ErrorResponse),
+  ),
+)]
 #[get("/planet-stations")]
-async fn planet_stations_progress(params: Query<InputOptions>) -> impl Responder {
-  reset_ephemeris_path();
-  let micro_interval = time::Duration::from_millis(30);  
-  let date = to_date_object(&params);
-  let def_keys = vec!["me", "ve", "ma", "ju", "sa", "ur", "ne", "pl"];
-  let key_string: String = params.bodies.clone().unwrap_or("".to_string());
-  let keys = body_keys_str_to_keys_or(key_string, def_keys);
-  let dateref_end: String = params.dt2.clone().unwrap_or(current_datetime_string());
-  let iso_mode: bool = params.iso.clone().unwrap_or(0) > 0;
-  let end_date = DateInfo::new(dateref_end.to_string().as_str());
-  let items: Vec<BodySpeedSet> = match_all_planet_stations_range(date.jd, end_date.jd, to_str_refs(&keys), iso_mode);
-  let valid = items.len() > 0;
-  thread::sleep(micro_interval);
-  Json(json!({ "valid": valid, "start": date,  "end": end_date, "items": items }))
-}
\ No newline at end of file
+async fn planet_stations_progress(params: Query<InputOptions>) -> Result<impl Responder, CalcErrors> {
+  let mut errors = input_errors(&params);
+  if let (true, Ok(start), Ok(end)) = (params.has_date_2(), to_date_object(&params), to_date_object_2(&params)) {
+    if end.jd - start.jd > MAX_STATION_DAYS {
+      errors.add(CalcError::InvalidParam("dt2".to_string(), format!("the range from dt to dt2 may not exceed {} days", MAX_STATION_DAYS)));
+    }
+  }
+  errors.into_result()?;
+  let result = run_ephemeris(move || {
+    let date = to_date_object(&params)?;
+    let def_keys = vec!["me", "ve", "ma", "ju", "sa", "ur", "ne", "pl"];
+    let key_string: String = params.bodies.clone().unwrap_or("".to_string());
+    let keys = body_keys_str_to_keys_or(key_string, def_keys);
+    let iso_mode: bool = params.iso.clone().unwrap_or(0) > 0;
+    // without dt2 the range ends now, unless that is too long, when the stations around dt are shown
+    let end_date = match params.has_date_2() {
+      true => to_date_object_2(&params)?,
+      false => {
+        let now = DateInfo::now();
+        if now.jd - date.jd > MAX_STATION_DAYS { date.clone() } else { now }
+      },
+    };
+    let items: Vec<BodySpeedSet> = match_all_planet_stations_range(date.jd, end_date.jd, to_str_refs(&keys), iso_mode);
+    let valid = items.len() > 0;
+    Ok(with_local_times(&params, json!({ "valid": valid, "start": date,  "end": end_date, "items": items })))
+  }).await?;
+  Ok(Json(result))
+}
+/// Complete retrograde cycles of the planets with retrograde stations between two dates.
+///
+/// Each cycle has the shadow entry, retrograde station, peak retrograde speed, direct station and shadow exit,
+/// the longitudes of the retrograde loop and the total duration in days. Without a later end date one year from dt is covered
+#[utoipa::path(
+  params(InputOptions),
+  responses(
+    (status = 200, description = "Success"),
+    (status = 400, description = "Invalid parameters", body = crate::errors::ErrorResponse),
+  ),
+)]
+#[get("/retrograde-cycles")]
+async fn list_retrograde_cycles(params: Query<InputOptions>) -> Result<impl Responder, CalcErrors> {
+  let mut errors = input_errors(&params);
+  if let (true, Ok(start), Ok(end)) = (params.has_date_2(), to_date_object(&params), to_date_object_2(&params)) {
+    if end.jd - start.jd > MAX_STATION_DAYS {
+      errors.add(CalcError::InvalidParam("dt2".to_string(), format!("the range from dt to dt2 may not exceed {} days", MAX_STATION_DAYS)));
+    }
+  }
+  errors.into_result()?;
+  let result = run_ephemeris(move || {
+    let date = to_date_object(&params)?;
+    let def_keys = vec!["me", "ve", "ma", "ju", "sa", "ur", "ne", "pl"];
+    let key_string: String = params.bodies.clone().unwrap_or("".to_string());
+    let keys = body_keys_str_to_keys_or(key_string, def_keys);
+    let iso_mode: bool = params.iso.clone().unwrap_or(0) > 0;
+    let end_date = match to_date_object_2(&params) {
+      Ok(end) if params.has_date_2() && end.jd > date.jd => end,
+      _ => DateInfo::new_from_jd(date.jd + 366f64),
+    };
+    let items: Vec<RetrogradeCycleSet> = calc_all_retrograde_cycles(date.jd, end_date.jd, to_str_refs(&keys), iso_mode);
+    Ok(with_local_times(&params, json!({ "start": date, "end": end_date, "items": items })))
+  }).await?;
+  Ok(Json(result))
+}
@@ -0,0 +1,132 @@
+use serde_json::json;
+use actix_web::{get, Responder, web::{Query, Json}};
+use crate::{query_params::*, ephemeris::run_ephemeris};
+use crate::calc::{
+  core::get_ayanamsha_value,
+  errors::{CalcError, CalcErrors},
+  models::{date_info::DateInfo, houses::match_house_systems_chars},
+  progressions::*,
+  settings::ayanamshas::match_ayanamsha_key,
+  utils::converters::*,
+};
+
+/*
+* Typed /progressions and /directions options. The target date (dt2) defaults to now and must fall within
+* MAX_PROGRESSION_YEARS after the birth date (dt)
+*/
+struct PredictiveOptions {
+  natal: DateInfo,
+  target: DateInfo,
+}
+
+impl PredictiveOptions {
+  fn from_input(params: &InputOptions) -> Result<Self, CalcErrors> {
+    let mut errors = input_errors(params);
+    let options = PredictiveOptions::check_dates(params, &mut errors);
+    errors.into_result()?;
+    Ok(options)
+  }
+
+  fn check_dates(params: &InputOptions, errors: &mut CalcErrors) -> Self {
+    let natal = to_date_object(params).unwrap_or(DateInfo::now());
+    let target = if params.has_date_2() { to_date_object_2(params).unwrap_or(DateInfo::now()) } else { DateInfo::now() };
+    let years = (target.jd - natal.jd) / TROPICAL_YEAR_DAYS;
+    if years < 0f64 || years > MAX_PROGRESSION_YEARS {
+      errors.add(CalcError::InvalidParam("dt2".to_string(), format!("the target date must be within {} years after the birth date", MAX_PROGRESSION_YEARS)));
+    }
+    PredictiveOptions { natal, target }
+  }
+
+  fn age(&self) -> f64 {
+    (self.target.jd - self.natal.jd) / TROPICAL_YEAR_DAYS
+  }
+}
+
+/*
+* Primary directions only: method and rate
+*/
+struct DirectionOptions {
+  dates: PredictiveOptions,
+  method: DirectionMethod,
+  rate: DirectionRate,
+}
+
+impl DirectionOptions {
+  fn from_input(params: &InputOptions) -> Result<Self, CalcErrors> {
+    let mut errors = input_errors(params);
+    let dates = PredictiveOptions::check_dates(params, &mut errors);
+    let method_key = params.method.clone().unwrap_or("placidus".to_string());
+    let method = errors.check(DirectionMethod::from_key(method_key.as_str()).ok_or(CalcError::InvalidParam("method".to_string(), format!("{} is not one of placidus, regiomontanus", method_key)))).unwrap_or(DirectionMethod::Placidus);
+    let rate_key = params.rate.clone().unwrap_or("naibod".to_string());
+    let rate = errors.check(DirectionRate::from_key(rate_key.as_str()).ok_or(CalcError::InvalidParam("rate".to_string(), format!("{} is not one of naibod, ptolemy", rate_key)))).unwrap_or(DirectionRate::Naibod);
+    errors.into_result()?;
+    Ok(DirectionOptions { dates, method, rate })
+  }
+}
+
+/// Secondary (day-for-a-year) and tertiary (day-for-a-month) progressed positions and houses for the target date dt2.
+///
+/// Progressed angles and houses are based on the natal ARMC advanced by the solar arc in right ascension
+#[utoipa::path(
+  params(InputOptions),
+  responses(
+    (status = 200, description = "Success"),
+    (status = 400, description = "Invalid parameters", body = crate::errors::ErrorResponse),
+  ),
+)]
+#[get("/progressions")]
+async fn list_progressions(params: Query<InputOptions>) -> Result<impl Responder, CalcErrors> {
+  let options = PredictiveOptions::from_input(&params)?;
+  let result = run_ephemeris(move || {
+    let geo = to_geopos_object(&params)?;
+    let def_keys = vec!["su", "mo", "ma", "me", "ju", "ve", "sa", "ur", "ne", "pl"];
+    let key_string: String = params.bodies.clone().unwrap_or("".to_string());
+    let keys = body_keys_str_to_keys_or(key_string, def_keys);
+    let h_systems = match_house_systems_chars(params.hsys.clone().unwrap_or("W".to_string()));
+    let sidereal: bool = params.sid.unwrap_or(0) > 0;
+    let aya: String = params.aya.clone().unwrap_or("tropical".to_string());
+    let aya_key = match_ayanamsha_key(aya.as_str());
+    let ayanamsha = get_ayanamsha_value(options.natal.jd, aya.as_str());
+    let aya_offset = if sidereal { ayanamsha } else { 0f64 };
+    let natal_jd = options.natal.jd;
+    let target_jd = options.target.jd;
+    let secondary = calc_progressed_chart(natal_jd, target_jd, geo, &to_str_refs(&keys), ProgressionType::Secondary, h_systems.clone(), aya_offset);
+    let tertiary = calc_progressed_chart(natal_jd, target_jd, geo, &to_str_refs(&keys), ProgressionType::Tertiary, h_systems, aya_offset);
+    Ok(with_local_times(&params, json!({ "date": options.natal, "refDate": options.target, "geo": geo, "age": options.age(), "ayanamsha": { "key": aya_key, "value": ayanamsha, "applied": sidereal }, "secondary": secondary, "tertiary": tertiary })))
+  }).await?;
+  Ok(Json(result))
+}
+
+/// Solar arc directions and direct primary directions in mundo up to the target date dt2.
+///
+/// Primary directions are conjunctions of the bodies with the ascendant, MC and natal bodies, calculated
+/// with Placidus semi-arcs or Regiomontanus poles (method) and converted to years via the Naibod or Ptolemy rate
+#[utoipa::path(
+  params(InputOptions),
+  responses(
+    (status = 200, description = "Success"),
+    (status = 400, description = "Invalid parameters", body = crate::errors::ErrorResponse),
+  ),
+)]
+#[get("/directions")]
+async fn list_directions(params: Query<InputOptions>) -> Result<impl Responder, CalcErrors> {
+  let options = DirectionOptions::from_input(&params)?;
+  let result = run_ephemeris(move || {
+    let geo = to_geopos_object(&params)?;
+    let def_keys = vec!["su", "mo", "ma", "me", "ju", "ve", "sa", "ur", "ne", "pl"];
+    let key_string: String = params.bodies.clone().unwrap_or("".to_string());
+    let keys = body_keys_str_to_keys_or(key_string, def_keys);
+    let iso_mode: bool = params.iso.clone().unwrap_or(0) > 0;
+    let sidereal: bool = params.sid.unwrap_or(0) > 0;
+    let aya: String = params.aya.clone().unwrap_or("tropical".to_string());
+    let aya_key = match_ayanamsha_key(aya.as_str());
+    let ayanamsha = get_ayanamsha_value(options.dates.natal.jd, aya.as_str());
+    let aya_offset = if sidereal { ayanamsha } else { 0f64 };
+    let natal_jd = options.dates.natal.jd;
+    let target_jd = options.dates.target.jd;
+    let solar_arc = calc_solar_arc_chart(natal_jd, target_jd, geo, &to_str_refs(&keys), aya_offset);
+    let primary = calc_primary_directions(natal_jd, target_jd, geo, &to_str_refs(&keys), options.method, options.rate, iso_mode);
+    Ok(with_local_times(&params, json!({ "date": options.dates.natal, "refDate": options.dates.target, "geo": geo, "age": options.dates.age(), "ayanamsha": { "key": aya_key, "value": ayanamsha, "applied": sidereal }, "solarArc": solar_arc, "primary": { "method": options.method.to_key(), "rate": options.rate.to_key(), "directions": primary } })))
+  }).await?;
+  Ok(Json(result))
+}
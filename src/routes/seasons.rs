@@ -0,0 +1,54 @@
+use serde_json::{json, Value};
+use actix_web::{get, Responder, web::{Query, Json}};
+use crate::{query_params::*, ephemeris::run_ephemeris};
+use crate::calc::{
+  core::get_ayanamsha_value,
+  errors::CalcErrors,
+  models::date_info::DateInfo,
+  progressions::TROPICAL_YEAR_DAYS,
+  seasons::{calc_equinoxes_solstices, calc_sankrantis, MAX_SEASON_YEARS},
+  settings::ayanamshas::match_sidereal_ayanamsha_key,
+  transits::AyanamshaRange,
+};
+use super::chart_data::calc_chart_data_jd;
+
+/// Exact equinoxes and solstices and optionally sidereal solar ingresses (sankrantis) over a number of years.
+///
+/// With full=1 a chart with the same options as /chart-data is cast at each equinox and solstice for the given location
+#[utoipa::path(
+  params(InputOptions),
+  responses(
+    (status = 200, description = "Success"),
+    (status = 400, description = "Invalid parameters", body = crate::errors::ErrorResponse),
+  ),
+)]
+#[get("/seasons")]
+async fn list_seasons(params: Query<InputOptions>) -> Result<impl Responder, CalcErrors> {
+  let mut errors = input_errors(&params);
+  let num_years = errors.check(to_num_in_range("years", params.years, 1, 1, MAX_SEASON_YEARS)).unwrap_or(1);
+  errors.into_result()?;
+  let result = run_ephemeris(move || {
+    let date = to_date_object(&params)?;
+    let end_date = DateInfo::new_from_jd(date.jd + num_years as f64 * TROPICAL_YEAR_DAYS);
+    let iso_mode: bool = params.iso.clone().unwrap_or(0) > 0;
+    let show_charts: bool = params.full.unwrap_or(0) > 0;
+    let sidereal: bool = params.sid.unwrap_or(0) > 0;
+    let mut items: Vec<Value> = Vec::new();
+    for event in calc_equinoxes_solstices(date.jd, end_date.jd, iso_mode) {
+      let mut item = json!(event);
+      if show_charts {
+        item["chart"] = json!(calc_chart_data_jd(&params, Some(event.jd))?);
+      }
+      items.push(item);
+    }
+    let mut result = json!({ "start": date, "end": end_date, "items": items });
+    if sidereal {
+      let aya_key = match_sidereal_ayanamsha_key(params.aya.clone().unwrap_or("true_citra".to_string()).as_str());
+      let aya_range = AyanamshaRange::new(date.jd, get_ayanamsha_value(date.jd, aya_key.as_str()), end_date.jd, get_ayanamsha_value(end_date.jd, aya_key.as_str()));
+      result["ayanamsha"] = json!({ "key": aya_key, "value": aya_range.value(date.jd) });
+      result["sankrantis"] = json!(calc_sankrantis(date.jd, end_date.jd, &aya_range, iso_mode));
+    }
+    Ok(with_local_times(&params, result))
+  }).await?;
+  Ok(Json(result))
+}
@@ -0,0 +1,88 @@
+use serde_json::json;
+use actix_web::{get, Responder, web::{Query, Json}};
+use crate::{query_params::*, ephemeris::run_ephemeris};
+use crate::calc::{
+  core::{get_ayanamsha_value, get_bodies_ecl_geo, get_bodies_ecl_topo},
+  errors::CalcErrors,
+  models::general::TopoMode,
+  rise_set_phases::{TransitionGroup, TransitionMode},
+  settings::{ayanamshas::match_ayanamsha_key, stars::{all_fixed_stars, FixedStar}},
+  stars::{calc_star_conjunctions, calc_star_heliacal_events, calc_star_pos, calc_star_transition_set, resolve_star, StarInfo},
+  utils::converters::*,
+};
+
+/*
+* Typed /stars options. Star names outside the built-in catalogue are resolved by the Swiss Ephemeris,
+* so these must be read with the lock held
+*/
+struct StarOptions {
+  topo: TopoMode,
+  mode: TransitionMode,
+}
+
+impl StarOptions {
+  fn from_input(params: &InputOptions) -> Result<Self, CalcErrors> {
+    let mut errors = input_errors_with(params, &|k| is_valid_input_key(k) || resolve_star(k).is_some());
+    let topo = errors.check(to_topo_mode(params, false)).unwrap_or(TopoMode::Geocentric);
+    let mode = errors.check(to_transition_mode(params)).unwrap_or(TransitionMode::CenterOnly);
+    errors.into_result()?;
+    Ok(StarOptions { topo, mode })
+  }
+}
+
+/// Fixed star positions, magnitudes and conjunctions with planets, optionally with rise/set times and heliacal events.
+///
+/// Star names (keys, traditional names or aliases) and 2-letter body keys may be mixed in bodies.
+/// Body keys are only used for conjunctions. All catalogue stars are shown if no star is specified
+#[utoipa::path(
+  params(InputOptions),
+  responses(
+    (status = 200, description = "Success"),
+    (status = 400, description = "Invalid parameters", body = crate::errors::ErrorResponse),
+  ),
+)]
+#[get("/stars")]
+async fn list_stars(params: Query<InputOptions>) -> Result<impl Responder, CalcErrors> {
+  let result = run_ephemeris(move || {
+    let options = StarOptions::from_input(&params)?;
+    let mode = options.mode;
+    let date = to_date_object(&params)?;
+    let geo_opt = if params.loc.is_some() { Some(to_geopos_object(&params)?) } else { None };
+    let def_keys = vec!["su", "mo", "ma", "me", "ju", "ve", "sa", "ur", "ne", "pl"];
+    let key_string: String = params.bodies.clone().unwrap_or("".to_string());
+    let keys = body_keys_str_to_keys_or(key_string.clone(), def_keys);
+    let matched_stars: Vec<FixedStar> = key_string.split(",").filter(|k| k.len() > 2).filter_map(|k| resolve_star(k)).collect();
+    let stars = if matched_stars.len() > 0 { matched_stars } else { all_fixed_stars() };
+    let topo = options.topo == TopoMode::Topocentric;
+    let iso_mode: bool = params.iso.clone().unwrap_or(0) > 0;
+    let show_rise_sets: bool = params.ct.clone().unwrap_or(0) > 0;
+    let show_heliacal: bool = params.ph.clone().unwrap_or(0) > 0;
+    let aya: String = params.aya.clone().unwrap_or("tropical".to_string());
+    let sidereal: bool = params.sid.unwrap_or(0) > 0;
+    let aya_key = match_ayanamsha_key(aya.as_str());
+    let ayanamsha = get_ayanamsha_value(date.jd, aya.as_str());
+    let aya_offset = if sidereal { ayanamsha } else { 0f64 };
+    let bodies = match geo_opt {
+      Some(geo) => if topo { get_bodies_ecl_topo(date.jd, &to_str_refs(&keys), geo, aya_offset) } else { get_bodies_ecl_geo(date.jd, &to_str_refs(&keys), aya_offset) },
+      _ => get_bodies_ecl_geo(date.jd, &to_str_refs(&keys), aya_offset),
+    };
+    let mut items: Vec<StarInfo> = Vec::new();
+    // stars missing from the star file are skipped
+    for (star, position) in stars.iter().filter_map(|star| calc_star_pos(date.jd, star, topo, geo_opt, aya_offset).map(|pos| (star, pos))) {
+      let mut info = StarInfo::new(star, position);
+      info.conjunctions = calc_star_conjunctions(&info.position, &bodies, params.orb);
+      if let Some(geo) = geo_opt {
+        if show_rise_sets {
+          info.rise_sets = calc_star_transition_set(date.jd, star, geo, mode).to_key_nums().iter().map(|item| item.as_flexi_value(iso_mode)).collect();
+        }
+        if show_heliacal {
+          info.heliacal = calc_star_heliacal_events(date.jd, star, geo, iso_mode);
+        }
+      }
+      items.push(info);
+    }
+    let valid = items.len() > 0;
+    Ok(with_local_times(&params, json!({ "valid": valid, "date": date, "geo": geo_opt, "ayanamsha": { "key": aya_key, "value": ayanamsha, "applied": sidereal }, "items": items })))
+  }).await?;
+  Ok(Json(result))
+}
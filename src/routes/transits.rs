@@ -0,0 +1,91 @@
+use serde_json::json;
+use actix_web::{get, Responder, web::{Query, Json}};
+use crate::{query_params::*, ephemeris::run_ephemeris};
+use crate::calc::{
+  aspects::match_aspect_types,
+  core::get_ayanamsha_value,
+  errors::{CalcError, CalcErrors},
+  models::date_info::DateInfo,
+  settings::ayanamshas::match_ayanamsha_key,
+  transits::*,
+  utils::converters::*,
+};
+
+const DEFAULT_TRANSIT_DAYS: f64 = 366f64;
+const MAX_TRANSIT_DAYS: f64 = 36525f64;
+
+/*
+* Typed /transits options. dt2 defaults to one year after dt and must be later than dt and within MAX_TRANSIT_DAYS
+*/
+struct TransitOptions {
+  start: DateInfo,
+  end: DateInfo,
+}
+
+impl TransitOptions {
+  fn from_input(params: &InputOptions) -> Result<Self, CalcErrors> {
+    let mut errors = input_errors(params);
+    let start = to_date_object(params).unwrap_or(DateInfo::now());
+    let end = if params.has_date_2() { to_date_object_2(params).unwrap_or(start.clone()) } else { DateInfo::new_from_jd(start.jd + DEFAULT_TRANSIT_DAYS) };
+    if end.jd <= start.jd || end.jd - start.jd > MAX_TRANSIT_DAYS {
+      errors.add(CalcError::InvalidParam("dt2".to_string(), format!("the end date must be later than dt and within {} days", MAX_TRANSIT_DAYS)));
+    }
+    errors.into_result()?;
+    Ok(TransitOptions { start, end })
+  }
+}
+
+/// Exact times when a body reaches a longitude or an angle to another body or fixed point.
+///
+/// The first body key is the transiting body. Any other body keys are moving reference bodies.
+/// Without reference bodies, the transiting body is matched against a fixed longitude (lng)
+/// or, if lng is not set, the target longitude is the orb value.
+#[utoipa::path(
+  params(InputOptions),
+  responses(
+    (status = 200, description = "Success"),
+    (status = 400, description = "Invalid parameters", body = crate::errors::ErrorResponse),
+  ),
+)]
+#[get("/transits")]
+async fn list_transits(params: Query<InputOptions>) -> Result<impl Responder, CalcErrors> {
+  let options = TransitOptions::from_input(&params)?;
+  let result = run_ephemeris(move || {
+    let date = options.start;
+    let end_jd = options.end.jd;
+    let key_string: String = params.bodies.clone().unwrap_or("".to_string());
+    let keys = body_keys_str_to_keys_or(key_string, vec!["su"]);
+    let iso_mode: bool = params.iso.clone().unwrap_or(0) > 0;
+    let aya: String = params.aya.clone().unwrap_or("tropical".to_string());
+    let sidereal: bool = params.sid.unwrap_or(0) > 0;
+    let aya_key = match_ayanamsha_key(aya.as_str());
+    let aya_range = if sidereal {
+      AyanamshaRange::new(date.jd, get_ayanamsha_value(date.jd, aya.as_str()), end_jd, get_ayanamsha_value(end_jd, aya.as_str()))
+    } else {
+      AyanamshaRange::tropical()
+    };
+    let angles: Vec<f64> = match params.asp.clone() {
+      Some(asp_str) => match_aspect_types(asp_str.as_str()).iter().map(|a| a.angle()).collect(),
+      _ => vec![params.orb.unwrap_or(0f64)],
+    };
+    let mut items: Vec<TransitEvent> = Vec::new();
+    if let Some(key) = keys.first() {
+      let ref_keys: Vec<String> = keys.iter().skip(1).map(|k| k.to_owned()).collect();
+      if ref_keys.len() > 0 {
+        for ref_key in ref_keys {
+          let mut rows = calc_aspect_transits(key.as_str(), &TransitRef::Body(ref_key), &angles, date.jd, end_jd, &aya_range, iso_mode);
+          items.append(&mut rows);
+        }
+        items.sort_by(|a, b| a.jd.total_cmp(&b.jd));
+      } else if let Some(lng) = params.lng {
+        items = calc_aspect_transits(key.as_str(), &TransitRef::Point(lng), &angles, date.jd, end_jd, &aya_range, iso_mode);
+      } else {
+        items = calc_longitude_transits(key.as_str(), params.orb.unwrap_or(0f64), date.jd, end_jd, &aya_range, iso_mode);
+      }
+    }
+    let valid = keys.len() > 0;
+    let end = options.end;
+    Ok(with_local_times(&params, json!({ "valid": valid, "start": date, "end": end, "ayanamsha": { "key": aya_key, "value": aya_range.value(date.jd), "applied": sidereal }, "items": items })))
+  }).await?;
+  Ok(Json(result))
+}
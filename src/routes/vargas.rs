@@ -0,0 +1,57 @@
+use serde_json::json;
+use actix_web::{get, Responder, web::{Query, Json}};
+use crate::{query_params::*, ephemeris::run_ephemeris};
+use crate::calc::{
+  core::get_ayanamsha_value,
+  errors::CalcErrors,
+  models::general::TopoMode,
+  settings::{ayanamshas::match_sidereal_ayanamsha_key, varga_values::match_varga_nums},
+  utils::converters::*,
+  vargas::calc_body_vargas,
+};
+
+/*
+* Typed /vargas options
+*/
+struct VargaOptions {
+  topo: TopoMode,
+}
+
+impl VargaOptions {
+  fn from_input(params: &InputOptions) -> Result<Self, CalcErrors> {
+    let mut errors = input_errors(params);
+    let topo = errors.check(to_topo_mode(params, false)).unwrap_or(TopoMode::Geocentric);
+    errors.into_result()?;
+    Ok(VargaOptions { topo })
+  }
+}
+
+/// Sign placements of the sidereal ascendant and bodies in divisional charts (D1 to D60).
+///
+/// Sign placements of the ascendant and bodies in each divisional chart. The ayanamsha defaults to True Citra
+#[utoipa::path(
+  params(InputOptions),
+  responses(
+    (status = 200, description = "Success"),
+    (status = 400, description = "Invalid parameters", body = crate::errors::ErrorResponse),
+  ),
+)]
+#[get("/vargas")]
+async fn list_vargas(params: Query<InputOptions>) -> Result<impl Responder, CalcErrors> {
+  let options = VargaOptions::from_input(&params)?;
+  let result = run_ephemeris(move || {
+    let date = to_date_object(&params)?;
+    let geo = to_geopos_object(&params)?;
+    let def_keys = vec!["su", "mo", "ma", "me", "ju", "ve", "sa", "ra", "ke"];
+    let key_string: String = params.bodies.clone().unwrap_or("".to_string());
+    let keys = body_keys_str_to_keys_or(key_string, def_keys);
+    let topo = options.topo == TopoMode::Topocentric;
+    let nums = match_varga_nums(params.vargas.clone().unwrap_or("all".to_string()).as_str());
+    let aya_key = match_sidereal_ayanamsha_key(params.aya.clone().unwrap_or("true_citra".to_string()).as_str());
+    let ayanamsha = get_ayanamsha_value(date.jd, aya_key.as_str());
+    let vargas = calc_body_vargas(date.jd, &to_str_refs(&keys), geo, topo, ayanamsha, &nums);
+    let valid = vargas.len() > 0;
+    Ok(with_local_times(&params, json!({ "valid": valid, "date": date, "geo": geo, "ayanamsha": { "key": aya_key, "value": ayanamsha }, "vargas": vargas })))
+  }).await?;
+  Ok(Json(result))
+}